@@ -1,71 +1,490 @@
-use std::time::Instant;
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use colored::Colorize;
+use lumi_fmt::formatter::Formatter;
 use lumi_lxr::lexer::Lexer;
 use lumi_lxr::source_code::SourceCode;
+use lumi_lxr::token_stream::TokenStream;
 use lumi_psr::ast::Ast;
+use lumi_psr::display_tree::DisplayTree;
 use lumi_psr::parser::ParseStream;
+use lumi_psr::stmts::fun::FunStmt;
+use lumi_psr::stmts::Stmt;
+use lumi_tc::type_checker::TypeChecker;
 use lumi_vm::chunk::Chunk;
 use lumi_vm::emitter::BytecodeEmitter;
+use lumi_vm::limits::VmLimits;
+use lumi_vm::permissions::{Grant, Permissions};
 use lumi_vm::vm::Vm;
 
 #[derive(Parser, Debug)]
 #[command()]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Runs the given file, same as `run <FILE>`.
     #[arg(short, long)]
-    file: String,
-    #[arg(short, long, default_value_t = true)]
+    file: Option<String>,
+    /// The file to run, unless given with `--file`, followed by the
+    /// arguments given to it, so that scripts can start with a shebang.
+    #[arg(
+        value_name = "[FILE] ARGS",
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    args: Vec<String>,
+    /// Checks types before running, `--type-check false` skips it.
+    #[arg(short, long, global = true, default_value_t = true, action = ArgAction::Set)]
     type_check: bool,
+    /// Stops the script after executing this many instructions.
+    #[arg(long, global = true)]
+    max_steps: Option<usize>,
+    /// Stops the script after running for this many milliseconds.
+    #[arg(long, global = true)]
+    timeout_ms: Option<u64>,
+    /// Stops the script when it has this many objects in memory.
+    #[arg(long, global = true)]
+    max_heap: Option<usize>,
+    /// Allows reading files, or only the given comma-separated paths.
+    #[arg(long, global = true, value_name = "PATHS", num_args = 0.., value_delimiter = ',', require_equals = true)]
+    allow_read: Option<Vec<String>>,
+    /// Allows writing files, or only the given comma-separated paths.
+    #[arg(long, global = true, value_name = "PATHS", num_args = 0.., value_delimiter = ',', require_equals = true)]
+    allow_write: Option<Vec<String>>,
+    /// Allows reading environment variables, or only the given ones.
+    #[arg(long, global = true, value_name = "NAMES", num_args = 0.., value_delimiter = ',', require_equals = true)]
+    allow_env: Option<Vec<String>>,
+    /// Allows everything, same as passing every `--allow-*` flag.
+    #[arg(short = 'A', long, global = true)]
+    allow_all: bool,
 }
 
-fn main() {
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compiles and runs a file.
+    Run {
+        file: String,
+        /// The arguments given to the file, after `--`.
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Runs the functions named `test_*` of the given files, each one from
+    /// the state the top level of its file left, which runs once.
+    Test {
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Only runs the tests whose name contains this text.
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Formats files in place.
+    Fmt {
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Only checks whether the files are formatted, exiting with an
+        /// error when any of them is not.
+        #[arg(long)]
+        check: bool,
+    },
+    /// Prints the tokens of a file.
+    Tokens {
+        file: String,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Tree)]
+        format: OutputFormat,
+    },
+    /// Prints the syntax tree of a file.
+    Ast {
+        file: String,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Tree)]
+        format: OutputFormat,
+    },
+    /// Starts a language server over stdin and stdout.
+    Lsp,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    /// Human readable output.
+    Tree,
+    /// JSON output, including the spans of every node.
+    Json,
+}
+
+fn main() -> ExitCode {
     let args = Args::parse();
+    let limits = VmLimits {
+        max_steps: args.max_steps,
+        timeout: args.timeout_ms.map(Duration::from_millis),
+        max_heap: args.max_heap,
+    };
+    let permissions = if args.allow_all {
+        Permissions::all()
+    } else {
+        Permissions {
+            read: grant(args.allow_read),
+            write: grant(args.allow_write),
+            env: grant(args.allow_env),
+        }
+    };
+    match (args.command, args.file) {
+        (
+            Some(Command::Run {
+                file,
+                args: script_args,
+            }),
+            _,
+        ) => run(&file, script_args, args.type_check, limits, permissions),
+        (None, Some(file)) => run(&file, args.args, args.type_check, limits, permissions),
+        (None, None) if !args.args.is_empty() => {
+            let (file, script_args) = args.args.split_first().unwrap();
+            run(
+                file,
+                script_args.to_vec(),
+                args.type_check,
+                limits,
+                permissions,
+            )
+        }
+        (Some(Command::Test { files, filter }), _) => test(
+            &files,
+            filter.as_deref(),
+            args.type_check,
+            limits,
+            permissions,
+        ),
+        (Some(Command::Fmt { files, check }), _) => fmt(&files, check),
+        (Some(Command::Tokens { file, format }), _) => tokens(&file, format),
+        (Some(Command::Ast { file, format }), _) => ast(&file, format),
+        (Some(Command::Lsp), _) => lsp(),
+        (None, None) => {
+            eprintln!("Expected a file to run, see --help");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Turns the values of an `--allow-*` flag into what it grants: nothing
+/// when it's missing and everything when it has no values.
+fn grant(values: Option<Vec<String>>) -> Grant {
+    match values {
+        None => Grant::Denied,
+        Some(values) if values.is_empty() => Grant::All,
+        Some(values) => Grant::Only(values),
+    }
+}
+
+fn run(
+    file: &str,
+    args: Vec<String>,
+    type_check: bool,
+    limits: VmLimits,
+    permissions: Permissions,
+) -> ExitCode {
     let core_source_code = SourceCode::from_file("core.ls").unwrap();
     let std_source_code = SourceCode::from_file("std.ls").unwrap();
-    let source_code = SourceCode::from_file(&args.file).unwrap();
+    let source_code = SourceCode::from_file(file).unwrap();
 
     let start_compilation_time = Instant::now();
 
     let mut chunk = Chunk::new();
+    let mut type_checker = type_check.then(TypeChecker::new);
+
+    let result = [core_source_code, std_source_code, source_code]
+        .iter()
+        .all(|code| compile(code.clone(), &mut chunk, type_checker.as_mut()).is_ok());
+
+    if !result {
+        return ExitCode::FAILURE;
+    }
 
-    let result = vec![core_source_code, std_source_code, source_code]
+    println!(
+        "Compilation time: {} milliseconds\n",
+        (Instant::now() - start_compilation_time).as_millis()
+    );
+
+    let mut vm = Vm::new(chunk)
+        .with_limits(limits)
+        .with_permissions(permissions)
+        .with_args(args);
+    let start_execution_time = Instant::now();
+
+    let code = match vm.run() {
+        // Like on Unix, only the lowest 8 bits of the code are kept.
+        Ok(_) => ExitCode::from(vm.exit_code().unwrap_or(0) as u8),
+        Err(runtime_error) => {
+            eprintln!("{}", runtime_error);
+            ExitCode::FAILURE
+        }
+    };
+    println!(
+        "\nExecution time: {} milliseconds",
+        (Instant::now() - start_execution_time).as_millis()
+    );
+    code
+}
+
+fn test(
+    files: &[String],
+    filter: Option<&str>,
+    type_check: bool,
+    limits: VmLimits,
+    permissions: Permissions,
+) -> ExitCode {
+    let core_source_code = SourceCode::from_file("core.ls").unwrap();
+    let std_source_code = SourceCode::from_file("std.ls").unwrap();
+
+    let (mut passed, mut filtered_out) = (0, 0);
+    let mut failures = vec![];
+    let mut compiled = true;
+    for file in files {
+        let source_code = SourceCode::from_file(file).unwrap();
+        let Ok((ast, _)) = parse(source_code.clone()) else {
+            compiled = false;
+            continue;
+        };
+        let mut chunk = Chunk::new();
+        let mut type_checker = type_check.then(TypeChecker::new);
+        let result = [
+            core_source_code.clone(),
+            std_source_code.clone(),
+            source_code,
+        ]
         .iter()
-        .all(|code| compile(code.clone(), &mut chunk).is_ok());
-
-    if result {
-        println!(
-            "{}: {} milliseconds\n",
-            "Compilation time",
-            (Instant::now() - start_compilation_time).as_millis()
-        );
-
-        let mut vm = Vm::new(chunk);
-        let start_execution_time = Instant::now();
-
-        match vm.run() {
-            Ok(_) => {}
-            Err(runtime_error) => {
-                eprintln!("{}", runtime_error);
+        .all(|code| compile(code.clone(), &mut chunk, type_checker.as_mut()).is_ok());
+        if !result {
+            compiled = false;
+            continue;
+        }
+        let tests = ast.stmts().iter().filter_map(|stmt| match stmt {
+            Stmt::Fun(fun @ FunStmt::Default { .. }) if fun.params().is_empty() => {
+                Some(fun.ident().symbol().as_str())
+            }
+            _ => None,
+        });
+        let mut selected = vec![];
+        for name in tests.filter(|name| name.starts_with("test_")) {
+            let test_name = format!("{}::{}", file, name);
+            if filter.is_some_and(|filter| !test_name.contains(filter)) {
+                filtered_out += 1;
+            } else {
+                selected.push((name, test_name));
+            }
+        }
+        if selected.is_empty() {
+            continue;
+        }
+        // The top level of the file runs once, then every test is called
+        // from the state it left, so the tests can't see each other's
+        // changes.
+        let mut vm = Vm::new(chunk)
+            .with_limits(limits)
+            .with_permissions(permissions.clone());
+        if let Some(failure) = failure(&mut vm) {
+            println!("test {} ... {}", file, "FAILED".red());
+            failures.push((file.clone(), failure));
+            continue;
+        }
+        let snapshot = vm.snapshot();
+        for (name, test_name) in selected {
+            vm.restore(&snapshot);
+            let call = SourceCode::new(&test_name, &format!("{}();", name));
+            if compile(call, vm.chunk_mut(), None).is_err() {
+                compiled = false;
+                continue;
+            }
+            match failure(&mut vm) {
+                None => {
+                    println!("test {} ... {}", test_name, "ok".green());
+                    passed += 1;
+                }
+                Some(failure) => {
+                    println!("test {} ... {}", test_name, "FAILED".red());
+                    failures.push((test_name, failure));
+                }
             }
         }
-        println!(
-            "\n{}: {} milliseconds",
-            "Execution time",
-            (Instant::now() - start_execution_time).as_millis()
-        );
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (test_name, failure) in &failures {
+            println!("\n---- {} ----\n{}", test_name, failure);
+        }
+    }
+    let ok = compiled && failures.is_empty();
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} filtered out",
+        if ok { "ok".green() } else { "FAILED".red() },
+        passed,
+        failures.len(),
+        filtered_out
+    );
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn fmt(files: &[String], check: bool) -> ExitCode {
+    let mut code = ExitCode::SUCCESS;
+    for file in files {
+        let source_code = SourceCode::from_file(file).unwrap();
+        let Ok((ast, tokens)) = parse(source_code.clone()) else {
+            code = ExitCode::FAILURE;
+            continue;
+        };
+        let formatted = Formatter::format(&ast, &tokens);
+        if &formatted == source_code.code() {
+            continue;
+        }
+        if check {
+            println!("Not formatted: {}", file);
+            code = ExitCode::FAILURE;
+        } else {
+            std::fs::write(file, formatted).unwrap();
+        }
+    }
+    code
+}
+
+fn tokens(file: &str, format: OutputFormat) -> ExitCode {
+    let source_code = SourceCode::from_file(file).unwrap();
+    let (tokens, errors) = Lexer::new(source_code).tokens_lossy();
+    // Each error is listed right before the first token starting at or after it.
+    let mut entries = Vec::with_capacity(tokens.len() + errors.len());
+    let mut errors_iter = errors.iter().peekable();
+    for token in &tokens {
+        while let Some(error) = errors_iter
+            .next_if(|error| error.span().start().index() <= token.span().start().index())
+        {
+            entries.push(Err(error));
+        }
+        entries.push(Ok(token));
+    }
+    entries.extend(errors_iter.map(Err));
+    let mut stdout = io::stdout().lock();
+    let result = match format {
+        OutputFormat::Tree => entries.iter().try_for_each(|entry| {
+            let (kind, text, span) = match entry {
+                Ok(token) => (
+                    format!("{:?}", token.kind()),
+                    token.source_text(),
+                    token.span(),
+                ),
+                Err(error) => ("Error".to_owned(), error.message().to_owned(), error.span()),
+            };
+            let (start, end) = (span.start(), span.end());
+            writeln!(
+                stdout,
+                "{} {:?} {}:{}..{}:{}",
+                kind,
+                text,
+                start.line(),
+                start.column(),
+                end.line(),
+                end.column()
+            )
+        }),
+        OutputFormat::Json => entries
+            .iter()
+            .map(|entry| match entry {
+                Ok(token) => serde_json::to_value(token),
+                Err(error) => serde_json::to_value(error),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|entries| serde_json::to_writer_pretty(&mut stdout, &entries))
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(stdout)),
+    };
+    let code = exit_code(result);
+    if errors.is_empty() {
+        code
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn ast(file: &str, format: OutputFormat) -> ExitCode {
+    let source_code = SourceCode::from_file(file).unwrap();
+    let Ok((ast, _)) = parse(source_code) else {
+        return ExitCode::FAILURE;
+    };
+    let mut stdout = io::stdout().lock();
+    let result = match format {
+        OutputFormat::Tree => ast.display(&mut stdout, 0),
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut stdout, &ast)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(stdout)),
+    };
+    exit_code(result)
+}
+
+fn lsp() -> ExitCode {
+    match lumi_lsp::server::run() {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn exit_code(result: io::Result<()>) -> ExitCode {
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        // The output was piped into a command that stopped reading early.
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the code appended to the chunk of the VM, returning why it failed,
+/// if it did.
+fn failure(vm: &mut Vm) -> Option<String> {
+    match vm.run() {
+        Ok(_) => match vm.exit_code() {
+            None | Some(0) => None,
+            Some(code) => Some(format!("exited with code {}", code)),
+        },
+        Err(runtime_error) => Some(runtime_error.to_string()),
     }
 }
 
-fn compile(source_code: SourceCode, chunk: &mut Chunk) -> Result<(), ()> {
+fn compile(
+    source_code: SourceCode,
+    chunk: &mut Chunk,
+    type_checker: Option<&mut TypeChecker>,
+) -> Result<(), ()> {
+    let (ast, _) = parse(source_code)?;
+    if let Some(type_checker) = type_checker {
+        if let Err(errors) = type_checker.check(&ast) {
+            for error in errors {
+                eprintln!("{}", error);
+            }
+            return Err(());
+        }
+    }
+    BytecodeEmitter::emit(&ast, chunk).map_err(|errors| {
+        for error in errors {
+            eprintln!("{}", error);
+        }
+    })
+}
+
+fn parse(source_code: SourceCode) -> Result<(Ast, TokenStream), ()> {
     let mut lexer = Lexer::new(source_code);
     match lexer.tokens() {
         Ok(tokens) => {
-            let mut parse_stream = ParseStream::new(tokens);
+            let mut parse_stream = ParseStream::new(tokens.clone());
             match parse_stream.parse::<Ast>() {
-                Ok(ast) => {
-                    BytecodeEmitter::emit(&ast, chunk);
-                    Ok(())
-                }
+                Ok(ast) => Ok((ast, tokens)),
                 Err(error) => {
                     eprintln!("{}", error);
                     Err(())
@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Output};
+
+/// The root of the workspace, holding `core.ls` and `std.ls`, which the
+/// binary reads from its working directory.
+fn workspace() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// A directory of the system's temporary directory, removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("lumi-cli-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn write(&self, name: &str, code: &str) -> PathBuf {
+        let path = self.0.join(name);
+        fs::write(&path, code).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cli"))
+        .args(args)
+        .current_dir(workspace())
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+const PRINT_ARGS: &str = r#"#!/usr/bin/env lumi
+let args = args();
+println args.len();
+println args.get(0);
+println args.get(1);
+"#;
+
+#[test]
+fn script_path_followed_by_its_arguments() {
+    let dir = TempDir::new("positional");
+    let script = dir.write("args.ls", PRINT_ARGS);
+    let output = cli(&[script.to_str().unwrap(), "first", "--second"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("2.0\nfirst\n--second\n"));
+}
+
+#[test]
+fn run_command_takes_arguments_after_a_separator() {
+    let dir = TempDir::new("run");
+    let script = dir.write("args.ls", PRINT_ARGS);
+    let output = cli(&["run", script.to_str().unwrap(), "--", "first", "second"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("2.0\nfirst\nsecond\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn shebang() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let dir = TempDir::new("shebang");
+    symlink(env!("CARGO_BIN_EXE_cli"), dir.0.join("lumi")).unwrap();
+    let script = dir.write("args.ls", PRINT_ARGS);
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let mut paths = vec![dir.0.clone()];
+    paths.extend(std::env::split_paths(&path));
+    let output = Command::new(&script)
+        .args(["first", "second"])
+        .env("PATH", std::env::join_paths(paths).unwrap())
+        .current_dir(workspace())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("2.0\nfirst\nsecond\n"));
+}
+
+#[test]
+fn tests_are_isolated_from_each_other() {
+    let dir = TempDir::new("isolation");
+    let file = dir.write(
+        "counter.ls",
+        r#"println("top level");
+
+class Counter {
+    count: Num,
+}
+
+let total = 0;
+let counter = Counter { count: 0 };
+
+fun bump() {
+    total = total + 1;
+    counter.count = counter.count + 1;
+}
+
+fun test_one() {
+    bump();
+    assertEq(total, 1);
+    assertEq(counter.count, 1);
+}
+
+fun test_fails() {
+    bump();
+    assert(false);
+}
+
+fun test_two() {
+    bump();
+    assertEq(total, 1);
+    assertEq(counter.count, 1);
+}
+"#,
+    );
+    let file = file.to_str().unwrap();
+    let output = cli(&["test", file]);
+    let report = stdout(&output);
+    assert!(!output.status.success());
+    assert_eq!(report.matches("top level").count(), 1);
+    assert!(report.contains(&format!("test {}::test_one ... ok", file)));
+    assert!(report.contains(&format!("test {}::test_two ... ok", file)));
+    assert!(report.contains("2 passed; 1 failed; 0 filtered out"));
+
+    let output = cli(&["test", file, "--filter", "two"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("1 passed; 0 failed; 2 filtered out"));
+}
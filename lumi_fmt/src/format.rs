@@ -0,0 +1,590 @@
+use lumi_lxr::span::Span;
+use lumi_psr::ast::Ast;
+use lumi_psr::exprs::class::Field as FieldExpr;
+use lumi_psr::exprs::Expr;
+use lumi_psr::generics::Generics;
+use lumi_psr::param::Param;
+use lumi_psr::stmts::block::BlockStmt;
+use lumi_psr::stmts::class::Field;
+use lumi_psr::stmts::fun::FunStmt;
+use lumi_psr::stmts::r#impl::Method;
+use lumi_psr::stmts::r#trait::ProtoMethod;
+use lumi_psr::stmts::Stmt;
+use lumi_psr::symbols::Ident;
+use lumi_psr::ty::Type;
+
+use crate::formatter::{Cursor, Formatter};
+
+pub(crate) trait Format {
+    fn format(&self, f: &mut Formatter);
+}
+
+impl Format for Ast {
+    fn format(&self, f: &mut Formatter) {
+        let mut cursor = Cursor::new(0);
+        for stmt in self.stmts() {
+            f.item(stmt.span(), &mut cursor, |f| stmt.format(f));
+        }
+        f.remaining_comments(&mut cursor);
+    }
+}
+
+impl Format for BlockStmt {
+    fn format(&self, f: &mut Formatter) {
+        if self.stmts().is_empty() && !f.has_comments_before(self.span().end().index()) {
+            f.write("{}");
+        } else {
+            f.write("{");
+            f.items(
+                self.stmts(),
+                &mut Cursor::new(self.span().start().line()),
+                self.span().end().index(),
+                Stmt::span,
+                |f, stmt| stmt.format(f),
+            );
+            f.write("}");
+        }
+    }
+}
+
+impl Format for Stmt {
+    fn format(&self, f: &mut Formatter) {
+        match self {
+            Self::Println(println) => {
+                f.write("println ");
+                f.expr(println.expr(), 1);
+                f.write(";");
+            }
+            Self::Block(block) => block.format(f),
+            Self::Let(r#let) => {
+                f.write("let ");
+                f.write(r#let.ident().span().as_str());
+                if let Some(ty) = r#let.ty() {
+                    f.write(": ");
+                    f.write(&ty_text(ty));
+                }
+                f.write(" = ");
+                f.expr(r#let.expr(), 1);
+                f.write(";");
+            }
+            Self::Const(r#const) => {
+                f.write("const ");
+                f.write(r#const.ident().span().as_str());
+                if let Some(ty) = r#const.ty() {
+                    f.write(": ");
+                    f.write(&ty_text(ty));
+                }
+                f.write(" = ");
+                f.expr(r#const.expr(), 1);
+                f.write(";");
+            }
+            Self::If(r#if) => {
+                f.write("if ");
+                f.expr(r#if.cond(), 2);
+                f.write(" ");
+                r#if.block().format(f);
+            }
+            Self::While(r#while) => {
+                f.write("while ");
+                f.expr(r#while.cond(), 2);
+                f.write(" ");
+                r#while.block().format(f);
+            }
+            Self::For(r#for) => {
+                f.write("for ");
+                f.write(r#for.ident().span().as_str());
+                f.write(" in ");
+                f.expr(r#for.iter(), 2);
+                f.write(" ");
+                r#for.block().format(f);
+            }
+            Self::Class(class) => {
+                f.write("class ");
+                f.write(class.ident().span().as_str());
+                f.write(&generics(class.generics()));
+                f.write(" ");
+                f.braced(class.span(), class.fields(), Field::span, |f, field| {
+                    field.format(f)
+                });
+            }
+            Self::Trait(r#trait) => {
+                f.write("trait ");
+                f.write(r#trait.ident().span().as_str());
+                f.write(&generics(r#trait.generics()));
+                for (index, supertrait) in r#trait.supertraits().iter().enumerate() {
+                    f.write(if index == 0 { ": " } else { " + " });
+                    f.write(supertrait.span().as_str());
+                }
+                f.write(" ");
+                f.braced(
+                    r#trait.span(),
+                    r#trait.protos(),
+                    ProtoMethod::span,
+                    |f, proto| proto.format(f),
+                );
+            }
+            Self::Impl(r#impl) => {
+                f.write("impl");
+                f.write(&generics(r#impl.generics()));
+                f.write(" ");
+                if let Some(tr) = r#impl.tr() {
+                    f.write(&ty_text(tr));
+                    f.write(" for ");
+                }
+                f.write(&ty_text(r#impl.ty()));
+                f.write(" ");
+                f.braced(
+                    r#impl.span(),
+                    r#impl.methods(),
+                    Method::span,
+                    |f, method| method.format(f),
+                );
+            }
+            Self::Expr(expr) => {
+                f.expr(expr.expr(), 1);
+                f.write(";");
+            }
+            Self::Fun(fun) => fun.format(f),
+            Self::Continue(_) => f.write("continue;"),
+            Self::Break(_) => f.write("break;"),
+            Self::Return(r#return) => match r#return.expr() {
+                Some(expr) if r#return.is_implicit() => f.expr(expr, 0),
+                Some(expr) => {
+                    f.write("return ");
+                    f.expr(expr, 1);
+                    f.write(";");
+                }
+                None => f.write("return;"),
+            },
+            Self::Throw(throw) => {
+                f.write("throw ");
+                f.expr(throw.expr(), 1);
+                f.write(";");
+            }
+            Self::Try(r#try) => {
+                f.write("try ");
+                r#try.block().format(f);
+                if let Some(catch) = r#try.catch() {
+                    f.write(" catch (");
+                    f.write(catch.ident().span().as_str());
+                    f.write(") ");
+                    catch.block().format(f);
+                }
+                if let Some(finally) = r#try.finally() {
+                    f.write(" finally ");
+                    finally.format(f);
+                }
+            }
+        }
+    }
+}
+
+impl Format for Field {
+    fn format(&self, f: &mut Formatter) {
+        f.write(self.ident().span().as_str());
+        f.write(": ");
+        f.write(&ty_text(self.ty()));
+        f.write(",");
+    }
+}
+
+impl Format for ProtoMethod {
+    fn format(&self, f: &mut Formatter) {
+        match self.block() {
+            Some(block) => {
+                f.signature(
+                    "fun ",
+                    self.ident(),
+                    None,
+                    self.params(),
+                    self.return_ty(),
+                    2,
+                );
+                f.write(" ");
+                block.format(f);
+            }
+            None => {
+                f.signature(
+                    "fun ",
+                    self.ident(),
+                    None,
+                    self.params(),
+                    self.return_ty(),
+                    1,
+                );
+                f.write(";");
+            }
+        }
+    }
+}
+
+impl Format for FunStmt {
+    fn format(&self, f: &mut Formatter) {
+        match self {
+            Self::Default {
+                ident,
+                generics,
+                params,
+                return_ty,
+                block,
+                ..
+            } => {
+                f.signature("fun ", ident, Some(generics), params, return_ty.as_ref(), 2);
+                f.write(" ");
+                block.format(f);
+            }
+            Self::Extern {
+                ident,
+                generics,
+                params,
+                return_ty,
+                ..
+            } => {
+                f.signature(
+                    "extern fun ",
+                    ident,
+                    Some(generics),
+                    params,
+                    return_ty.as_ref(),
+                    1,
+                );
+                f.write(";");
+            }
+        }
+    }
+}
+
+impl Format for Method {
+    fn format(&self, f: &mut Formatter) {
+        match self {
+            Self::Default {
+                is_static,
+                ident,
+                params,
+                return_ty,
+                block,
+                ..
+            } => {
+                let keyword = if *is_static { "static fun " } else { "fun " };
+                f.signature(keyword, ident, None, params, return_ty.as_ref(), 2);
+                f.write(" ");
+                block.format(f);
+            }
+            Self::Extern {
+                is_static,
+                ident,
+                params,
+                return_ty,
+                ..
+            } => {
+                let keyword = if *is_static {
+                    "extern static fun "
+                } else {
+                    "extern fun "
+                };
+                f.signature(keyword, ident, None, params, return_ty.as_ref(), 1);
+                f.write(";");
+            }
+        }
+    }
+}
+
+impl Formatter {
+    /// Writes the items between braces, one per line, or `{}` when there
+    /// is nothing inside them.
+    fn braced<T>(
+        &mut self,
+        span: &Span,
+        items: &[T],
+        item_span: impl Fn(&T) -> &Span,
+        write: impl FnMut(&mut Self, &T),
+    ) {
+        if items.is_empty() && !self.has_comments_before(span.end().index()) {
+            self.write("{}");
+        } else {
+            self.write("{");
+            self.items(
+                items,
+                &mut Cursor::new(span.start().line()),
+                span.end().index(),
+                item_span,
+                write,
+            );
+            self.write("}");
+        }
+    }
+
+    /// Writes a function signature, moving each parameter to its own line
+    /// when it does not fit in one.
+    fn signature(
+        &mut self,
+        keyword: &str,
+        ident: &Ident,
+        generics: Option<&Generics>,
+        params: &[Param],
+        return_ty: Option<&Type>,
+        trailing: usize,
+    ) {
+        self.write(keyword);
+        self.write(ident.span().as_str());
+        if let Some(generics) = generics {
+            self.write(&self::generics(generics));
+        }
+        let return_ty = return_ty
+            .map(|ty| format!(" -> {}", ty_text(ty)))
+            .unwrap_or_default();
+        let flat = format!(
+            "({}){}",
+            params.iter().map(param).collect::<Vec<_>>().join(", "),
+            return_ty
+        );
+        if params.is_empty() || self.fits(flat.chars().count() + trailing) {
+            self.write(&flat);
+        } else {
+            self.write("(");
+            self.items(
+                params,
+                &mut Cursor::new(ident.span().end().line()),
+                params.last().unwrap().span().end().index(),
+                Param::span,
+                |f, p| {
+                    f.write(&param(p));
+                    f.write(",");
+                },
+            );
+            self.write(")");
+            self.write(&return_ty);
+        }
+    }
+
+    /// Writes the expression in a single line when it fits, leaving room
+    /// for `trailing` characters after it, and wraps it otherwise.
+    pub(crate) fn expr(&mut self, expr: &Expr, trailing: usize) {
+        match flat(expr) {
+            Some(text) if self.fits(text.chars().count() + trailing) => self.write(&text),
+            _ => self.wrapped(expr, trailing),
+        }
+    }
+
+    fn wrapped(&mut self, expr: &Expr, trailing: usize) {
+        match expr {
+            Expr::Call(call) if call.args().is_empty() => {
+                self.expr(call.callee(), trailing + 2);
+                self.write("()");
+            }
+            Expr::Call(call) => {
+                self.expr(call.callee(), 1);
+                self.write("(");
+                self.items(
+                    call.args(),
+                    &mut Cursor::new(call.callee().span().end().line()),
+                    call.span().end().index(),
+                    Expr::span,
+                    |f, arg| {
+                        f.expr(arg, 1);
+                        f.write(",");
+                    },
+                );
+                self.write(")");
+            }
+            Expr::Class(class) if class.fields().is_empty() => {
+                self.expr(class.cls(), trailing + 3);
+                self.write(" {}");
+            }
+            Expr::Class(class) => {
+                self.expr(class.cls(), 2);
+                self.write(" {");
+                self.items(
+                    class.fields(),
+                    &mut Cursor::new(class.cls().span().end().line()),
+                    class.span().end().index(),
+                    FieldExpr::span,
+                    |f, field| {
+                        f.write(field.ident().span().as_str());
+                        if let Some(value) = field.value() {
+                            f.write(": ");
+                            f.expr(value, 1);
+                        }
+                        f.write(",");
+                    },
+                );
+                self.write("}");
+            }
+            Expr::Binary(binary) if binary.op().span().as_str() == "=" => {
+                self.expr(binary.left(), 2);
+                self.write(" = ");
+                self.expr(binary.right(), trailing);
+            }
+            Expr::Binary(binary) => {
+                // `a + b - c` is parsed as `(a + b) - c`, so the operands of
+                // the same precedence are collected from the left side.
+                let level = precedence(binary.op().span().as_str());
+                let mut rest = vec![binary];
+                let mut first = binary.left();
+                while let Expr::Binary(left) = first {
+                    if precedence(left.op().span().as_str()) != level {
+                        break;
+                    }
+                    rest.push(left);
+                    first = left.left();
+                }
+                self.expr(first, 0);
+                self.indent();
+                for (i, binary) in rest.iter().rev().enumerate() {
+                    self.newline();
+                    self.write(binary.op().span().as_str());
+                    self.write(" ");
+                    let last = i == rest.len() - 1;
+                    self.expr(binary.right(), if last { trailing } else { 0 });
+                }
+                self.dedent();
+            }
+            Expr::Get(get) => {
+                let dot = if get.is_safe() { "?." } else { "." };
+                self.expr(
+                    get.expr(),
+                    get.ident().span().as_str().len() + dot.len() + trailing,
+                );
+                self.write(dot);
+                self.write(get.ident().span().as_str());
+            }
+            Expr::Paren(paren) => {
+                self.write("(");
+                self.expr(paren.expr(), trailing + 1);
+                self.write(")");
+            }
+            Expr::Unary(unary) => {
+                self.write(unary.op().span().as_str());
+                self.expr(unary.expr(), trailing);
+            }
+            Expr::Unwrap(unwrap) => {
+                self.expr(unwrap.expr(), trailing + 2);
+                self.write("!!");
+            }
+            Expr::Try(r#try) => {
+                self.expr(r#try.expr(), trailing + 1);
+                self.write("?");
+            }
+            _ => self.write(&flat(expr).unwrap()),
+        }
+    }
+}
+
+fn precedence(op: &str) -> usize {
+    match op {
+        "=" => 0,
+        "??" => 1,
+        "||" => 2,
+        "&&" => 3,
+        "==" | "!=" => 4,
+        ">" | ">=" | "<" | "<=" => 5,
+        ".." | "..=" => 6,
+        "+" | "-" => 7,
+        _ => 8,
+    }
+}
+
+fn param(param: &Param) -> String {
+    format!("{}: {}", param.ident().span().as_str(), ty_text(param.ty()))
+}
+
+/// Renders a type along with its arguments, as in `Map<Num, List<Num>>`.
+fn ty_text(ty: &Type) -> String {
+    let nullable = if ty.is_nullable() { "?" } else { "" };
+    if ty.args().is_empty() {
+        return format!("{}{}", ty.ident().span().as_str(), nullable);
+    }
+    let args = ty.args().iter().map(ty_text).collect::<Vec<_>>();
+    format!(
+        "{}<{}>{}",
+        ty.ident().span().as_str(),
+        args.join(", "),
+        nullable
+    )
+}
+
+/// Renders the type parameters of a declaration, as in `<T: Ord + Eq, U>`,
+/// or nothing when there are none.
+fn generics(generics: &Generics) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+    let params = generics
+        .params()
+        .iter()
+        .map(|param| {
+            let bounds = param
+                .bounds()
+                .iter()
+                .map(|bound| bound.span().source_text())
+                .collect::<Vec<_>>();
+            if bounds.is_empty() {
+                param.ident().span().source_text()
+            } else {
+                format!("{}: {}", param.ident().span().as_str(), bounds.join(" + "))
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("<{}>", params.join(", "))
+}
+
+/// Renders the expression in a single line, or returns `None` when it
+/// contains a class literal that was written across multiple lines, which
+/// is always kept that way.
+fn flat(expr: &Expr) -> Option<String> {
+    Some(match expr {
+        Expr::Ident(ident) => ident.ident().span().source_text(),
+        Expr::Lit(lit) => lit.span().source_text(),
+        Expr::Paren(paren) => format!("({})", flat(paren.expr())?),
+        Expr::Unary(unary) => format!("{}{}", unary.op().span().as_str(), flat(unary.expr())?),
+        Expr::Binary(binary) => match binary.op().span().as_str() {
+            op @ (".." | "..=") => {
+                format!("{}{}{}", flat(binary.left())?, op, flat(binary.right())?)
+            }
+            op => format!("{} {} {}", flat(binary.left())?, op, flat(binary.right())?),
+        },
+        Expr::Call(call) => format!(
+            "{}({})",
+            flat(call.callee())?,
+            call.args()
+                .iter()
+                .map(flat)
+                .collect::<Option<Vec<_>>>()?
+                .join(", ")
+        ),
+        Expr::Get(get) => format!(
+            "{}{}{}",
+            flat(get.expr())?,
+            if get.is_safe() { "?." } else { "." },
+            get.ident().span().as_str()
+        ),
+        Expr::Unwrap(unwrap) => format!("{}!!", flat(unwrap.expr())?),
+        Expr::Try(r#try) => format!("{}?", flat(r#try.expr())?),
+        Expr::Path(path) => format!(
+            "{}::{}",
+            path.ty().span().as_str(),
+            path.ident().span().as_str()
+        ),
+        Expr::Class(class) => {
+            let fields = class
+                .fields()
+                .iter()
+                .map(|field| match field.value() {
+                    Some(value) => Some(format!(
+                        "{}: {}",
+                        field.ident().span().as_str(),
+                        flat(value)?
+                    )),
+                    None => Some(field.ident().span().source_text()),
+                })
+                .collect::<Option<Vec<_>>>()?;
+            if fields.is_empty() {
+                format!("{} {{}}", flat(class.cls())?)
+            } else if class.span().start().line() != class.span().end().line() {
+                return None;
+            } else {
+                format!("{} {{ {} }}", flat(class.cls())?, fields.join(", "))
+            }
+        }
+    })
+}
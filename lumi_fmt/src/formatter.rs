@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+
+use lumi_lxr::span::Span;
+use lumi_lxr::token_stream::TokenStream;
+use lumi_lxr::trivia::Trivia;
+use lumi_psr::ast::Ast;
+
+use crate::format::Format;
+
+/// The maximum number of characters in a line. Longer lines are wrapped
+/// where the syntax allows it.
+pub const MAX_WIDTH: usize = 100;
+
+const INDENT: &str = "    ";
+
+/// The `Cursor` tracks the source line where the previous item of a list
+/// ended, so comments and blank lines can be placed relative to it.
+pub(crate) struct Cursor {
+    line: usize,
+    first: bool,
+}
+
+impl Cursor {
+    /// Creates a cursor for a list opened at the given source line.
+    pub(crate) fn new(line: usize) -> Self {
+        Self { line, first: true }
+    }
+}
+
+/// The `Formatter` prints an `Ast` back to source code, using four spaces
+/// for indentation and keeping the comments that were found in the
+/// source code.
+pub struct Formatter {
+    out: String,
+    indent: usize,
+    line_start: bool,
+    comments: VecDeque<Trivia>,
+}
+
+impl Formatter {
+    /// Formats the given `ast`, keeping the comments attached to the
+    /// `tokens` it was parsed from.
+    pub fn format(ast: &Ast, tokens: &TokenStream) -> String {
+        let mut formatter = Self {
+            out: String::new(),
+            indent: 0,
+            line_start: true,
+            comments: tokens
+                .iter()
+                .flat_map(|token| token.trivia().iter().cloned())
+                .collect(),
+        };
+        ast.format(&mut formatter);
+        if !formatter.out.is_empty() {
+            formatter.out.push('\n');
+        }
+        formatter.out
+    }
+
+    /// Writes all comments left, used at the end of the file.
+    pub(crate) fn remaining_comments(&mut self, cursor: &mut Cursor) {
+        self.comments_before(usize::MAX, cursor);
+    }
+
+    pub(crate) fn write(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.line_start {
+            self.out.push_str(&INDENT.repeat(self.indent));
+            self.line_start = false;
+        }
+        self.out.push_str(text);
+    }
+
+    /// Starts a new line. The indentation is only written together with
+    /// the next text, so empty lines have no trailing whitespace.
+    pub(crate) fn newline(&mut self) {
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.line_start = true;
+    }
+
+    pub(crate) fn indent(&mut self) {
+        self.indent += 1;
+    }
+
+    pub(crate) fn dedent(&mut self) {
+        self.indent -= 1;
+    }
+
+    /// Checks whether text with the given width fits in the current line.
+    pub(crate) fn fits(&self, width: usize) -> bool {
+        let column = if self.line_start {
+            self.indent * INDENT.len()
+        } else {
+            self.out[self.out.rfind('\n').map_or(0, |index| index + 1)..]
+                .chars()
+                .count()
+        };
+        column + width <= MAX_WIDTH
+    }
+
+    /// Checks whether there are comments before the given source index.
+    pub(crate) fn has_comments_before(&self, index: usize) -> bool {
+        self.comments
+            .front()
+            .is_some_and(|comment| comment.span().start().index() < index)
+    }
+
+    /// Writes the item on its own line, after the comments that precede it.
+    /// A single blank line is kept when the source code had one or more
+    /// blank lines before the item.
+    pub(crate) fn item(&mut self, span: &Span, cursor: &mut Cursor, write: impl FnOnce(&mut Self)) {
+        self.comments_before(span.start().index(), cursor);
+        self.separate(span.start().line(), cursor);
+        write(self);
+        cursor.line = span.end().line();
+        cursor.first = false;
+    }
+
+    /// Writes each item on its own indented line, followed by the comments
+    /// found before `end`. The delimiters are written by the caller.
+    pub(crate) fn items<T>(
+        &mut self,
+        items: &[T],
+        cursor: &mut Cursor,
+        end: usize,
+        span: impl Fn(&T) -> &Span,
+        mut write: impl FnMut(&mut Self, &T),
+    ) {
+        self.indent();
+        for item in items {
+            self.item(span(item), cursor, |f| write(f, item));
+        }
+        self.comments_before(end, cursor);
+        self.dedent();
+        self.newline();
+    }
+
+    /// Writes the comments that start before the given source index. A
+    /// single line comment in the same line as the previous item stays at
+    /// the end of that line, any other comment gets a line of its own.
+    pub(crate) fn comments_before(&mut self, index: usize, cursor: &mut Cursor) {
+        while self.has_comments_before(index) {
+            let comment = self.comments.pop_front().unwrap();
+            let span = comment.span();
+            let trailing = span.start().line() == cursor.line
+                && span.end().line() == cursor.line
+                && !self.line_start;
+            if trailing {
+                self.write(" ");
+            } else {
+                self.separate(span.start().line(), cursor);
+            }
+            self.comment(&comment);
+            cursor.line = span.end().line();
+            cursor.first = false;
+        }
+    }
+
+    fn separate(&mut self, line: usize, cursor: &Cursor) {
+        if !cursor.first && line > cursor.line + 1 {
+            self.newline();
+        }
+        self.newline();
+    }
+
+    /// Writes the comment, moving the lines of block comments along with
+    /// the indentation of their first line.
+    fn comment(&mut self, comment: &Trivia) {
+        let shift = comment.span().start().column() - 1;
+        for (i, line) in comment.span().as_str().lines().enumerate() {
+            if i > 0 {
+                self.newline();
+                let removed: usize = line
+                    .chars()
+                    .take(shift)
+                    .take_while(|char| char.is_whitespace())
+                    .map(char::len_utf8)
+                    .sum();
+                self.write(line[removed..].trim_end());
+            } else {
+                self.write(line.trim_end());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use lumi_lxr::lexer::Lexer;
+    use lumi_lxr::source_code::SourceCode;
+    use lumi_psr::parser::ParseStream;
+
+    use super::*;
+
+    fn format(code: &str) -> String {
+        let tokens = Lexer::new(SourceCode::new("test.ls", code))
+            .tokens()
+            .unwrap();
+        let ast = ParseStream::new(tokens.clone())
+            .parse::<Ast>()
+            .unwrap_or_else(|error| panic!("{}", error.message()));
+        Formatter::format(&ast, &tokens)
+    }
+
+    /// Checks that formatting already formatted code changes nothing.
+    fn assert_idempotent(code: &str) {
+        let formatted = format(code);
+        assert_eq!(format(&formatted), formatted);
+    }
+
+    #[test]
+    fn samples_and_prelude_are_idempotent() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let samples = fs::read_dir(root.join("samples"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path());
+        let prelude = [root.join("core.ls"), root.join("std.ls")];
+        for path in samples.chain(prelude) {
+            assert_idempotent(&fs::read_to_string(&path).unwrap());
+        }
+    }
+
+    #[test]
+    fn unformatted_code_is_idempotent() {
+        assert_idempotent("class Point{x:Num,y:Num}fun f(a:Num)->Num{a+1}\nprintln f(1);");
+    }
+
+    #[test]
+    fn keeps_comments() {
+        let code = "\
+/// Adds one.
+fun inc(a: Num) -> Num {
+    // the result
+    a + 1 /* inline */
+}
+
+/* A block
+   comment */
+println inc(1); // trailing
+";
+        let formatted = format(code);
+        for comment in [
+            "/// Adds one.",
+            "// the result",
+            "/* inline */",
+            "/* A block\n   comment */",
+            "println inc(1); // trailing",
+        ] {
+            assert!(
+                formatted.contains(comment),
+                "{:?} lost in\n{}",
+                comment,
+                formatted
+            );
+        }
+        assert_eq!(format(&formatted), formatted);
+    }
+
+    #[test]
+    fn keeps_comments_at_the_end_of_the_file() {
+        assert_eq!(
+            format("println 1;\n\n// the end\n"),
+            "println 1;\n\n// the end\n"
+        );
+    }
+}
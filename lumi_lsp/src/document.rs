@@ -0,0 +1,196 @@
+use std::fs;
+
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, Hover, HoverContents, Location, MarkupContent, MarkupKind,
+    Position, Range, Url,
+};
+use lumi_lxr::lexer::Lexer;
+use lumi_lxr::source_code::SourceCode;
+use lumi_lxr::span::Span;
+use lumi_psr::ast::Ast;
+use lumi_psr::parser::ParseStream;
+use lumi_tc::definition::Reference;
+use lumi_tc::type_checker::TypeChecker;
+
+/// The `Document` stores an open file along with everything the server
+/// knows about it: its syntax tree, its resolved symbols and the
+/// diagnostics found while analyzing it.
+pub(crate) struct Document {
+    uri: Url,
+    source_code: SourceCode,
+    ast: Option<Ast>,
+    type_checker: Option<TypeChecker>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Document {
+    /// Lexes, parses and type checks the given text with a copy of the
+    /// type checker that checked the prelude, so its declarations are
+    /// visible.
+    pub(crate) fn analyze(uri: Url, text: &str, prelude: &TypeChecker) -> Self {
+        let file_path = uri
+            .to_file_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| uri.to_string());
+        let source_code = SourceCode::new(&file_path, text);
+        let mut document = Self {
+            uri,
+            source_code: source_code.clone(),
+            ast: None,
+            type_checker: None,
+            diagnostics: vec![],
+        };
+        let tokens = match Lexer::new(source_code).tokens() {
+            Ok(tokens) => tokens,
+            Err(errors) => {
+                for error in errors {
+                    document.diagnostic(error.span(), error.message());
+                }
+                return document;
+            }
+        };
+        let ast = match ParseStream::new(tokens).parse::<Ast>() {
+            Ok(ast) => ast,
+            Err(error) => {
+                document.diagnostic(error.span(), error.message());
+                return document;
+            }
+        };
+        let mut type_checker = prelude.clone();
+        if let Err(errors) = type_checker.check(&ast) {
+            for error in errors {
+                document.diagnostic(error.span(), error.message());
+            }
+        }
+        document.ast = Some(ast);
+        document.type_checker = Some(type_checker);
+        document
+    }
+
+    pub(crate) fn ast(&self) -> Option<&Ast> {
+        self.ast.as_ref()
+    }
+
+    pub(crate) fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub(crate) fn definition(&self, position: Position) -> Option<Location> {
+        let type_checker = self.type_checker.as_ref()?;
+        let reference = self.reference_at(position)?;
+        let span = type_checker.definition(reference.def()).span()?;
+        self.location(span)
+    }
+
+    pub(crate) fn references(&self, position: Position, declaration: bool) -> Vec<Location> {
+        let (Some(type_checker), Some(reference)) =
+            (self.type_checker.as_ref(), self.reference_at(position))
+        else {
+            return vec![];
+        };
+        let def = reference.def();
+        let definition = type_checker.definition(def).span();
+        type_checker
+            .references()
+            .iter()
+            .filter(|reference| reference.def() == def)
+            .filter(|reference| declaration || Some(reference.span()) != definition)
+            .filter_map(|reference| self.location(reference.span()))
+            .collect()
+    }
+
+    pub(crate) fn hover(&self, position: Position) -> Option<Hover> {
+        let type_checker = self.type_checker.as_ref()?;
+        let reference = self.reference_at(position)?;
+        let definition = type_checker.definition(reference.def());
+        let mut value = format!("```lumiscript\n{}\n```", definition.detail());
+        if let Some(docs) = definition.docs() {
+            value.push_str("\n\n");
+            value.push_str(docs);
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(range(reference.span())),
+        })
+    }
+
+    fn diagnostic(&mut self, span: &Span, message: &str) {
+        self.diagnostics.push(Diagnostic {
+            range: range(span),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some("lumi".to_owned()),
+            message: message.to_owned(),
+            ..Default::default()
+        });
+    }
+
+    /// Finds the innermost reference of this document under the given
+    /// position.
+    fn reference_at(&self, position: Position) -> Option<&Reference> {
+        let index = self.index(position);
+        self.type_checker
+            .as_ref()?
+            .references()
+            .iter()
+            .filter(|reference| self.contains(reference.span()))
+            .filter(|reference| {
+                let span = reference.span();
+                span.start().index() <= index && index <= span.end().index()
+            })
+            .min_by_key(|reference| {
+                reference.span().end().index() - reference.span().start().index()
+            })
+    }
+
+    fn contains(&self, span: &Span) -> bool {
+        span.source_code().file_path() == self.source_code.file_path()
+    }
+
+    fn location(&self, span: &Span) -> Option<Location> {
+        let uri = if self.contains(span) {
+            self.uri.clone()
+        } else {
+            let path = fs::canonicalize(span.source_code().file_path()).ok()?;
+            Url::from_file_path(path).ok()?
+        };
+        Some(Location::new(uri, range(span)))
+    }
+
+    /// Converts a position, whose character is counted in UTF-16 code
+    /// units, into a byte index of the source code.
+    fn index(&self, position: Position) -> usize {
+        let code = self.source_code.code();
+        let line_start = code
+            .split_inclusive('\n')
+            .take(position.line as usize)
+            .map(str::len)
+            .sum::<usize>();
+        let mut units = 0;
+        for (index, char) in code[line_start..].char_indices() {
+            if units >= position.character as usize || char == '\n' {
+                return line_start + index;
+            }
+            units += char.len_utf16();
+        }
+        code.len()
+    }
+}
+
+pub(crate) fn range(span: &Span) -> Range {
+    Range::new(
+        position(span, span.start().index()),
+        position(span, span.end().index()),
+    )
+}
+
+fn position(span: &Span, index: usize) -> Position {
+    let code = span.source_code().code();
+    let index = index.min(code.len());
+    let line_start = code[..index].rfind('\n').map_or(0, |line_end| line_end + 1);
+    let line = code[..line_start].matches('\n').count();
+    let character = code[line_start..index].encode_utf16().count();
+    Position::new(line as u32, character as u32)
+}
@@ -0,0 +1,3 @@
+mod document;
+pub mod server;
+mod symbols;
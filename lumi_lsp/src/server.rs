@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationTrait, PublishDiagnostics,
+};
+use lsp_types::request::{
+    DocumentSymbolRequest, GotoDefinition, HoverRequest, References, Request as RequestTrait,
+};
+use lsp_types::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
+    HoverParams, HoverProviderCapability, OneOf, PublishDiagnosticsParams, ReferenceParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use lumi_lxr::lexer::Lexer;
+use lumi_lxr::source_code::SourceCode;
+use lumi_psr::ast::Ast;
+use lumi_psr::parser::ParseStream;
+use lumi_tc::type_checker::TypeChecker;
+
+use crate::document::Document;
+use crate::symbols::document_symbols;
+
+/// Files declaring the built-in traits and functions, embedded so the server
+/// works from any directory. They're checked once, and every document is
+/// checked on top of them, just like they are compiled before every program.
+const PRELUDE: [(&str, &str); 2] = [
+    (
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../core.ls"),
+        include_str!("../../core.ls"),
+    ),
+    (
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../std.ls"),
+        include_str!("../../std.ls"),
+    ),
+];
+
+/// Runs a language server over stdin and stdout until the client shuts it
+/// down.
+pub fn run() -> Result<(), Box<dyn Error + Sync + Send>> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    })?;
+    connection.initialize(capabilities)?;
+    Server {
+        connection,
+        prelude: prelude(),
+        documents: HashMap::new(),
+    }
+    .main_loop()?;
+    io_threads.join()?;
+    Ok(())
+}
+
+struct Server {
+    connection: Connection,
+    prelude: TypeChecker,
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    fn main_loop(mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.request(request);
+                    self.connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => self.notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            GotoDefinition::METHOD => {
+                serde_json::from_value(request.params).map(|params: GotoDefinitionParams| {
+                    let position = params.text_document_position_params;
+                    let location = self
+                        .documents
+                        .get(&position.text_document.uri)
+                        .and_then(|document| document.definition(position.position));
+                    serde_json::to_value(location.map(GotoDefinitionResponse::Scalar))
+                })
+            }
+            References::METHOD => {
+                serde_json::from_value(request.params).map(|params: ReferenceParams| {
+                    let position = params.text_document_position;
+                    let locations =
+                        self.documents
+                            .get(&position.text_document.uri)
+                            .map(|document| {
+                                document.references(
+                                    position.position,
+                                    params.context.include_declaration,
+                                )
+                            });
+                    serde_json::to_value(locations)
+                })
+            }
+            HoverRequest::METHOD => {
+                serde_json::from_value(request.params).map(|params: HoverParams| {
+                    let position = params.text_document_position_params;
+                    let hover = self
+                        .documents
+                        .get(&position.text_document.uri)
+                        .and_then(|document| document.hover(position.position));
+                    serde_json::to_value(hover)
+                })
+            }
+            DocumentSymbolRequest::METHOD => {
+                serde_json::from_value(request.params).map(|params: DocumentSymbolParams| {
+                    let symbols = self
+                        .documents
+                        .get(&params.text_document.uri)
+                        .and_then(Document::ast)
+                        .map(|ast| DocumentSymbolResponse::Nested(document_symbols(ast.stmts())));
+                    serde_json::to_value(symbols)
+                })
+            }
+            _ => {
+                return Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("Method '{}' not supported", request.method),
+                )
+            }
+        };
+        match result.and_then(|value| value) {
+            Ok(value) => Response::new_ok(id, value),
+            Err(error) => Response::new_err(
+                id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                error.to_string(),
+            ),
+        }
+    }
+
+    fn notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document = params.text_document;
+                self.open(document.uri, &document.text)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.open(params.text_document.uri, &change.text)?;
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+                self.publish(params.text_document.uri, vec![])?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn open(&mut self, uri: Url, text: &str) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let document = Document::analyze(uri.clone(), text, &self.prelude);
+        let diagnostics = document.diagnostics().to_vec();
+        self.documents.insert(uri.clone(), document);
+        self.publish(uri, diagnostics)
+    }
+
+    fn publish(
+        &self,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))?;
+        Ok(())
+    }
+}
+
+/// Checks the prelude files. Files that don't parse are skipped.
+fn prelude() -> TypeChecker {
+    let mut type_checker = TypeChecker::new();
+    for (file_path, code) in PRELUDE {
+        let Ok(tokens) = Lexer::new(SourceCode::new(file_path, code)).tokens() else {
+            continue;
+        };
+        if let Ok(ast) = ParseStream::new(tokens).parse::<Ast>() {
+            let _ = type_checker.check(&ast);
+        }
+    }
+    type_checker
+}
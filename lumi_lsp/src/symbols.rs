@@ -0,0 +1,152 @@
+use lsp_types::{DocumentSymbol, SymbolKind};
+use lumi_lxr::span::Span;
+use lumi_psr::stmts::fun::FunStmt;
+use lumi_psr::stmts::r#impl::Method;
+use lumi_psr::stmts::Stmt;
+use lumi_psr::symbols::Ident;
+
+use crate::document::range;
+
+/// Gets the classes, traits, impls and functions declared by the given
+/// statements, nested the same way they are in the source code.
+pub(crate) fn document_symbols(stmts: &[Stmt]) -> Vec<DocumentSymbol> {
+    let mut symbols = vec![];
+    for stmt in stmts {
+        match stmt {
+            Stmt::Class(class) => {
+                let fields = class
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        let detail = field.ty().span().source_text();
+                        symbol(
+                            field.ident(),
+                            field.span(),
+                            SymbolKind::FIELD,
+                            Some(detail),
+                            vec![],
+                        )
+                    })
+                    .collect();
+                symbols.push(symbol(
+                    class.ident(),
+                    class.span(),
+                    SymbolKind::CLASS,
+                    None,
+                    fields,
+                ));
+            }
+            Stmt::Trait(r#trait) => {
+                let protos = r#trait
+                    .protos()
+                    .iter()
+                    .map(|proto| {
+                        let children = proto
+                            .block()
+                            .map_or_else(Vec::new, |block| document_symbols(block.stmts()));
+                        symbol(
+                            proto.ident(),
+                            proto.span(),
+                            SymbolKind::METHOD,
+                            None,
+                            children,
+                        )
+                    })
+                    .collect();
+                symbols.push(symbol(
+                    r#trait.ident(),
+                    r#trait.span(),
+                    SymbolKind::INTERFACE,
+                    None,
+                    protos,
+                ));
+            }
+            Stmt::Impl(r#impl) => {
+                let methods = r#impl
+                    .methods()
+                    .iter()
+                    .map(|method| {
+                        let children = match method {
+                            Method::Default { block, .. } => document_symbols(block.stmts()),
+                            Method::Extern { .. } => vec![],
+                        };
+                        symbol(
+                            method.ident(),
+                            method.span(),
+                            SymbolKind::METHOD,
+                            None,
+                            children,
+                        )
+                    })
+                    .collect();
+                let name = match r#impl.tr() {
+                    Some(tr) => format!(
+                        "impl {} for {}",
+                        tr.span().source_text(),
+                        r#impl.ty().span().source_text()
+                    ),
+                    None => format!("impl {}", r#impl.ty().span().source_text()),
+                };
+                #[allow(deprecated)]
+                symbols.push(DocumentSymbol {
+                    name,
+                    detail: None,
+                    kind: SymbolKind::OBJECT,
+                    tags: None,
+                    deprecated: None,
+                    range: range(r#impl.span()),
+                    selection_range: range(r#impl.ty().span()),
+                    children: Some(methods),
+                });
+            }
+            Stmt::Fun(fun) => {
+                let children = match fun {
+                    FunStmt::Default { block, .. } => document_symbols(block.stmts()),
+                    FunStmt::Extern { .. } => vec![],
+                };
+                symbols.push(symbol(
+                    fun.ident(),
+                    fun.span(),
+                    SymbolKind::FUNCTION,
+                    None,
+                    children,
+                ));
+            }
+            Stmt::Block(block) => symbols.extend(document_symbols(block.stmts())),
+            Stmt::If(r#if) => symbols.extend(document_symbols(r#if.stmts())),
+            Stmt::While(r#while) => symbols.extend(document_symbols(r#while.stmts())),
+            Stmt::For(r#for) => symbols.extend(document_symbols(r#for.block().stmts())),
+            Stmt::Try(r#try) => {
+                symbols.extend(document_symbols(r#try.block().stmts()));
+                if let Some(catch) = r#try.catch() {
+                    symbols.extend(document_symbols(catch.block().stmts()));
+                }
+                if let Some(finally) = r#try.finally() {
+                    symbols.extend(document_symbols(finally.stmts()));
+                }
+            }
+            _ => {}
+        }
+    }
+    symbols
+}
+
+fn symbol(
+    ident: &Ident,
+    span: &Span,
+    kind: SymbolKind,
+    detail: Option<String>,
+    children: Vec<DocumentSymbol>,
+) -> DocumentSymbol {
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: ident.source_text(),
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: range(span),
+        selection_range: range(ident.span()),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
@@ -0,0 +1,46 @@
+//! Lexes a generated 100k-line file and reports the throughput.
+//!
+//! Run with `cargo bench -p lumi_lxr`.
+
+use std::time::Instant;
+
+use lumi_lxr::lexer::Lexer;
+use lumi_lxr::source_code::SourceCode;
+
+const LINES: usize = 100_000;
+const RUNS: usize = 5;
+
+fn generate() -> String {
+    let chunk = "\
+// Computes the distância between two points.
+fun distância(a: Point, b: Point) -> Num {
+    let δx = a.x - b.x; /* ünïcödé */
+    let δy = a.y - b.y;
+    return δx + δy * 2.5;
+}
+";
+    let chunk_lines = chunk.lines().count();
+    chunk.repeat(LINES / chunk_lines + 1)
+}
+
+fn main() {
+    let code = generate();
+    let lines = code.lines().count();
+    let mut best = f64::MAX;
+    let mut tokens = 0;
+    for _ in 0..RUNS {
+        let source_code = SourceCode::new("bench.ls", &code);
+        let start = Instant::now();
+        let stream = Lexer::new(source_code).tokens().ok().unwrap();
+        best = best.min(start.elapsed().as_secs_f64());
+        tokens = stream.iter().len();
+    }
+    println!(
+        "lexed {} lines ({} bytes, {} tokens) in {:.2} ms ({:.0} lines/s)",
+        lines,
+        code.len(),
+        tokens,
+        best * 1000.0,
+        lines as f64 / best
+    );
+}
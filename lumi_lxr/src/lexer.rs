@@ -1,12 +1,18 @@
 use std::fmt::Display;
 
 use colored::Colorize;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use unicode_ident::{is_xid_continue, is_xid_start};
 
 use crate::token::{Token, TokenKind};
 use crate::token_stream::TokenStream;
+use crate::trivia::{Trivia, TriviaKind};
 use crate::utils::line_column::LineColumn;
 use crate::utils::source_code::SourceCode;
 use crate::utils::span::Span;
+use crate::utils::string::escape;
+use crate::utils::symbol::Symbol;
 
 #[derive(Debug, Clone)]
 pub struct LexError {
@@ -14,6 +20,26 @@ pub struct LexError {
     span: Span,
 }
 
+impl LexError {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl Serialize for LexError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("LexError", 3)?;
+        error.serialize_field("kind", "Error")?;
+        error.serialize_field("message", &self.message)?;
+        error.serialize_field("span", &self.span)?;
+        error.end()
+    }
+}
+
 impl Display for LexError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let line = self.span.start().line();
@@ -24,7 +50,14 @@ impl Display for LexError {
             .code()
             .lines()
             .nth(line - 1)
-            .unwrap();
+            .unwrap_or_default();
+        // Only the first line of an error spanning several lines, such as
+        // an unterminated block comment, is underlined.
+        let width = if self.span.end().line() == line {
+            self.span.end().column().saturating_sub(column)
+        } else {
+            (line_content.chars().count() + 1).saturating_sub(column)
+        };
         let output = format!(
             "{}: {} \
             \n{} {}:{}:{} \
@@ -46,18 +79,20 @@ impl Display for LexError {
             " ",
             "|".blue().bold(),
             " ".repeat(column),
-            "^".repeat(self.span.end().column() - self.span.start().column())
-                .red()
-                .bold(),
+            "^".repeat(width).red().bold(),
         );
         write!(f, "{}", output)
     }
 }
 
+/// The `Lexer` walks the source code once, from start to end, decoding
+/// one UTF-8 character at a time.
 pub struct Lexer {
     cur_line_column: LineColumn,
     source_code: SourceCode,
     errors: Vec<LexError>,
+    trivia: Vec<Trivia>,
+    finished: bool,
 }
 
 impl Lexer {
@@ -66,25 +101,45 @@ impl Lexer {
             cur_line_column: LineColumn::default(),
             source_code,
             errors: vec![],
+            trivia: vec![],
+            finished: false,
         }
     }
 
     pub fn tokens(&mut self) -> Result<TokenStream, Vec<LexError>> {
+        let (tokens, errors) = self.tokens_lossy();
+        if errors.is_empty() {
+            Ok(TokenStream::new(&tokens))
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Lexes the whole source code, returning the tokens found around the
+    /// errors along with the errors themselves.
+    pub fn tokens_lossy(&mut self) -> (Vec<Token>, Vec<LexError>) {
         let mut tokens = vec![];
         while let Some(token) = self.next_token() {
             tokens.push(token);
         }
-        if self.errors.is_empty() {
-            Ok(TokenStream::new(&tokens))
-        } else {
-            Err(self.errors.clone())
-        }
+        (tokens, std::mem::take(&mut self.errors))
     }
 }
 
 impl Lexer {
-    fn create_token(&self, kind: TokenKind, start: LineColumn, end: LineColumn) -> Token {
+    /// Creates a token, attaching to it the trivia found since the
+    /// previous one.
+    fn create_token(&mut self, kind: TokenKind, start: LineColumn, end: LineColumn) -> Token {
         Token::new(kind, start, end, self.source_code.clone())
+            .with_trivia(std::mem::take(&mut self.trivia))
+    }
+
+    fn push_trivia(&mut self, kind: TriviaKind, start: LineColumn) {
+        let end = self.cur_line_column;
+        self.trivia.push(Trivia::new(
+            kind,
+            Span::new(start, end, self.source_code.clone()),
+        ));
     }
 
     fn report_error(&mut self, message: &str, span: Span) {
@@ -98,93 +153,220 @@ impl Lexer {
         self.cur_line_column.index()
     }
 
+    fn rest(&self) -> &str {
+        &self.source_code.code()[self.cur_index()..]
+    }
+
     fn next_index(&mut self) {
-        if self.peek() == '\n' {
-            self.cur_line_column.next_line();
-        } else {
-            self.cur_line_column.next_column();
+        if let Some(char) = self.rest().chars().next() {
+            self.cur_line_column.advance(char);
         }
     }
 
     fn is_at_end(&self) -> bool {
-        self.cur_index() == self.source_code.code().len()
+        self.cur_index() >= self.source_code.code().len()
     }
 
     fn peek(&self) -> char {
-        self.source_code
-            .code()
-            .bytes()
-            .nth(self.cur_index())
-            .unwrap_or(b'\0') as char
+        self.rest().chars().next().unwrap_or('\0')
     }
 
     fn peek2(&self) -> char {
-        self.source_code
-            .code()
-            .bytes()
-            .nth(self.cur_index() + 1)
-            .unwrap_or(b'\0') as char
+        self.rest().chars().nth(1).unwrap_or('\0')
     }
 
     fn skip_whitespace(&mut self) {
         while !self.is_at_end() && self.peek().is_whitespace() {
-            if self.peek() == '\n' {
-                self.cur_line_column.next_line();
-            } else {
-                self.cur_line_column.next_column();
-            }
+            self.next_index();
         }
     }
 
-    fn skip_one_line_comment(&mut self) {
+    fn shebang(&mut self) {
+        let start = self.cur_line_column;
+        while !self.is_at_end() && self.peek() != '\n' {
+            self.next_index();
+        }
+        self.push_trivia(TriviaKind::Shebang, start);
+    }
+
+    fn line_comment(&mut self) {
+        let start = self.cur_line_column;
         self.next_index(); // /
         self.next_index(); // /
         while !self.is_at_end() && self.peek() != '\n' {
             self.next_index();
         }
+        let text = &self.source_code[start..self.cur_line_column];
+        let kind = if text.starts_with("///") && !text.starts_with("////") {
+            TriviaKind::DocLineComment
+        } else {
+            TriviaKind::LineComment
+        };
+        self.push_trivia(kind, start);
     }
 
-    fn skip_multiline_line_comment(&mut self) {
+    fn block_comment(&mut self) {
         let start = self.cur_line_column;
         self.next_index(); // /
         self.next_index(); // *
-        while !self.is_at_end() && (self.peek() != '*' || self.peek2() != '/') {
-            if self.peek() == '\n' {
-                self.cur_line_column.next_line();
+        let mut depth = 1;
+        while !self.is_at_end() && depth > 0 {
+            if self.peek() == '/' && self.peek2() == '*' {
+                self.next_index(); // /
+                self.next_index(); // *
+                depth += 1;
+            } else if self.peek() == '*' && self.peek2() == '/' {
+                self.next_index(); // *
+                self.next_index(); // /
+                depth -= 1;
             } else {
-                self.cur_line_column.next_column();
+                self.next_index();
             }
         }
-        let end = self.cur_line_column;
-        if self.is_at_end() {
+        if depth > 0 {
+            let end = self.cur_line_column;
             self.report_error(
                 "Expected closing comment",
                 Span::new(start, end, self.source_code.clone()),
             );
         }
-        self.next_index(); // *
-        self.next_index(); // /
+        let text = &self.source_code[start..self.cur_line_column];
+        let kind = if text.starts_with("/**") && !text.starts_with("/***") && text != "/**/" {
+            TriviaKind::DocBlockComment
+        } else {
+            TriviaKind::BlockComment
+        };
+        self.push_trivia(kind, start);
+    }
+
+    /// Skips the digits of the given radix, including `_` separators, and
+    /// returns how many digits were found.
+    fn skip_digits(&mut self, radix: u32) -> usize {
+        let mut count = 0;
+        while self.peek().is_digit(radix) || self.peek() == '_' {
+            if self.peek() != '_' {
+                count += 1;
+            }
+            self.next_index();
+        }
+        count
+    }
+
+    fn number(&mut self) -> Token {
+        let start = self.cur_line_column;
+        let (radix, name) = match (self.peek(), self.peek2()) {
+            ('0', 'x' | 'X') => (16, "hexadecimal"),
+            ('0', 'o' | 'O') => (8, "octal"),
+            ('0', 'b' | 'B') => (2, "binary"),
+            _ => (10, "decimal"),
+        };
+        let mut valid = true;
+        if radix != 10 {
+            self.next_index(); // 0
+            self.next_index(); // x, o or b
+            if self.skip_digits(radix) == 0 {
+                let end = self.cur_line_column;
+                self.report_error(
+                    &format!("Expected {} digits", name),
+                    Span::new(start, end, self.source_code.clone()),
+                );
+                valid = false;
+            }
+        } else {
+            self.skip_digits(10);
+            if self.peek() == '.' && self.peek2().is_ascii_digit() {
+                self.next_index(); // .
+                self.skip_digits(10);
+            }
+            if matches!(self.peek(), 'e' | 'E') {
+                self.next_index(); // e
+                if matches!(self.peek(), '+' | '-') {
+                    self.next_index();
+                }
+                if self.skip_digits(10) == 0 {
+                    let end = self.cur_line_column;
+                    self.report_error(
+                        "Expected exponent digits",
+                        Span::new(start, end, self.source_code.clone()),
+                    );
+                    valid = false;
+                }
+            }
+        }
+        if is_xid_continue(self.peek()) {
+            let invalid = self.peek();
+            while is_xid_continue(self.peek()) {
+                self.next_index();
+            }
+            if valid {
+                let end = self.cur_line_column;
+                self.report_error(
+                    &format!("Invalid digit '{}' in {} literal", invalid, name),
+                    Span::new(start, end, self.source_code.clone()),
+                );
+            }
+        }
+        let end = self.cur_line_column;
+        self.create_token(TokenKind::Number, start, end)
+    }
+
+    fn string(&mut self) -> Token {
+        let start = self.cur_line_column;
+        self.next_index(); // "
+        while !self.is_at_end() && self.peek() != '"' && self.peek() != '\n' {
+            if self.peek() == '\\' {
+                let escape_start = self.cur_line_column;
+                self.next_index(); // \
+                let char = self.peek();
+                if !self.is_at_end() && char != '\n' {
+                    self.next_index();
+                }
+                if escape(char).is_none() {
+                    let end = self.cur_line_column;
+                    self.report_error(
+                        &format!("Unknown escape sequence '\\{}'", char.escape_default()),
+                        Span::new(escape_start, end, self.source_code.clone()),
+                    );
+                }
+            } else {
+                self.next_index();
+            }
+        }
+        if self.peek() == '"' {
+            self.next_index();
+        } else {
+            let end = self.cur_line_column;
+            self.report_error(
+                "Expected closing quote",
+                Span::new(start, end, self.source_code.clone()),
+            );
+        }
+        let end = self.cur_line_column;
+        self.create_token(TokenKind::String, start, end)
     }
 
     fn next_token(&mut self) -> Option<Token> {
+        if self.cur_index() == 0 && self.rest().starts_with("#!") {
+            self.shebang();
+        }
         while !self.is_at_end()
             && ((self.peek() == '/' && self.peek2() == '*')
                 || (self.peek() == '/' && self.peek2() == '/')
                 || self.peek().is_whitespace())
         {
             if self.peek() == '/' && self.peek2() == '*' {
-                self.skip_multiline_line_comment();
+                self.block_comment();
             } else if self.peek() == '/' && self.peek2() == '/' {
-                self.skip_one_line_comment();
+                self.line_comment();
             } else {
                 self.skip_whitespace();
             }
         }
-        if self.cur_index() > self.source_code.code().len() {
+        if self.finished {
             None
         } else if self.is_at_end() {
+            self.finished = true;
             let start = self.cur_line_column;
-            self.cur_line_column.next_column();
             let end = start;
             Some(self.create_token(TokenKind::Eof, start, end))
         } else {
@@ -202,10 +384,44 @@ impl Lexer {
                     Some(self.create_token(TokenKind::Comma, start, end))
                 }
                 ':' => {
+                    self.next_index();
+                    if self.peek() == ':' {
+                        self.next_index();
+                        let end = self.cur_line_column;
+                        Some(self.create_token(TokenKind::ColonColon, start, end))
+                    } else {
+                        let end = self.cur_line_column;
+                        Some(self.create_token(TokenKind::Colon, start, end))
+                    }
+                }
+                '?' => {
+                    self.next_index();
+                    if self.peek() == '.' {
+                        self.next_index();
+                        let end = self.cur_line_column;
+                        Some(self.create_token(TokenKind::InterrogationDot, start, end))
+                    } else if self.peek() == '?' {
+                        self.next_index();
+                        let end = self.cur_line_column;
+                        Some(self.create_token(TokenKind::InterrogationInterrogation, start, end))
+                    } else {
+                        let end = self.cur_line_column;
+                        Some(self.create_token(TokenKind::Interrogation, start, end))
+                    }
+                }
+                '&' if self.peek2() == '&' => {
+                    self.next_index();
                     self.next_index();
                     let end = self.cur_line_column;
-                    Some(self.create_token(TokenKind::Colon, start, end))
+                    Some(self.create_token(TokenKind::AmpersandAmpersand, start, end))
                 }
+                '|' if self.peek2() == '|' => {
+                    self.next_index();
+                    self.next_index();
+                    let end = self.cur_line_column;
+                    Some(self.create_token(TokenKind::PipePipe, start, end))
+                }
+                '"' => Some(self.string()),
                 '(' => {
                     self.next_index();
                     let end = self.cur_line_column;
@@ -241,6 +457,11 @@ impl Lexer {
                     let end = self.cur_line_column;
                     Some(self.create_token(TokenKind::Slash, start, end))
                 }
+                '%' => {
+                    self.next_index();
+                    let end = self.cur_line_column;
+                    Some(self.create_token(TokenKind::Percent, start, end))
+                }
                 '-' => {
                     self.next_index();
                     if self.peek() == '>' {
@@ -296,10 +517,10 @@ impl Lexer {
                     if self.peek() == '=' {
                         self.next_index();
                         let end = self.cur_line_column;
-                        Some(self.create_token(TokenKind::Greater, start, end))
+                        Some(self.create_token(TokenKind::GreaterEqual, start, end))
                     } else {
                         let end = self.cur_line_column;
-                        Some(self.create_token(TokenKind::GreaterEqual, start, end))
+                        Some(self.create_token(TokenKind::Greater, start, end))
                     }
                 }
                 '<' => {
@@ -307,29 +528,18 @@ impl Lexer {
                     if self.peek() == '=' {
                         self.next_index();
                         let end = self.cur_line_column;
-                        Some(self.create_token(TokenKind::Less, start, end))
+                        Some(self.create_token(TokenKind::LessEqual, start, end))
                     } else {
                         let end = self.cur_line_column;
-                        Some(self.create_token(TokenKind::LessEqual, start, end))
+                        Some(self.create_token(TokenKind::Less, start, end))
                     }
                 }
                 _ => {
-                    if self.peek().is_digit(10) {
-                        let start = self.cur_line_column;
-                        while self.peek().is_digit(10) {
-                            self.next_index();
-                        }
-                        if self.peek() == '.' && self.peek2().is_digit(10) {
-                            self.next_index(); // .
-                            while self.peek().is_digit(10) {
-                                self.next_index();
-                            }
-                        }
-                        let end = self.cur_line_column;
-                        Some(self.create_token(TokenKind::Number, start, end))
-                    } else if self.peek().is_alphabetic() || self.peek() == '_' {
+                    if self.peek().is_ascii_digit() {
+                        Some(self.number())
+                    } else if is_xid_start(self.peek()) || self.peek() == '_' {
                         let start = self.cur_line_column;
-                        while self.peek().is_alphanumeric() || self.peek() == '_' {
+                        while is_xid_continue(self.peek()) {
                             self.next_index();
                         }
                         let end = self.cur_line_column;
@@ -354,11 +564,20 @@ impl Lexer {
                             "return" => TokenKind::Return,
                             "break" => TokenKind::Break,
                             "continue" => TokenKind::Continue,
+                            "throw" => TokenKind::Throw,
+                            "try" => TokenKind::Try,
+                            "catch" => TokenKind::Catch,
+                            "finally" => TokenKind::Finally,
                             "println" => TokenKind::Println,
                             _ => TokenKind::Ident,
                         };
 
-                        Some(self.create_token(kind, start, end))
+                        if kind == TokenKind::Ident {
+                            let symbol = Symbol::intern(&self.source_code[start..end]);
+                            Some(self.create_token(kind, start, end).with_symbol(symbol))
+                        } else {
+                            Some(self.create_token(kind, start, end))
+                        }
                     } else {
                         self.next_index();
                         let end = self.cur_line_column;
@@ -374,3 +593,149 @@ impl Lexer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::number::parse_number;
+
+    /// Lexes `code`, returning the kind and text of every token but `Eof`.
+    fn lex(code: &str) -> Result<Vec<(TokenKind, String)>, Vec<String>> {
+        let mut lexer = Lexer::new(SourceCode::new("test.ls", code));
+        match lexer.tokens() {
+            Ok(tokens) => Ok(tokens
+                .iter()
+                .filter(|token| token.kind() != TokenKind::Eof)
+                .map(|token| (token.kind(), token.source_text()))
+                .collect()),
+            Err(errors) => Err(errors
+                .iter()
+                .map(|error| error.message().to_owned())
+                .collect()),
+        }
+    }
+
+    /// Lexes `code` as a single number literal and parses its value.
+    fn number(code: &str) -> f64 {
+        let tokens = lex(code).unwrap();
+        assert_eq!(tokens, vec![(TokenKind::Number, code.to_owned())]);
+        parse_number(code).unwrap()
+    }
+
+    #[test]
+    fn integers_and_fractions() {
+        assert_eq!(number("0"), 0.0);
+        assert_eq!(number("42"), 42.0);
+        assert_eq!(number("3.25"), 3.25);
+    }
+
+    #[test]
+    fn dot_without_digits_is_not_a_fraction() {
+        let tokens = lex("1.foo").unwrap();
+        let kinds = tokens.iter().map(|(kind, _)| *kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![TokenKind::Number, TokenKind::Dot, TokenKind::Ident]
+        );
+    }
+
+    #[test]
+    fn exponents() {
+        assert_eq!(number("1e3"), 1000.0);
+        assert_eq!(number("2.5E-3"), 0.0025);
+        assert_eq!(number("1e+2"), 100.0);
+    }
+
+    #[test]
+    fn radix_prefixes() {
+        assert_eq!(number("0xFF"), 255.0);
+        assert_eq!(number("0XfF"), 255.0);
+        assert_eq!(number("0o17"), 15.0);
+        assert_eq!(number("0b1010"), 10.0);
+    }
+
+    #[test]
+    fn separators() {
+        assert_eq!(number("1_000_000"), 1_000_000.0);
+        assert_eq!(number("0b1111_0000"), 240.0);
+        assert_eq!(number("1_0.5_0"), 10.5);
+    }
+
+    #[test]
+    fn missing_radix_digits() {
+        assert_eq!(
+            lex("0x"),
+            Err(vec!["Expected hexadecimal digits".to_owned()])
+        );
+        assert_eq!(lex("0o;"), Err(vec!["Expected octal digits".to_owned()]));
+        assert_eq!(lex("0b"), Err(vec!["Expected binary digits".to_owned()]));
+    }
+
+    #[test]
+    fn missing_exponent_digits() {
+        assert_eq!(lex("1e"), Err(vec!["Expected exponent digits".to_owned()]));
+        assert_eq!(
+            lex("1e+;"),
+            Err(vec!["Expected exponent digits".to_owned()])
+        );
+    }
+
+    #[test]
+    fn invalid_digits() {
+        assert_eq!(
+            lex("0b102"),
+            Err(vec!["Invalid digit '2' in binary literal".to_owned()])
+        );
+        assert_eq!(lex("0o8"), Err(vec!["Expected octal digits".to_owned()]));
+        assert_eq!(
+            lex("12abc"),
+            Err(vec!["Invalid digit 'a' in decimal literal".to_owned()])
+        );
+    }
+
+    #[test]
+    fn one_error_per_invalid_literal() {
+        let errors = lex("0xZZ 1e").unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "Expected hexadecimal digits".to_owned(),
+                "Expected exponent digits".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_nested_block_comment_on_several_lines() {
+        let code = "let a = 1; /* outer\n/* inner */\nb";
+        let mut lexer = Lexer::new(SourceCode::new("test.ls", code));
+        let errors = lexer.tokens().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), "Expected closing comment");
+        let report = errors[0].to_string();
+        assert!(report.contains("let a = 1; /* outer"));
+        assert!(report.contains(&"^".repeat(8)));
+        assert!(!report.contains(&"^".repeat(9)));
+    }
+
+    #[test]
+    fn lossy_lexing_keeps_the_tokens_around_errors() {
+        let (tokens, errors) =
+            Lexer::new(SourceCode::new("test.ls", "let a = @ 1;")).tokens_lossy();
+        let kinds = tokens.iter().map(|token| token.kind()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Let,
+                TokenKind::Ident,
+                TokenKind::Equal,
+                TokenKind::Bad,
+                TokenKind::Number,
+                TokenKind::Semicolon,
+                TokenKind::Eof,
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span().source_text(), "@");
+    }
+}
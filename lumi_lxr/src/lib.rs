@@ -1,6 +1,7 @@
 pub mod lexer;
 pub mod token;
 pub mod token_stream;
+pub mod trivia;
 mod utils;
 
 pub use utils::*;
@@ -1,10 +1,15 @@
 use std::fmt::Debug;
 
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::trivia::Trivia;
 use crate::utils::line_column::LineColumn;
 use crate::utils::source_code::SourceCode;
 use crate::utils::span::Span;
+use crate::utils::symbol::Symbol;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum TokenKind {
     If,
     Else,
@@ -22,6 +27,7 @@ pub enum TokenKind {
     Semicolon,
     Comma,
     Colon,
+    ColonColon,
     Bad,
     Dot,
     DotDot,
@@ -31,6 +37,7 @@ pub enum TokenKind {
     MinusGreater,
     Star,
     Slash,
+    Percent,
     Equal,
     EqualEqual,
     Bang,
@@ -54,13 +61,23 @@ pub enum TokenKind {
     Trait,
     Impl,
     Interrogation,
+    InterrogationDot,
+    InterrogationInterrogation,
+    AmpersandAmpersand,
+    PipePipe,
     Static,
+    Throw,
+    Try,
+    Catch,
+    Finally,
 }
 
 #[derive(Clone)]
 pub struct Token {
     span: Span,
     kind: TokenKind,
+    symbol: Option<Symbol>,
+    trivia: Vec<Trivia>,
 }
 
 impl Token {
@@ -73,9 +90,17 @@ impl Token {
         Self {
             kind,
             span: Span::new(start, end, source_code),
+            symbol: None,
+            trivia: vec![],
         }
     }
 
+    /// Attaches the interned text of the token, used by identifiers.
+    pub fn with_symbol(mut self, symbol: Symbol) -> Self {
+        self.symbol = Some(symbol);
+        self
+    }
+
     pub fn source_text(&self) -> String {
         self.span.source_text()
     }
@@ -87,6 +112,33 @@ impl Token {
     pub fn kind(&self) -> TokenKind {
         self.kind
     }
+
+    /// Attaches the trivia, such as comments, that precedes the token.
+    pub fn with_trivia(mut self, trivia: Vec<Trivia>) -> Self {
+        self.trivia = trivia;
+        self
+    }
+
+    /// Gets the trivia, such as comments, that precedes the token.
+    pub fn trivia(&self) -> &[Trivia] {
+        &self.trivia
+    }
+
+    /// Gets the interned text of the token, if it is an identifier.
+    pub fn symbol(&self) -> Option<Symbol> {
+        self.symbol
+    }
+}
+
+impl Serialize for Token {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut token = serializer.serialize_struct("Token", 4)?;
+        token.serialize_field("kind", &self.kind)?;
+        token.serialize_field("text", self.span.as_str())?;
+        token.serialize_field("span", &self.span)?;
+        token.serialize_field("trivia", &self.trivia)?;
+        token.end()
+    }
 }
 
 impl Debug for Token {
@@ -16,7 +16,7 @@ impl TokenStream {
     }
 
     /// Returns an iterator over the stream.
-    pub fn iter(&self) -> Iter<Token> {
+    pub fn iter(&self) -> Iter<'_, Token> {
         self.stream.iter()
     }
 }
@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+use crate::utils::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TriviaKind {
+    /// A `// ...` comment.
+    LineComment,
+    /// A `/* ... */` comment, which may contain nested block comments.
+    BlockComment,
+    /// A `/// ...` documentation comment.
+    DocLineComment,
+    /// A `/** ... */` documentation comment.
+    DocBlockComment,
+    /// A `#!` line at the start of the file, naming the interpreter of an
+    /// executable script.
+    Shebang,
+}
+
+/// The `Trivia` represents a piece of source code that has no meaning to
+/// the parser, such as comments. It is kept next to the token it
+/// precedes, so tools like the formatter and documentation generators can
+/// still read it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Trivia {
+    kind: TriviaKind,
+    span: Span,
+}
+
+impl Trivia {
+    pub fn new(kind: TriviaKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    pub fn kind(&self) -> TriviaKind {
+        self.kind
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Checks whether the trivia is a documentation comment.
+    pub fn is_doc(&self) -> bool {
+        matches!(
+            self.kind,
+            TriviaKind::DocLineComment | TriviaKind::DocBlockComment
+        )
+    }
+
+    /// Gets the comment text without its delimiters, or `None` when the
+    /// trivia is not a documentation comment.
+    ///
+    /// For `///` comments the first space is removed, and for `/** */`
+    /// comments the leading `*` of every line is removed as well.
+    pub fn doc_text(&self) -> Option<String> {
+        let text = self.span.as_str();
+        match self.kind {
+            TriviaKind::DocLineComment => {
+                let text = &text[3..];
+                Some(text.strip_prefix(' ').unwrap_or(text).to_owned())
+            }
+            TriviaKind::DocBlockComment => {
+                let text = &text[3..text.len() - 2];
+                let lines: Vec<&str> = text
+                    .lines()
+                    .map(|line| {
+                        let line = line.trim();
+                        let line = line.strip_prefix('*').unwrap_or(line);
+                        line.strip_prefix(' ').unwrap_or(line)
+                    })
+                    .collect();
+                let start = lines.iter().position(|line| !line.is_empty());
+                let end = lines.iter().rposition(|line| !line.is_empty());
+                match (start, end) {
+                    (Some(start), Some(end)) => Some(lines[start..=end].join("\n")),
+                    _ => Some(String::new()),
+                }
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,7 +1,9 @@
-/// The `LineColumn` stores the line, the column and the index of a
+use serde::Serialize;
+
+/// The `LineColumn` stores the line, the column and the byte index of a
 /// certain source code. It is useful for showing error messages and
 /// warnings.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct LineColumn {
     line: usize,
     column: usize,
@@ -24,18 +26,20 @@ impl LineColumn {
         self.index
     }
 
-    /// Increases the column value in 1 and the index value also to 1.
-    pub(crate) fn next_column(&mut self) {
-        self.column += 1;
-        self.index += 1;
-    }
-
-    /// Increases the line and the index values in 1 and resets the column
-    /// to its default value (1).
-    pub(crate) fn next_line(&mut self) {
-        self.line += 1;
-        self.column = 1;
-        self.index += 1;
+    /// Moves past the given character. A line break moves to the start of
+    /// the next line, any other character moves to the next column.
+    ///
+    /// Columns are counted in characters while the index is counted in
+    /// bytes, so multi-byte characters take one column but advance the
+    /// index by their UTF-8 length.
+    pub(crate) fn advance(&mut self, char: char) {
+        if char == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        self.index += char.len_utf8();
     }
 }
 
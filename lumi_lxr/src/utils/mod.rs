@@ -1,3 +1,6 @@
 pub mod line_column;
+pub mod number;
 pub mod source_code;
 pub mod span;
+pub mod string;
+pub mod symbol;
@@ -0,0 +1,26 @@
+/// Parses the text of a number literal, as accepted by the lexer, into its
+/// value.
+///
+/// Supports decimal literals with an optional fraction and exponent
+/// (`1.5e-9`), hexadecimal (`0xFF`), octal (`0o17`) and binary (`0b1010`)
+/// literals, all of them with optional `_` digit separators.
+///
+/// # Arguments
+/// * `text` - The number literal text.
+pub fn parse_number(text: &str) -> Option<f64> {
+    let text = text.replace('_', "");
+    let radix = match text.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => return text.parse().ok(),
+    };
+    let digits = &text[2..];
+    if digits.is_empty() {
+        return None;
+    }
+    digits.chars().try_fold(0.0, |value, char| {
+        char.to_digit(radix)
+            .map(|digit| value * f64::from(radix) + f64::from(digit))
+    })
+}
@@ -44,6 +44,20 @@ pub struct SourceCode {
 }
 
 impl SourceCode {
+    /// Creates a `SourceCode` instance given its file path and content.
+    ///
+    /// # Arguments
+    /// * `file_path` - The path used when reporting errors.
+    /// * `code` - The source code content.
+    pub fn new(file_path: &str, code: &str) -> Self {
+        Self {
+            inner: Rc::new(InnerSourceCode {
+                file_path: file_path.to_owned(),
+                code: code.to_owned(),
+            }),
+        }
+    }
+
     /// Creates a `SourceCode` instance given its file path.
     ///
     /// # Arguments
@@ -72,7 +86,7 @@ impl SourceCode {
 
 impl PartialEq for SourceCode {
     fn eq(&self, other: &Self) -> bool {
-        self.inner.code == other.inner.code && self.inner.file_path == self.inner.file_path
+        self.inner.code == other.inner.code && self.inner.file_path == other.inner.file_path
     }
 }
 
@@ -1,3 +1,6 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
 use super::line_column::LineColumn;
 use super::source_code::SourceCode;
 
@@ -53,7 +56,12 @@ impl Span {
 
     /// Gets the span text.
     pub fn source_text(&self) -> String {
-        String::from(&self.source_code[self.start..self.end])
+        String::from(self.as_str())
+    }
+
+    /// Gets the span text without allocating.
+    pub fn as_str(&self) -> &str {
+        &self.source_code[self.start..self.end]
     }
 
     pub fn source_code(&self) -> &SourceCode {
@@ -61,6 +69,17 @@ impl Span {
     }
 }
 
+/// Spans are serialized as their `start` and `end` positions, leaving the
+/// source code out.
+impl Serialize for Span {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut span = serializer.serialize_struct("Span", 2)?;
+        span.serialize_field("start", &self.start)?;
+        span.serialize_field("end", &self.end)?;
+        span.end()
+    }
+}
+
 #[macro_export]
 macro_rules! span {
     ($t:ident) => {
@@ -0,0 +1,35 @@
+/// Parses the text of a string literal, as accepted by the lexer, into its
+/// value.
+///
+/// The text includes the surrounding quotes. Supports the `\n`, `\t`,
+/// `\r`, `\0`, `\\` and `\"` escapes.
+///
+/// # Arguments
+/// * `text` - The string literal text.
+pub fn parse_string(text: &str) -> Option<String> {
+    let text = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            value.push(char);
+            continue;
+        }
+        value.push(escape(chars.next()?)?);
+    }
+    Some(value)
+}
+
+/// Gets the character written by an escape sequence, given the character
+/// following the `\`.
+pub(crate) fn escape(char: char) -> Option<char> {
+    match char {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' => Some('\\'),
+        '"' => Some('"'),
+        _ => None,
+    }
+}
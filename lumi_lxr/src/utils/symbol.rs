@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+
+use serde::{Serialize, Serializer};
+
+/// The `Symbol` is an interned string, usually an identifier.
+///
+/// Comparing and hashing symbols is as cheap as comparing and hashing a
+/// `u32`, which makes them a good fit for keys on hot paths such as scope
+/// and method lookups.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Gets the symbol of the given string, interning it if needed.
+    pub fn intern(string: &str) -> Self {
+        INTERNER.with(|interner| interner.borrow_mut().intern(string))
+    }
+
+    /// Gets the interned string.
+    pub fn as_str(&self) -> &'static str {
+        INTERNER.with(|interner| interner.borrow().get(*self))
+    }
+
+    /// Gets the symbol index.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The `Interner` stores every interned string once. Strings are leaked so
+/// they can be handed out as `&'static str`; they live as long as the
+/// program anyway.
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+impl Interner {
+    fn prefilled(strings: &[&'static str]) -> Self {
+        let mut interner = Self {
+            symbols: HashMap::new(),
+            strings: vec![],
+        };
+        for string in strings {
+            interner.intern(string);
+        }
+        interner
+    }
+
+    fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(string) {
+            *symbol
+        } else {
+            let string: &'static str = Box::leak(string.to_owned().into_boxed_str());
+            let symbol = Symbol(self.strings.len() as u32);
+            self.strings.push(string);
+            self.symbols.insert(string, symbol);
+            symbol
+        }
+    }
+
+    fn get(&self, symbol: Symbol) -> &'static str {
+        self.strings[symbol.0 as usize]
+    }
+}
+
+macro_rules! predefined_symbols {
+    ($($name:ident: $string:literal),* $(,)?) => {
+        /// Symbols interned up front, so they can be used without a lookup.
+        #[allow(non_upper_case_globals)]
+        pub mod sym {
+            use super::Symbol;
+
+            predefined_symbols!(@consts 0u32, $($name,)*);
+        }
+
+        thread_local! {
+            static INTERNER: RefCell<Interner> = RefCell::new(Interner::prefilled(&[$($string),*]));
+        }
+    };
+    (@consts $index:expr, $name:ident, $($rest:ident,)*) => {
+        pub const $name: Symbol = Symbol($index);
+        predefined_symbols!(@consts $index + 1u32, $($rest,)*);
+    };
+    (@consts $index:expr,) => {};
+}
+
+predefined_symbols! {
+    this: "this",
+    This: "This",
+    other: "other",
+    add: "add",
+    sub: "sub",
+    mul: "mul",
+    div: "div",
+    rem: "rem",
+    eq: "eq",
+    not: "not",
+    toBool: "toBool",
+    clock: "clock",
+    Nil: "Nil",
+    Bool: "Bool",
+    Num: "Num",
+    Str: "Str",
+    Error: "Error",
+    message: "message",
+    trace: "trace",
+    Result: "Result",
+    Option: "Option",
+    value: "value",
+    error: "error",
+    ok: "ok",
+    some: "some",
+    List: "List",
+    ListNode: "ListNode",
+    head: "head",
+    next: "next",
+    length: "length",
+    state: "state",
+}
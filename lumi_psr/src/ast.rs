@@ -1,13 +1,16 @@
+use std::io::{self, Write};
+
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::DisplayTree;
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::stmts::Stmt;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Ast {
-    stmts: Vec<Stmt>,
+    pub(crate) stmts: Vec<Stmt>,
 }
 
 impl Ast {
@@ -31,9 +34,10 @@ impl Parse for Ast {
 }
 
 impl DisplayTree for Ast {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         for stmt in &self.stmts {
-            stmt.display(layer)
+            stmt.display(f, layer)?;
         }
+        Ok(())
     }
 }
@@ -1,7 +1,9 @@
+use std::io::{self, Write};
+
 pub trait DisplayTree {
-    fn display(&self, layer: usize);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()>;
 }
 
-pub fn branch(branch_name: &str, layer: usize) {
-    println!("{}", format!("{}├── {}", "│   ".repeat(layer), branch_name));
+pub fn branch(f: &mut dyn Write, branch_name: &str, layer: usize) -> io::Result<()> {
+    writeln!(f, "{}├── {}", "│   ".repeat(layer), branch_name)
 }
@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+
+use lumi_lxr::trivia::Trivia;
+use serde::Serialize;
+
+use crate::display_tree::{branch, DisplayTree};
+use crate::parse::Parse;
+use crate::parser::{ParseError, ParseStream};
+
+/// The `Docs` stores the documentation comments (`///` and `/** */`)
+/// written right before an item.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Docs {
+    pub(crate) comments: Vec<Trivia>,
+}
+
+impl Docs {
+    pub fn comments(&self) -> &Vec<Trivia> {
+        &self.comments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty()
+    }
+
+    /// Gets the documentation text, without the comment delimiters, or
+    /// `None` if the item is not documented.
+    pub fn text(&self) -> Option<String> {
+        if self.comments.is_empty() {
+            None
+        } else {
+            Some(
+                self.comments
+                    .iter()
+                    .filter_map(|comment| comment.doc_text())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        }
+    }
+}
+
+/// Reads the documentation comments attached to the next token, without
+/// consuming it.
+impl Parse for Docs {
+    fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        Ok(Self {
+            comments: input
+                .peek()
+                .trivia()
+                .iter()
+                .filter(|trivia| trivia.is_doc())
+                .cloned()
+                .collect(),
+        })
+    }
+}
+
+impl DisplayTree for Docs {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        if let Some(text) = self.text() {
+            branch(f, &format!("Docs: {:?}", text), layer)?;
+        }
+        Ok(())
+    }
+}
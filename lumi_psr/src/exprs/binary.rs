@@ -1,4 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::parse::Parse;
@@ -8,7 +12,7 @@ use super::Expr;
 
 #[derive(Debug)]
 pub struct BinaryOp {
-    span: Span,
+    pub(crate) span: Span,
 }
 
 impl BinaryOp {
@@ -29,18 +33,27 @@ impl Parse for BinaryOp {
     }
 }
 
+impl Serialize for BinaryOp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut op = serializer.serialize_struct("BinaryOp", 2)?;
+        op.serialize_field("op", self.span.as_str())?;
+        op.serialize_field("span", &self.span)?;
+        op.end()
+    }
+}
+
 impl DisplayTree for BinaryOp {
-    fn display(&self, layer: usize) {
-        branch(&format!("BinaryOp: {}", self.source_text()), layer)
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, &format!("BinaryOp: {}", self.source_text()), layer)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct BinaryExpr {
-    span: Span,
-    left: Box<Expr>,
-    op: BinaryOp,
-    right: Box<Expr>,
+    pub(crate) span: Span,
+    pub(crate) left: Box<Expr>,
+    pub(crate) op: BinaryOp,
+    pub(crate) right: Box<Expr>,
 }
 
 impl BinaryExpr {
@@ -71,10 +84,11 @@ impl BinaryExpr {
 }
 
 impl DisplayTree for BinaryExpr {
-    fn display(&self, layer: usize) {
-        branch("BinaryExpr", layer);
-        self.left.display(layer + 1);
-        self.op.display(layer + 1);
-        self.right.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "BinaryExpr", layer)?;
+        self.left.display(f, layer + 1)?;
+        self.op.display(f, layer + 1)?;
+        self.right.display(f, layer + 1)?;
+        Ok(())
     }
 }
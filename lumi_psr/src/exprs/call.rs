@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::branch;
 use crate::display_tree::DisplayTree;
@@ -20,6 +23,9 @@ impl Parse for Vec<Expr> {
             args.push(input.parse()?);
             while input.peek().kind() != TokenKind::RightParen {
                 input.expect(TokenKind::Comma)?;
+                if input.peek().kind() == TokenKind::RightParen {
+                    break;
+                }
                 args.push(input.parse()?);
             }
             Ok(args)
@@ -28,18 +34,19 @@ impl Parse for Vec<Expr> {
 }
 
 impl DisplayTree for Vec<Expr> {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         for expr in self {
-            expr.display(layer);
+            expr.display(f, layer)?;
         }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct CallExpr {
-    span: Span,
-    callee: Box<Expr>,
-    args: Vec<Expr>,
+    pub(crate) span: Span,
+    pub(crate) callee: Box<Expr>,
+    pub(crate) args: Vec<Expr>,
 }
 
 impl CallExpr {
@@ -70,12 +77,13 @@ impl CallExpr {
 }
 
 impl DisplayTree for CallExpr {
-    fn display(&self, layer: usize) {
-        branch("CallExpr", layer);
-        self.callee.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "CallExpr", layer)?;
+        self.callee.display(f, layer + 1)?;
         if !self.args.is_empty() {
-            branch("Args", layer + 1);
-            self.args.display(layer + 2);
+            branch(f, "Args", layer + 1)?;
+            self.args.display(f, layer + 2)?;
         }
+        Ok(())
     }
 }
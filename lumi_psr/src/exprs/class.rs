@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::parse::Parse;
@@ -8,11 +11,11 @@ use crate::symbols::{Colon, Ident, LeftBrace, RightBrace};
 
 use super::Expr;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Field {
-    span: Span,
-    ident: Ident,
-    value: Option<Expr>,
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) value: Option<Expr>,
 }
 
 impl Field {
@@ -51,12 +54,13 @@ impl Parse for Field {
 }
 
 impl DisplayTree for Field {
-    fn display(&self, layer: usize) {
-        branch("Field", layer);
-        self.ident.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "Field", layer)?;
+        self.ident.display(f, layer + 1)?;
         if let Some(value) = &self.value {
-            value.display(layer + 1);
+            value.display(f, layer + 1)?;
         }
+        Ok(())
     }
 }
 
@@ -69,6 +73,9 @@ impl Parse for Vec<Field> {
             fields.push(input.parse()?);
             while input.peek().kind() != TokenKind::RightBrace {
                 input.expect(TokenKind::Comma)?;
+                if input.peek().kind() == TokenKind::RightBrace {
+                    break;
+                }
                 fields.push(input.parse()?);
             }
             Ok(fields)
@@ -77,21 +84,22 @@ impl Parse for Vec<Field> {
 }
 
 impl DisplayTree for Vec<Field> {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         if !self.is_empty() {
-            branch("Fields", layer);
+            branch(f, "Fields", layer)?;
             for field in self {
-                field.display(layer + 1);
+                field.display(f, layer + 1)?;
             }
         }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ClassExpr {
-    span: Span,
-    cls: Box<Expr>,
-    fields: Vec<Field>,
+    pub(crate) span: Span,
+    pub(crate) cls: Box<Expr>,
+    pub(crate) fields: Vec<Field>,
 }
 
 impl ClassExpr {
@@ -122,9 +130,10 @@ impl ClassExpr {
 }
 
 impl DisplayTree for ClassExpr {
-    fn display(&self, layer: usize) {
-        branch("ClassExpr", layer);
-        self.cls.display(layer + 1);
-        self.fields.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ClassExpr", layer)?;
+        self.cls.display(f, layer + 1)?;
+        self.fields.display(f, layer + 1)?;
+        Ok(())
     }
 }
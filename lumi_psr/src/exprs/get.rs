@@ -1,15 +1,19 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
-use crate::symbols::{Dot, Ident};
+use crate::symbols::{Dot, Ident, InterrogationDot};
 
 use super::Expr;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct GetExpr {
-    span: Span,
-    expr: Box<Expr>,
-    ident: Ident,
+    pub(crate) span: Span,
+    pub(crate) expr: Box<Expr>,
+    pub(crate) ident: Ident,
+    pub(crate) is_safe: bool,
 }
 
 impl GetExpr {
@@ -18,6 +22,18 @@ impl GetExpr {
             span: Span::range(expr.span(), ident.span()),
             expr: Box::new(expr),
             ident,
+            is_safe: false,
+        }
+    }
+
+    /// Creates a safe property access, `a?.b`, which gives `nil` instead
+    /// of reading the property when `a` is `nil`.
+    pub(crate) fn safe(expr: Expr, _interrogation_dot: InterrogationDot, ident: Ident) -> Self {
+        Self {
+            span: Span::range(expr.span(), ident.span()),
+            expr: Box::new(expr),
+            ident,
+            is_safe: true,
         }
     }
 
@@ -32,12 +48,22 @@ impl GetExpr {
     pub fn ident(&self) -> &Ident {
         &self.ident
     }
+
+    pub fn is_safe(&self) -> bool {
+        self.is_safe
+    }
 }
 
 impl DisplayTree for GetExpr {
-    fn display(&self, layer: usize) {
-        branch("GetExpr", layer);
-        self.ident.display(layer + 1);
-        self.expr.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        let name = if self.is_safe {
+            "SafeGetExpr"
+        } else {
+            "GetExpr"
+        };
+        branch(f, name, layer)?;
+        self.ident.display(f, layer + 1)?;
+        self.expr.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,12 +1,15 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::ident;
 use crate::symbols::Ident;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct IdentExpr {
-    ident: Ident,
+    pub(crate) ident: Ident,
 }
 
 ident!(IdentExpr);
@@ -22,7 +25,12 @@ impl IdentExpr {
 }
 
 impl DisplayTree for IdentExpr {
-    fn display(&self, layer: usize) {
-        branch(&format!("IdentExpr: {}", self.ident.source_text()), layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(
+            f,
+            &format!("IdentExpr: {}", self.ident.source_text()),
+            layer,
+        )?;
+        Ok(())
     }
 }
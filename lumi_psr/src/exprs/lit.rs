@@ -1,4 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 
 use crate::display_tree::branch;
 use crate::display_tree::DisplayTree;
@@ -8,6 +12,7 @@ pub enum LitExpr {
     Num { span: Span },
     Bool { span: Span },
     Nil { span: Span },
+    Str { span: Span },
 }
 
 impl LitExpr {
@@ -29,17 +34,41 @@ impl LitExpr {
         }
     }
 
+    pub fn str(span: &Span) -> Self {
+        Self::Str {
+            span: Span::from(span),
+        }
+    }
+
     pub fn span(&self) -> &Span {
         match self {
             Self::Num { span } => span,
             Self::Bool { span } => span,
             Self::Nil { span } => span,
+            Self::Str { span } => span,
         }
     }
 }
 
+/// Literals are serialized with their text, so `0xFF` is kept as written.
+impl Serialize for LitExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let lit = match self {
+            Self::Num { .. } => "Num",
+            Self::Bool { .. } => "Bool",
+            Self::Nil { .. } => "Nil",
+            Self::Str { .. } => "Str",
+        };
+        let mut state = serializer.serialize_struct("LitExpr", 3)?;
+        state.serialize_field("lit", lit)?;
+        state.serialize_field("text", self.span().as_str())?;
+        state.serialize_field("span", self.span())?;
+        state.end()
+    }
+}
+
 impl DisplayTree for LitExpr {
-    fn display(&self, layer: usize) {
-        branch(&format!("ExprLit: {}", self.span().source_text()), layer)
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, &format!("ExprLit: {}", self.span().source_text()), layer)
     }
 }
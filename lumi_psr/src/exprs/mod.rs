@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::DisplayTree;
 use crate::parse::Parse;
@@ -13,7 +16,10 @@ use self::get::GetExpr;
 use self::ident::IdentExpr;
 use self::lit::LitExpr;
 use self::paren::ParenExpr;
+use self::path::PathExpr;
+use self::r#try::TryExpr;
 use self::unary::UnaryExpr;
+use self::unwrap::UnwrapExpr;
 
 pub mod binary;
 pub mod call;
@@ -22,9 +28,13 @@ pub mod get;
 pub mod ident;
 pub mod lit;
 pub mod paren;
+pub mod path;
+pub mod r#try;
 pub mod unary;
+pub mod unwrap;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
 pub enum Expr {
     Ident(IdentExpr),
     Lit(LitExpr),
@@ -34,6 +44,9 @@ pub enum Expr {
     Class(ClassExpr),
     Call(CallExpr),
     Get(GetExpr),
+    Path(PathExpr),
+    Unwrap(UnwrapExpr),
+    Try(TryExpr),
 }
 
 impl Expr {
@@ -47,6 +60,9 @@ impl Expr {
             Self::Class(cls) => cls.span(),
             Self::Call(call) => call.span(),
             Self::Get(get) => get.span(),
+            Self::Path(path) => path.span(),
+            Self::Unwrap(unwrap) => unwrap.span(),
+            Self::Try(r#try) => r#try.span(),
         }
     }
 
@@ -62,16 +78,19 @@ impl Parse for Expr {
 }
 
 impl DisplayTree for Expr {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         match self {
-            Self::Ident(ident) => ident.display(layer),
-            Self::Lit(lit) => lit.display(layer),
-            Self::Paren(paren) => paren.display(layer),
-            Self::Unary(unary) => unary.display(layer),
-            Self::Binary(binary) => binary.display(layer),
-            Self::Class(cls) => cls.display(layer),
-            Self::Call(call) => call.display(layer),
-            Self::Get(get) => get.display(layer),
+            Self::Ident(ident) => ident.display(f, layer),
+            Self::Lit(lit) => lit.display(f, layer),
+            Self::Paren(paren) => paren.display(f, layer),
+            Self::Unary(unary) => unary.display(f, layer),
+            Self::Binary(binary) => binary.display(f, layer),
+            Self::Class(cls) => cls.display(f, layer),
+            Self::Call(call) => call.display(f, layer),
+            Self::Get(get) => get.display(f, layer),
+            Self::Path(path) => path.display(f, layer),
+            Self::Unwrap(unwrap) => unwrap.display(f, layer),
+            Self::Try(r#try) => r#try.display(f, layer),
         }
     }
 }
@@ -81,7 +100,7 @@ fn ambiguous_expr(input: &mut ParseStream, allow_class: bool) -> Result<Expr, Pa
 }
 
 fn assignment(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError> {
-    let mut left = equality(input, allow_class)?;
+    let mut left = coalesce(input, allow_class)?;
     if input.peek().kind() == TokenKind::Equal {
         let operator = input.parse()?;
         let right = assignment(input, allow_class)?;
@@ -90,6 +109,36 @@ fn assignment(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseE
     Ok(left)
 }
 
+fn coalesce(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError> {
+    let mut left = or(input, allow_class)?;
+    while input.peek().kind() == TokenKind::InterrogationInterrogation {
+        let operator = input.parse()?;
+        let right = or(input, allow_class)?;
+        left = Expr::Binary(BinaryExpr::new(left, operator, right));
+    }
+    Ok(left)
+}
+
+fn or(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError> {
+    let mut left = and(input, allow_class)?;
+    while input.peek().kind() == TokenKind::PipePipe {
+        let operator = input.parse()?;
+        let right = and(input, allow_class)?;
+        left = Expr::Binary(BinaryExpr::new(left, operator, right));
+    }
+    Ok(left)
+}
+
+fn and(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError> {
+    let mut left = equality(input, allow_class)?;
+    while input.peek().kind() == TokenKind::AmpersandAmpersand {
+        let operator = input.parse()?;
+        let right = equality(input, allow_class)?;
+        left = Expr::Binary(BinaryExpr::new(left, operator, right));
+    }
+    Ok(left)
+}
+
 fn equality(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError> {
     let mut left = comparison(input, allow_class)?;
     while matches!(
@@ -139,7 +188,10 @@ fn term(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError>
 
 fn factor(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError> {
     let mut left = unary(input, allow_class)?;
-    while matches!(input.peek().kind(), TokenKind::Star | TokenKind::Slash) {
+    while matches!(
+        input.peek().kind(),
+        TokenKind::Star | TokenKind::Slash | TokenKind::Percent
+    ) {
         let operator = input.parse()?;
         let right = unary(input, allow_class)?;
         left = Expr::Binary(BinaryExpr::new(left, operator, right));
@@ -163,10 +215,7 @@ fn unary(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError>
 
 fn call(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError> {
     let mut expr = primary(input)?;
-    while matches!(
-        input.peek().kind(),
-        TokenKind::Dot | TokenKind::LeftParen | TokenKind::LeftBrace
-    ) {
+    loop {
         expr = match input.peek().kind() {
             TokenKind::LeftParen => Expr::Call(CallExpr::new(
                 expr,
@@ -175,17 +224,20 @@ fn call(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError>
                 input.parse()?,
             )),
             TokenKind::Dot => Expr::Get(GetExpr::new(expr, input.parse()?, input.parse()?)),
-            _ => {
-                if !allow_class {
-                    break;
-                }
-                Expr::Class(ClassExpr::new(
-                    expr,
-                    input.parse()?,
-                    input.parse()?,
-                    input.parse()?,
-                ))
+            TokenKind::InterrogationDot => {
+                Expr::Get(GetExpr::safe(expr, input.parse()?, input.parse()?))
             }
+            TokenKind::Bang if input.peek2().kind() == TokenKind::Bang => {
+                Expr::Unwrap(UnwrapExpr::new(expr, input.parse()?, input.parse()?))
+            }
+            TokenKind::Interrogation => Expr::Try(TryExpr::new(expr, input.parse()?)),
+            TokenKind::LeftBrace if allow_class => Expr::Class(ClassExpr::new(
+                expr,
+                input.parse()?,
+                input.parse()?,
+                input.parse()?,
+            )),
+            _ => break,
         }
     }
     Ok(expr)
@@ -193,9 +245,21 @@ fn call(input: &mut ParseStream, allow_class: bool) -> Result<Expr, ParseError>
 
 fn primary(input: &mut ParseStream) -> Result<Expr, ParseError> {
     match input.peek().kind() {
-        TokenKind::Ident => Ok(Expr::Ident(IdentExpr::new(input.parse()?))),
+        TokenKind::Ident => {
+            let ident = input.parse()?;
+            if input.peek().kind() == TokenKind::ColonColon {
+                Ok(Expr::Path(PathExpr::new(
+                    ident,
+                    input.parse()?,
+                    input.parse()?,
+                )))
+            } else {
+                Ok(Expr::Ident(IdentExpr::new(ident)))
+            }
+        }
         TokenKind::Number => Ok(Expr::Lit(LitExpr::num(input.next().span()))),
         TokenKind::Nil => Ok(Expr::Lit(LitExpr::nil(input.next().span()))),
+        TokenKind::String => Ok(Expr::Lit(LitExpr::str(input.next().span()))),
         TokenKind::True | TokenKind::False => Ok(Expr::Lit(LitExpr::bool(input.next().span()))),
         TokenKind::LeftParen => Ok(Expr::Paren(ParenExpr::new(
             input.parse()?,
@@ -1,4 +1,7 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::{
     display_tree::DisplayTree,
@@ -7,10 +10,10 @@ use crate::{
 
 use super::Expr;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ParenExpr {
-    span: Span,
-    expr: Box<Expr>,
+    pub(crate) span: Span,
+    pub(crate) expr: Box<Expr>,
 }
 
 impl ParenExpr {
@@ -31,7 +34,7 @@ impl ParenExpr {
 }
 
 impl DisplayTree for ParenExpr {
-    fn display(&self, layer: usize) {
-        self.expr.display(layer)
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        self.expr.display(f, layer)
     }
 }
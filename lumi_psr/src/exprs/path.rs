@@ -0,0 +1,46 @@
+use std::io::{self, Write};
+
+use lumi_lxr::span::Span;
+use serde::Serialize;
+
+use crate::display_tree::{branch, DisplayTree};
+use crate::symbols::{ColonColon, Ident};
+
+/// The `PathExpr` reads a static method of a class, as in `Point::new`.
+#[derive(Debug, Serialize)]
+pub struct PathExpr {
+    pub(crate) span: Span,
+    pub(crate) ty: Ident,
+    pub(crate) ident: Ident,
+}
+
+impl PathExpr {
+    pub(crate) fn new(ty: Ident, _colon_colon: ColonColon, ident: Ident) -> Self {
+        Self {
+            span: Span::range(ty.span(), ident.span()),
+            ty,
+            ident,
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn ty(&self) -> &Ident {
+        &self.ty
+    }
+
+    pub fn ident(&self) -> &Ident {
+        &self.ident
+    }
+}
+
+impl DisplayTree for PathExpr {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "PathExpr", layer)?;
+        self.ty.display(f, layer + 1)?;
+        self.ident.display(f, layer + 1)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use std::io::{self, Write};
+
+use lumi_lxr::span::Span;
+use serde::Serialize;
+
+use crate::display_tree::{branch, DisplayTree};
+use crate::symbols::Interrogation;
+
+use super::Expr;
+
+/// The `TryExpr` gets the value of a `Result` or an `Option`, as in `a?`,
+/// returning it from the enclosing function when it holds an error or
+/// nothing.
+#[derive(Debug, Serialize)]
+pub struct TryExpr {
+    pub(crate) span: Span,
+    pub(crate) expr: Box<Expr>,
+}
+
+impl TryExpr {
+    pub(crate) fn new(expr: Expr, interrogation: Interrogation) -> Self {
+        Self {
+            span: Span::range(expr.span(), interrogation.span()),
+            expr: Box::new(expr),
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn expr(&self) -> &Expr {
+        self.expr.as_ref()
+    }
+}
+
+impl DisplayTree for TryExpr {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "TryExpr", layer)?;
+        self.expr.display(f, layer + 1)?;
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::parse::Parse;
@@ -8,7 +12,7 @@ use super::Expr;
 
 #[derive(Debug)]
 pub struct UnaryOp {
-    span: Span,
+    pub(crate) span: Span,
 }
 
 impl UnaryOp {
@@ -29,17 +33,26 @@ impl Parse for UnaryOp {
     }
 }
 
+impl Serialize for UnaryOp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut op = serializer.serialize_struct("UnaryOp", 2)?;
+        op.serialize_field("op", self.span.as_str())?;
+        op.serialize_field("span", &self.span)?;
+        op.end()
+    }
+}
+
 impl DisplayTree for UnaryOp {
-    fn display(&self, layer: usize) {
-        branch(&format!("UnaryOp: {}", self.source_text()), layer)
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, &format!("UnaryOp: {}", self.source_text()), layer)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct UnaryExpr {
-    span: Span,
-    op: UnaryOp,
-    expr: Box<Expr>,
+    pub(crate) span: Span,
+    pub(crate) op: UnaryOp,
+    pub(crate) expr: Box<Expr>,
 }
 
 impl UnaryExpr {
@@ -65,9 +78,10 @@ impl UnaryExpr {
 }
 
 impl DisplayTree for UnaryExpr {
-    fn display(&self, layer: usize) {
-        branch("UnaryExpr", layer);
-        self.op.display(layer + 1);
-        self.expr.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "UnaryExpr", layer)?;
+        self.op.display(f, layer + 1)?;
+        self.expr.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -0,0 +1,42 @@
+use std::io::{self, Write};
+
+use lumi_lxr::span::Span;
+use serde::Serialize;
+
+use crate::display_tree::{branch, DisplayTree};
+use crate::symbols::Bang;
+
+use super::Expr;
+
+/// The `UnwrapExpr` asserts that a nullable value isn't `nil`, as in
+/// `a!!`, failing at runtime when it is.
+#[derive(Debug, Serialize)]
+pub struct UnwrapExpr {
+    pub(crate) span: Span,
+    pub(crate) expr: Box<Expr>,
+}
+
+impl UnwrapExpr {
+    pub(crate) fn new(expr: Expr, _bang: Bang, bang: Bang) -> Self {
+        Self {
+            span: Span::range(expr.span(), bang.span()),
+            expr: Box::new(expr),
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn expr(&self) -> &Expr {
+        self.expr.as_ref()
+    }
+}
+
+impl DisplayTree for UnwrapExpr {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "UnwrapExpr", layer)?;
+        self.expr.display(f, layer + 1)?;
+        Ok(())
+    }
+}
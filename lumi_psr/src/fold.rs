@@ -0,0 +1,779 @@
+//! Rewriting of the syntax tree by value.
+//!
+//! Every method of `Fold` has a default implementation that calls the free
+//! function with the same name, which rebuilds the node from its folded
+//! children. Spans are kept as they were, so errors found in a rewritten
+//! tree still point to the original source code.
+
+use crate::ast::Ast;
+use crate::docs::Docs;
+use crate::exprs::binary::{BinaryExpr, BinaryOp};
+use crate::exprs::call::CallExpr;
+use crate::exprs::class::{ClassExpr, Field as FieldExpr};
+use crate::exprs::get::GetExpr;
+use crate::exprs::ident::IdentExpr;
+use crate::exprs::lit::LitExpr;
+use crate::exprs::paren::ParenExpr;
+use crate::exprs::path::PathExpr;
+use crate::exprs::r#try::TryExpr;
+use crate::exprs::unary::{UnaryExpr, UnaryOp};
+use crate::exprs::unwrap::UnwrapExpr;
+use crate::exprs::Expr;
+use crate::generics::{GenericParam, Generics};
+use crate::param::Param;
+use crate::stmts::block::BlockStmt;
+use crate::stmts::class::{ClassStmt, Field};
+use crate::stmts::expr::ExprStmt;
+use crate::stmts::fun::FunStmt;
+use crate::stmts::println::PrintlnStmt;
+use crate::stmts::r#break::BreakStmt;
+use crate::stmts::r#const::{ConstStmt, ConstType};
+use crate::stmts::r#continue::ContinueStmt;
+use crate::stmts::r#for::ForStmt;
+use crate::stmts::r#if::IfStmt;
+use crate::stmts::r#impl::{ImplStmt, Method};
+use crate::stmts::r#let::LetStmt;
+use crate::stmts::r#return::ReturnStmt;
+use crate::stmts::r#trait::{ProtoMethod, TraitStmt};
+use crate::stmts::r#try::{CatchClause, TryStmt};
+use crate::stmts::r#while::WhileStmt;
+use crate::stmts::throw::ThrowStmt;
+use crate::stmts::Stmt;
+use crate::symbols::Ident;
+use crate::ty::Type;
+
+pub trait Fold {
+    fn fold_ast(&mut self, node: Ast) -> Ast {
+        fold_ast(self, node)
+    }
+
+    fn fold_stmt(&mut self, node: Stmt) -> Stmt {
+        fold_stmt(self, node)
+    }
+
+    fn fold_block_stmt(&mut self, node: BlockStmt) -> BlockStmt {
+        fold_block_stmt(self, node)
+    }
+
+    fn fold_println_stmt(&mut self, node: PrintlnStmt) -> PrintlnStmt {
+        fold_println_stmt(self, node)
+    }
+
+    fn fold_let_stmt(&mut self, node: LetStmt) -> LetStmt {
+        fold_let_stmt(self, node)
+    }
+
+    fn fold_const_stmt(&mut self, node: ConstStmt) -> ConstStmt {
+        fold_const_stmt(self, node)
+    }
+
+    fn fold_if_stmt(&mut self, node: IfStmt) -> IfStmt {
+        fold_if_stmt(self, node)
+    }
+
+    fn fold_while_stmt(&mut self, node: WhileStmt) -> WhileStmt {
+        fold_while_stmt(self, node)
+    }
+
+    fn fold_for_stmt(&mut self, node: ForStmt) -> ForStmt {
+        fold_for_stmt(self, node)
+    }
+
+    fn fold_class_stmt(&mut self, node: ClassStmt) -> ClassStmt {
+        fold_class_stmt(self, node)
+    }
+
+    fn fold_field(&mut self, node: Field) -> Field {
+        fold_field(self, node)
+    }
+
+    fn fold_trait_stmt(&mut self, node: TraitStmt) -> TraitStmt {
+        fold_trait_stmt(self, node)
+    }
+
+    fn fold_proto_method(&mut self, node: ProtoMethod) -> ProtoMethod {
+        fold_proto_method(self, node)
+    }
+
+    fn fold_impl_stmt(&mut self, node: ImplStmt) -> ImplStmt {
+        fold_impl_stmt(self, node)
+    }
+
+    fn fold_method(&mut self, node: Method) -> Method {
+        fold_method(self, node)
+    }
+
+    fn fold_expr_stmt(&mut self, node: ExprStmt) -> ExprStmt {
+        fold_expr_stmt(self, node)
+    }
+
+    fn fold_fun_stmt(&mut self, node: FunStmt) -> FunStmt {
+        fold_fun_stmt(self, node)
+    }
+
+    fn fold_continue_stmt(&mut self, node: ContinueStmt) -> ContinueStmt {
+        fold_continue_stmt(self, node)
+    }
+
+    fn fold_break_stmt(&mut self, node: BreakStmt) -> BreakStmt {
+        fold_break_stmt(self, node)
+    }
+
+    fn fold_return_stmt(&mut self, node: ReturnStmt) -> ReturnStmt {
+        fold_return_stmt(self, node)
+    }
+
+    fn fold_throw_stmt(&mut self, node: ThrowStmt) -> ThrowStmt {
+        fold_throw_stmt(self, node)
+    }
+
+    fn fold_try_stmt(&mut self, node: TryStmt) -> TryStmt {
+        fold_try_stmt(self, node)
+    }
+
+    fn fold_catch_clause(&mut self, node: CatchClause) -> CatchClause {
+        fold_catch_clause(self, node)
+    }
+
+    fn fold_expr(&mut self, node: Expr) -> Expr {
+        fold_expr(self, node)
+    }
+
+    fn fold_ident_expr(&mut self, node: IdentExpr) -> IdentExpr {
+        fold_ident_expr(self, node)
+    }
+
+    fn fold_lit_expr(&mut self, node: LitExpr) -> LitExpr {
+        fold_lit_expr(self, node)
+    }
+
+    fn fold_paren_expr(&mut self, node: ParenExpr) -> ParenExpr {
+        fold_paren_expr(self, node)
+    }
+
+    fn fold_unary_expr(&mut self, node: UnaryExpr) -> UnaryExpr {
+        fold_unary_expr(self, node)
+    }
+
+    fn fold_unary_op(&mut self, node: UnaryOp) -> UnaryOp {
+        fold_unary_op(self, node)
+    }
+
+    fn fold_binary_expr(&mut self, node: BinaryExpr) -> BinaryExpr {
+        fold_binary_expr(self, node)
+    }
+
+    fn fold_binary_op(&mut self, node: BinaryOp) -> BinaryOp {
+        fold_binary_op(self, node)
+    }
+
+    fn fold_class_expr(&mut self, node: ClassExpr) -> ClassExpr {
+        fold_class_expr(self, node)
+    }
+
+    fn fold_field_expr(&mut self, node: FieldExpr) -> FieldExpr {
+        fold_field_expr(self, node)
+    }
+
+    fn fold_call_expr(&mut self, node: CallExpr) -> CallExpr {
+        fold_call_expr(self, node)
+    }
+
+    fn fold_get_expr(&mut self, node: GetExpr) -> GetExpr {
+        fold_get_expr(self, node)
+    }
+
+    fn fold_path_expr(&mut self, node: PathExpr) -> PathExpr {
+        fold_path_expr(self, node)
+    }
+
+    fn fold_unwrap_expr(&mut self, node: UnwrapExpr) -> UnwrapExpr {
+        fold_unwrap_expr(self, node)
+    }
+
+    fn fold_try_expr(&mut self, node: TryExpr) -> TryExpr {
+        fold_try_expr(self, node)
+    }
+
+    fn fold_param(&mut self, node: Param) -> Param {
+        fold_param(self, node)
+    }
+
+    fn fold_type(&mut self, node: Type) -> Type {
+        fold_type(self, node)
+    }
+
+    fn fold_generics(&mut self, node: Generics) -> Generics {
+        fold_generics(self, node)
+    }
+
+    fn fold_generic_param(&mut self, node: GenericParam) -> GenericParam {
+        fold_generic_param(self, node)
+    }
+
+    fn fold_ident(&mut self, node: Ident) -> Ident {
+        fold_ident(self, node)
+    }
+
+    fn fold_docs(&mut self, node: Docs) -> Docs {
+        fold_docs(self, node)
+    }
+}
+
+pub fn fold_ast<F: Fold + ?Sized>(f: &mut F, node: Ast) -> Ast {
+    Ast {
+        stmts: node
+            .stmts
+            .into_iter()
+            .map(|stmt| f.fold_stmt(stmt))
+            .collect(),
+    }
+}
+
+pub fn fold_stmt<F: Fold + ?Sized>(f: &mut F, node: Stmt) -> Stmt {
+    match node {
+        Stmt::Println(println) => Stmt::Println(f.fold_println_stmt(println)),
+        Stmt::Block(block) => Stmt::Block(f.fold_block_stmt(block)),
+        Stmt::Let(r#let) => Stmt::Let(f.fold_let_stmt(r#let)),
+        Stmt::Const(r#const) => Stmt::Const(f.fold_const_stmt(r#const)),
+        Stmt::If(r#if) => Stmt::If(f.fold_if_stmt(r#if)),
+        Stmt::While(r#while) => Stmt::While(f.fold_while_stmt(r#while)),
+        Stmt::For(r#for) => Stmt::For(f.fold_for_stmt(r#for)),
+        Stmt::Class(class) => Stmt::Class(f.fold_class_stmt(class)),
+        Stmt::Trait(r#trait) => Stmt::Trait(f.fold_trait_stmt(r#trait)),
+        Stmt::Impl(r#impl) => Stmt::Impl(f.fold_impl_stmt(r#impl)),
+        Stmt::Expr(expr) => Stmt::Expr(f.fold_expr_stmt(expr)),
+        Stmt::Fun(fun) => Stmt::Fun(f.fold_fun_stmt(fun)),
+        Stmt::Continue(r#continue) => Stmt::Continue(f.fold_continue_stmt(r#continue)),
+        Stmt::Break(r#break) => Stmt::Break(f.fold_break_stmt(r#break)),
+        Stmt::Return(r#return) => Stmt::Return(f.fold_return_stmt(r#return)),
+        Stmt::Throw(throw) => Stmt::Throw(f.fold_throw_stmt(throw)),
+        Stmt::Try(r#try) => Stmt::Try(f.fold_try_stmt(r#try)),
+    }
+}
+
+pub fn fold_block_stmt<F: Fold + ?Sized>(f: &mut F, node: BlockStmt) -> BlockStmt {
+    BlockStmt {
+        stmts: node
+            .stmts
+            .into_iter()
+            .map(|stmt| f.fold_stmt(stmt))
+            .collect(),
+        ..node
+    }
+}
+
+pub fn fold_println_stmt<F: Fold + ?Sized>(f: &mut F, node: PrintlnStmt) -> PrintlnStmt {
+    PrintlnStmt {
+        expr: f.fold_expr(node.expr),
+        ..node
+    }
+}
+
+pub fn fold_let_stmt<F: Fold + ?Sized>(f: &mut F, node: LetStmt) -> LetStmt {
+    LetStmt {
+        ident: f.fold_ident(node.ident),
+        ty: node.ty.map(|ty| f.fold_type(ty)),
+        expr: f.fold_expr(node.expr),
+        ..node
+    }
+}
+
+pub fn fold_const_stmt<F: Fold + ?Sized>(f: &mut F, node: ConstStmt) -> ConstStmt {
+    ConstStmt {
+        ident: f.fold_ident(node.ident),
+        ty: node.ty.map(|ty| ConstType {
+            ty: f.fold_type(ty.ty),
+            ..ty
+        }),
+        expr: f.fold_expr(node.expr),
+        ..node
+    }
+}
+
+pub fn fold_if_stmt<F: Fold + ?Sized>(f: &mut F, node: IfStmt) -> IfStmt {
+    IfStmt {
+        cond: f.fold_expr(node.cond),
+        block: f.fold_block_stmt(node.block),
+        ..node
+    }
+}
+
+pub fn fold_while_stmt<F: Fold + ?Sized>(f: &mut F, node: WhileStmt) -> WhileStmt {
+    WhileStmt {
+        cond: f.fold_expr(node.cond),
+        block: f.fold_block_stmt(node.block),
+        ..node
+    }
+}
+
+pub fn fold_for_stmt<F: Fold + ?Sized>(f: &mut F, node: ForStmt) -> ForStmt {
+    ForStmt {
+        ident: f.fold_ident(node.ident),
+        iter: f.fold_expr(node.iter),
+        block: f.fold_block_stmt(node.block),
+        ..node
+    }
+}
+
+pub fn fold_class_stmt<F: Fold + ?Sized>(f: &mut F, node: ClassStmt) -> ClassStmt {
+    ClassStmt {
+        docs: f.fold_docs(node.docs),
+        ident: f.fold_ident(node.ident),
+        generics: f.fold_generics(node.generics),
+        fields: node
+            .fields
+            .into_iter()
+            .map(|field| f.fold_field(field))
+            .collect(),
+        ..node
+    }
+}
+
+pub fn fold_field<F: Fold + ?Sized>(f: &mut F, node: Field) -> Field {
+    Field {
+        docs: f.fold_docs(node.docs),
+        ident: f.fold_ident(node.ident),
+        ty: f.fold_type(node.ty),
+        ..node
+    }
+}
+
+pub fn fold_trait_stmt<F: Fold + ?Sized>(f: &mut F, node: TraitStmt) -> TraitStmt {
+    TraitStmt {
+        docs: f.fold_docs(node.docs),
+        ident: f.fold_ident(node.ident),
+        generics: f.fold_generics(node.generics),
+        supertraits: node
+            .supertraits
+            .into_iter()
+            .map(|supertrait| f.fold_ident(supertrait))
+            .collect(),
+        protos: node
+            .protos
+            .into_iter()
+            .map(|proto| f.fold_proto_method(proto))
+            .collect(),
+        ..node
+    }
+}
+
+pub fn fold_proto_method<F: Fold + ?Sized>(f: &mut F, node: ProtoMethod) -> ProtoMethod {
+    ProtoMethod {
+        docs: f.fold_docs(node.docs),
+        ident: f.fold_ident(node.ident),
+        params: node
+            .params
+            .into_iter()
+            .map(|param| f.fold_param(param))
+            .collect(),
+        return_ty: node.return_ty.map(|return_ty| f.fold_type(return_ty)),
+        block: node.block.map(|block| f.fold_block_stmt(block)),
+        ..node
+    }
+}
+
+pub fn fold_impl_stmt<F: Fold + ?Sized>(f: &mut F, node: ImplStmt) -> ImplStmt {
+    ImplStmt {
+        generics: f.fold_generics(node.generics),
+        tr: node.tr.map(|tr| f.fold_type(tr)),
+        ty: f.fold_type(node.ty),
+        methods: node
+            .methods
+            .into_iter()
+            .map(|method| f.fold_method(method))
+            .collect(),
+        ..node
+    }
+}
+
+pub fn fold_method<F: Fold + ?Sized>(f: &mut F, node: Method) -> Method {
+    match node {
+        Method::Default {
+            span,
+            docs,
+            is_static,
+            ident,
+            params,
+            return_ty,
+            block,
+        } => Method::Default {
+            span,
+            docs: f.fold_docs(docs),
+            is_static,
+            ident: f.fold_ident(ident),
+            params: params
+                .into_iter()
+                .map(|param| f.fold_param(param))
+                .collect(),
+            return_ty: return_ty.map(|return_ty| f.fold_type(return_ty)),
+            block: f.fold_block_stmt(block),
+        },
+        Method::Extern {
+            span,
+            docs,
+            is_static,
+            ident,
+            params,
+            return_ty,
+        } => Method::Extern {
+            span,
+            docs: f.fold_docs(docs),
+            is_static,
+            ident: f.fold_ident(ident),
+            params: params
+                .into_iter()
+                .map(|param| f.fold_param(param))
+                .collect(),
+            return_ty: return_ty.map(|return_ty| f.fold_type(return_ty)),
+        },
+    }
+}
+
+pub fn fold_expr_stmt<F: Fold + ?Sized>(f: &mut F, node: ExprStmt) -> ExprStmt {
+    ExprStmt {
+        expr: f.fold_expr(node.expr),
+        ..node
+    }
+}
+
+pub fn fold_fun_stmt<F: Fold + ?Sized>(f: &mut F, node: FunStmt) -> FunStmt {
+    match node {
+        FunStmt::Default {
+            span,
+            docs,
+            ident,
+            generics,
+            params,
+            return_ty,
+            block,
+        } => FunStmt::Default {
+            span,
+            docs: f.fold_docs(docs),
+            ident: f.fold_ident(ident),
+            generics: f.fold_generics(generics),
+            params: params
+                .into_iter()
+                .map(|param| f.fold_param(param))
+                .collect(),
+            return_ty: return_ty.map(|return_ty| f.fold_type(return_ty)),
+            block: f.fold_block_stmt(block),
+        },
+        FunStmt::Extern {
+            span,
+            docs,
+            ident,
+            generics,
+            params,
+            return_ty,
+        } => FunStmt::Extern {
+            span,
+            docs: f.fold_docs(docs),
+            ident: f.fold_ident(ident),
+            generics: f.fold_generics(generics),
+            params: params
+                .into_iter()
+                .map(|param| f.fold_param(param))
+                .collect(),
+            return_ty: return_ty.map(|return_ty| f.fold_type(return_ty)),
+        },
+    }
+}
+
+pub fn fold_continue_stmt<F: Fold + ?Sized>(_f: &mut F, node: ContinueStmt) -> ContinueStmt {
+    node
+}
+
+pub fn fold_break_stmt<F: Fold + ?Sized>(_f: &mut F, node: BreakStmt) -> BreakStmt {
+    node
+}
+
+pub fn fold_return_stmt<F: Fold + ?Sized>(f: &mut F, node: ReturnStmt) -> ReturnStmt {
+    ReturnStmt {
+        expr: node.expr.map(|expr| f.fold_expr(expr)),
+        ..node
+    }
+}
+
+pub fn fold_throw_stmt<F: Fold + ?Sized>(f: &mut F, node: ThrowStmt) -> ThrowStmt {
+    ThrowStmt {
+        expr: f.fold_expr(node.expr),
+        ..node
+    }
+}
+
+pub fn fold_try_stmt<F: Fold + ?Sized>(f: &mut F, node: TryStmt) -> TryStmt {
+    TryStmt {
+        block: f.fold_block_stmt(node.block),
+        catch: node.catch.map(|catch| f.fold_catch_clause(catch)),
+        finally: node.finally.map(|finally| f.fold_block_stmt(finally)),
+        ..node
+    }
+}
+
+pub fn fold_catch_clause<F: Fold + ?Sized>(f: &mut F, node: CatchClause) -> CatchClause {
+    CatchClause {
+        ident: f.fold_ident(node.ident),
+        block: f.fold_block_stmt(node.block),
+        ..node
+    }
+}
+
+pub fn fold_expr<F: Fold + ?Sized>(f: &mut F, node: Expr) -> Expr {
+    match node {
+        Expr::Ident(ident) => Expr::Ident(f.fold_ident_expr(ident)),
+        Expr::Lit(lit) => Expr::Lit(f.fold_lit_expr(lit)),
+        Expr::Paren(paren) => Expr::Paren(f.fold_paren_expr(paren)),
+        Expr::Unary(unary) => Expr::Unary(f.fold_unary_expr(unary)),
+        Expr::Binary(binary) => Expr::Binary(f.fold_binary_expr(binary)),
+        Expr::Class(class) => Expr::Class(f.fold_class_expr(class)),
+        Expr::Call(call) => Expr::Call(f.fold_call_expr(call)),
+        Expr::Get(get) => Expr::Get(f.fold_get_expr(get)),
+        Expr::Path(path) => Expr::Path(f.fold_path_expr(path)),
+        Expr::Unwrap(unwrap) => Expr::Unwrap(f.fold_unwrap_expr(unwrap)),
+        Expr::Try(r#try) => Expr::Try(f.fold_try_expr(r#try)),
+    }
+}
+
+pub fn fold_ident_expr<F: Fold + ?Sized>(f: &mut F, node: IdentExpr) -> IdentExpr {
+    IdentExpr {
+        ident: f.fold_ident(node.ident),
+    }
+}
+
+pub fn fold_lit_expr<F: Fold + ?Sized>(_f: &mut F, node: LitExpr) -> LitExpr {
+    node
+}
+
+pub fn fold_paren_expr<F: Fold + ?Sized>(f: &mut F, node: ParenExpr) -> ParenExpr {
+    ParenExpr {
+        expr: Box::new(f.fold_expr(*node.expr)),
+        ..node
+    }
+}
+
+pub fn fold_unary_expr<F: Fold + ?Sized>(f: &mut F, node: UnaryExpr) -> UnaryExpr {
+    UnaryExpr {
+        op: f.fold_unary_op(node.op),
+        expr: Box::new(f.fold_expr(*node.expr)),
+        ..node
+    }
+}
+
+pub fn fold_unary_op<F: Fold + ?Sized>(_f: &mut F, node: UnaryOp) -> UnaryOp {
+    node
+}
+
+pub fn fold_binary_expr<F: Fold + ?Sized>(f: &mut F, node: BinaryExpr) -> BinaryExpr {
+    BinaryExpr {
+        left: Box::new(f.fold_expr(*node.left)),
+        op: f.fold_binary_op(node.op),
+        right: Box::new(f.fold_expr(*node.right)),
+        ..node
+    }
+}
+
+pub fn fold_binary_op<F: Fold + ?Sized>(_f: &mut F, node: BinaryOp) -> BinaryOp {
+    node
+}
+
+pub fn fold_class_expr<F: Fold + ?Sized>(f: &mut F, node: ClassExpr) -> ClassExpr {
+    ClassExpr {
+        cls: Box::new(f.fold_expr(*node.cls)),
+        fields: node
+            .fields
+            .into_iter()
+            .map(|field| f.fold_field_expr(field))
+            .collect(),
+        ..node
+    }
+}
+
+pub fn fold_field_expr<F: Fold + ?Sized>(f: &mut F, node: FieldExpr) -> FieldExpr {
+    FieldExpr {
+        ident: f.fold_ident(node.ident),
+        value: node.value.map(|value| f.fold_expr(value)),
+        ..node
+    }
+}
+
+pub fn fold_call_expr<F: Fold + ?Sized>(f: &mut F, node: CallExpr) -> CallExpr {
+    CallExpr {
+        callee: Box::new(f.fold_expr(*node.callee)),
+        args: node.args.into_iter().map(|arg| f.fold_expr(arg)).collect(),
+        ..node
+    }
+}
+
+pub fn fold_get_expr<F: Fold + ?Sized>(f: &mut F, node: GetExpr) -> GetExpr {
+    GetExpr {
+        expr: Box::new(f.fold_expr(*node.expr)),
+        ident: f.fold_ident(node.ident),
+        ..node
+    }
+}
+
+pub fn fold_path_expr<F: Fold + ?Sized>(f: &mut F, node: PathExpr) -> PathExpr {
+    PathExpr {
+        ty: f.fold_ident(node.ty),
+        ident: f.fold_ident(node.ident),
+        ..node
+    }
+}
+
+pub fn fold_unwrap_expr<F: Fold + ?Sized>(f: &mut F, node: UnwrapExpr) -> UnwrapExpr {
+    UnwrapExpr {
+        expr: Box::new(f.fold_expr(*node.expr)),
+        ..node
+    }
+}
+
+pub fn fold_try_expr<F: Fold + ?Sized>(f: &mut F, node: TryExpr) -> TryExpr {
+    TryExpr {
+        expr: Box::new(f.fold_expr(*node.expr)),
+        ..node
+    }
+}
+
+pub fn fold_param<F: Fold + ?Sized>(f: &mut F, node: Param) -> Param {
+    Param {
+        ident: f.fold_ident(node.ident),
+        ty: f.fold_type(node.ty),
+        ..node
+    }
+}
+
+pub fn fold_type<F: Fold + ?Sized>(f: &mut F, node: Type) -> Type {
+    Type {
+        ident: f.fold_ident(node.ident),
+        args: node.args.into_iter().map(|arg| f.fold_type(arg)).collect(),
+        ..node
+    }
+}
+
+pub fn fold_generics<F: Fold + ?Sized>(f: &mut F, node: Generics) -> Generics {
+    Generics {
+        params: node
+            .params
+            .into_iter()
+            .map(|param| f.fold_generic_param(param))
+            .collect(),
+    }
+}
+
+pub fn fold_generic_param<F: Fold + ?Sized>(f: &mut F, node: GenericParam) -> GenericParam {
+    GenericParam {
+        ident: f.fold_ident(node.ident),
+        bounds: node
+            .bounds
+            .into_iter()
+            .map(|bound| f.fold_ident(bound))
+            .collect(),
+        ..node
+    }
+}
+
+pub fn fold_ident<F: Fold + ?Sized>(_f: &mut F, node: Ident) -> Ident {
+    node
+}
+
+pub fn fold_docs<F: Fold + ?Sized>(_f: &mut F, node: Docs) -> Docs {
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::test_utils::{parse, EVERY_NODE};
+
+    use super::*;
+
+    /// Implements every method of `Fold` to record its name before
+    /// folding the children of the node.
+    macro_rules! recorder {
+        ($($method:ident: $ty:ty),* $(,)?) => {
+            const METHODS: &[&str] = &[$(stringify!($method)),*];
+
+            impl Fold for Recorder {
+                $(
+                    fn $method(&mut self, node: $ty) -> $ty {
+                        self.visited.insert(stringify!($method));
+                        $method(self, node)
+                    }
+                )*
+            }
+        };
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        visited: HashSet<&'static str>,
+    }
+
+    recorder! {
+        fold_ast: Ast,
+        fold_stmt: Stmt,
+        fold_block_stmt: BlockStmt,
+        fold_println_stmt: PrintlnStmt,
+        fold_let_stmt: LetStmt,
+        fold_const_stmt: ConstStmt,
+        fold_if_stmt: IfStmt,
+        fold_while_stmt: WhileStmt,
+        fold_for_stmt: ForStmt,
+        fold_class_stmt: ClassStmt,
+        fold_field: Field,
+        fold_trait_stmt: TraitStmt,
+        fold_proto_method: ProtoMethod,
+        fold_impl_stmt: ImplStmt,
+        fold_method: Method,
+        fold_expr_stmt: ExprStmt,
+        fold_fun_stmt: FunStmt,
+        fold_continue_stmt: ContinueStmt,
+        fold_break_stmt: BreakStmt,
+        fold_return_stmt: ReturnStmt,
+        fold_throw_stmt: ThrowStmt,
+        fold_try_stmt: TryStmt,
+        fold_catch_clause: CatchClause,
+        fold_expr: Expr,
+        fold_ident_expr: IdentExpr,
+        fold_lit_expr: LitExpr,
+        fold_paren_expr: ParenExpr,
+        fold_unary_expr: UnaryExpr,
+        fold_unary_op: UnaryOp,
+        fold_binary_expr: BinaryExpr,
+        fold_binary_op: BinaryOp,
+        fold_class_expr: ClassExpr,
+        fold_field_expr: FieldExpr,
+        fold_call_expr: CallExpr,
+        fold_get_expr: GetExpr,
+        fold_path_expr: PathExpr,
+        fold_unwrap_expr: UnwrapExpr,
+        fold_try_expr: TryExpr,
+        fold_param: Param,
+        fold_type: Type,
+        fold_generics: Generics,
+        fold_generic_param: GenericParam,
+        fold_ident: Ident,
+        fold_docs: Docs,
+    }
+
+    #[test]
+    fn folds_every_node_kind() {
+        let mut recorder = Recorder::default();
+        recorder.fold_ast(parse(EVERY_NODE));
+        for method in METHODS {
+            assert!(
+                recorder.visited.contains(method),
+                "{} was not called",
+                method
+            );
+        }
+    }
+
+    #[test]
+    fn folding_without_changes_keeps_the_tree() {
+        let folded = Recorder::default().fold_ast(parse(EVERY_NODE));
+        assert_eq!(format!("{:?}", folded), format!("{:?}", parse(EVERY_NODE)));
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::{self, Write};
+
+use lumi_lxr::span;
+use lumi_lxr::span::Span;
+use lumi_lxr::token::TokenKind;
+use serde::Serialize;
+
+use crate::display_tree::{branch, DisplayTree};
+use crate::ident;
+use crate::parse::Parse;
+use crate::parser::{ParseError, ParseStream};
+use crate::symbols::{Colon, Greater, Ident, Less, Plus};
+
+/// The `GenericParam` declares a type parameter along with the traits its
+/// arguments must implement, as in `T: Ord + Eq`.
+#[derive(Debug, Serialize)]
+pub struct GenericParam {
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) bounds: Vec<Ident>,
+}
+
+span!(GenericParam);
+ident!(GenericParam);
+
+impl GenericParam {
+    pub fn bounds(&self) -> &Vec<Ident> {
+        &self.bounds
+    }
+}
+
+impl Parse for GenericParam {
+    fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let ident: Ident = input.parse()?;
+        let mut bounds: Vec<Ident> = vec![];
+        if input.peek().kind() == TokenKind::Colon {
+            let _colon: Colon = input.parse()?;
+            bounds.push(input.parse()?);
+            while input.peek().kind() == TokenKind::Plus {
+                let _plus: Plus = input.parse()?;
+                bounds.push(input.parse()?);
+            }
+        }
+        let span = match bounds.last() {
+            Some(bound) => Span::range(ident.span(), bound.span()),
+            None => ident.span().clone(),
+        };
+        Ok(Self {
+            span,
+            ident,
+            bounds,
+        })
+    }
+}
+
+impl DisplayTree for GenericParam {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "GenericParam", layer)?;
+        self.ident.display(f, layer + 1)?;
+        for bound in &self.bounds {
+            bound.display(f, layer + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `Generics` are the type parameters declared between `<` and `>`
+/// after the name of a function, class or trait, or after `impl`.
+#[derive(Debug, Default, Serialize)]
+pub struct Generics {
+    pub(crate) params: Vec<GenericParam>,
+}
+
+impl Generics {
+    pub fn params(&self) -> &Vec<GenericParam> {
+        &self.params
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+impl Parse for Generics {
+    fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        if input.peek().kind() != TokenKind::Less {
+            return Ok(Self::default());
+        }
+        let _less: Less = input.parse()?;
+        let mut params = vec![input.parse()?];
+        while input.peek().kind() == TokenKind::Comma {
+            input.expect(TokenKind::Comma)?;
+            params.push(input.parse()?);
+        }
+        let _greater: Greater = input.parse()?;
+        Ok(Self { params })
+    }
+}
+
+impl DisplayTree for Generics {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        if !self.params.is_empty() {
+            branch(f, "Generics", layer)?;
+            for param in &self.params {
+                param.display(f, layer + 1)?;
+            }
+        }
+        Ok(())
+    }
+}
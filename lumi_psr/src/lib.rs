@@ -1,9 +1,17 @@
+pub mod ast;
 pub mod display_tree;
+pub mod docs;
 pub mod exprs;
+pub mod fold;
+pub mod generics;
+pub mod param;
 pub mod parse;
 pub mod parser;
 pub mod stmts;
 pub mod symbols;
 pub mod ty;
-pub mod param;
-pub mod ast;
+pub mod visit;
+pub mod visit_mut;
+
+#[cfg(test)]
+mod test_utils;
@@ -1,6 +1,9 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::ident;
@@ -9,16 +12,22 @@ use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{Colon, Ident};
 use crate::ty::Type;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Param {
-    span: Span,
-    ident: Ident,
-    ty: Type,
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) ty: Type,
 }
 
 span!(Param);
 ident!(Param);
 
+impl Param {
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+}
+
 impl Parse for Param {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
         let ident: Ident = input.parse()?;
@@ -33,10 +42,11 @@ impl Parse for Param {
 }
 
 impl DisplayTree for Param {
-    fn display(&self, layer: usize) {
-        branch("Param", layer);
-        self.ident.display(layer + 1);
-        self.ty.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "Param", layer)?;
+        self.ident.display(f, layer + 1)?;
+        self.ty.display(f, layer + 1)?;
+        Ok(())
     }
 }
 
@@ -49,6 +59,9 @@ impl Parse for Vec<Param> {
             params.push(input.parse()?);
             while input.peek().kind() == TokenKind::Comma {
                 input.expect(TokenKind::Comma)?;
+                if input.peek().kind() == TokenKind::RightParen {
+                    break;
+                }
                 params.push(input.parse()?);
             }
             Ok(params)
@@ -57,12 +70,13 @@ impl Parse for Vec<Param> {
 }
 
 impl DisplayTree for Vec<Param> {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         if !self.is_empty() {
-            branch("Params", layer);
+            branch(f, "Params", layer)?;
             for param in self {
-                param.display(layer + 1);
+                param.display(f, layer + 1)?;
             }
         }
+        Ok(())
     }
 }
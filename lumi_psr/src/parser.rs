@@ -12,6 +12,16 @@ pub struct ParseError {
     pub(crate) span: Span,
 }
 
+impl ParseError {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let line = self.span.start().line();
@@ -1,6 +1,9 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::parse::Parse;
@@ -9,10 +12,10 @@ use crate::symbols::{LeftBrace, RightBrace};
 
 use super::Stmt;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct BlockStmt {
-    span: Span,
-    stmts: Vec<Stmt>,
+    pub(crate) span: Span,
+    pub(crate) stmts: Vec<Stmt>,
 }
 
 span!(BlockStmt);
@@ -40,8 +43,9 @@ impl Parse for BlockStmt {
 }
 
 impl DisplayTree for BlockStmt {
-    fn display(&self, layer: usize) {
-        branch("BlockStmt", layer);
-        self.stmts.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "BlockStmt", layer)?;
+        self.stmts.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,14 +1,17 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{Break, Semicolon};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct BreakStmt {
-    span: Span,
+    pub(crate) span: Span,
 }
 
 span!(BreakStmt);
@@ -24,7 +27,8 @@ impl Parse for BreakStmt {
 }
 
 impl DisplayTree for BreakStmt {
-    fn display(&self, layer: usize) {
-        branch("BreakStmt", layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "BreakStmt", layer)?;
+        Ok(())
     }
 }
@@ -1,30 +1,49 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
+use crate::docs::Docs;
+use crate::generics::Generics;
 use crate::ident;
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{Class, Colon, Ident, LeftBrace, RightBrace};
 use crate::ty::Type;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Field {
-    span: Span,
-    ident: Ident,
-    ty: Type,
+    pub(crate) span: Span,
+    pub(crate) docs: Docs,
+    pub(crate) ident: Ident,
+    pub(crate) ty: Type,
 }
 
 span!(Field);
+ident!(Field);
+
+impl Field {
+    pub fn docs(&self) -> &Docs {
+        &self.docs
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+}
 
 impl Parse for Field {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let docs: Docs = input.parse()?;
         let ident: Ident = input.parse()?;
         let _colon: Colon = input.parse()?;
         let ty: Type = input.parse()?;
         Ok(Self {
             span: Span::range(ident.span(), ty.span()),
+            docs,
             ident,
             ty,
         })
@@ -32,10 +51,12 @@ impl Parse for Field {
 }
 
 impl DisplayTree for Field {
-    fn display(&self, layer: usize) {
-        branch("Field", layer);
-        self.ident.display(layer + 1);
-        self.ty.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "Field", layer)?;
+        self.docs.display(f, layer + 1)?;
+        self.ident.display(f, layer + 1)?;
+        self.ty.display(f, layer + 1)?;
+        Ok(())
     }
 }
 
@@ -48,6 +69,9 @@ impl Parse for Vec<Field> {
             fields.push(input.parse()?);
             while input.peek().kind() != TokenKind::RightBrace {
                 input.expect(TokenKind::Comma)?;
+                if input.peek().kind() == TokenKind::RightBrace {
+                    break;
+                }
                 fields.push(input.parse()?);
             }
             Ok(fields)
@@ -56,25 +80,36 @@ impl Parse for Vec<Field> {
 }
 
 impl DisplayTree for Vec<Field> {
-    fn display(&self, layer: usize) {
-        branch("Fields", layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "Fields", layer)?;
         for field in self {
-            field.display(layer + 1);
+            field.display(f, layer + 1)?;
         }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ClassStmt {
-    span: Span,
-    ident: Ident,
-    fields: Vec<Field>,
+    pub(crate) span: Span,
+    pub(crate) docs: Docs,
+    pub(crate) ident: Ident,
+    pub(crate) generics: Generics,
+    pub(crate) fields: Vec<Field>,
 }
 
 span!(ClassStmt);
 ident!(ClassStmt);
 
 impl ClassStmt {
+    pub fn docs(&self) -> &Docs {
+        &self.docs
+    }
+
+    pub fn generics(&self) -> &Generics {
+        &self.generics
+    }
+
     pub fn fields(&self) -> &Vec<Field> {
         &self.fields
     }
@@ -82,23 +117,30 @@ impl ClassStmt {
 
 impl Parse for ClassStmt {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let docs: Docs = input.parse()?;
         let class: Class = input.parse()?;
         let ident: Ident = input.parse()?;
+        let generics: Generics = input.parse()?;
         let _left_brace: LeftBrace = input.parse()?;
         let fields: Vec<Field> = input.parse()?;
         let right_brace: RightBrace = input.parse()?;
         Ok(Self {
             span: Span::range(class.span(), right_brace.span()),
+            docs,
             ident,
+            generics,
             fields,
         })
     }
 }
 
 impl DisplayTree for ClassStmt {
-    fn display(&self, layer: usize) {
-        branch("ClassStmt", layer);
-        self.ident.display(layer + 1);
-        self.fields.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ClassStmt", layer)?;
+        self.docs.display(f, layer + 1)?;
+        self.ident.display(f, layer + 1)?;
+        self.generics.display(f, layer + 1)?;
+        self.fields.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,6 +1,9 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::exprs::Expr;
@@ -10,10 +13,10 @@ use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{Colon, Const, Equal, Ident, Semicolon};
 use crate::ty::Type;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ConstType {
-    span: Span,
-    ty: Type,
+    pub(crate) span: Span,
+    pub(crate) ty: Type,
 }
 
 span!(ConstType);
@@ -39,18 +42,22 @@ impl Parse for Option<ConstType> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ConstStmt {
-    span: Span,
-    ident: Ident,
-    ty: Option<ConstType>,
-    expr: Expr,
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) ty: Option<ConstType>,
+    pub(crate) expr: Expr,
 }
 
 span!(ConstStmt);
 ident!(ConstStmt);
 
 impl ConstStmt {
+    pub fn ty(&self) -> Option<&Type> {
+        self.ty.as_ref().map(|ty| &ty.ty)
+    }
+
     pub fn expr(&self) -> &Expr {
         &self.expr
     }
@@ -74,12 +81,13 @@ impl Parse for ConstStmt {
 }
 
 impl DisplayTree for ConstStmt {
-    fn display(&self, layer: usize) {
-        branch("ConstStmt", layer);
-        self.ident.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ConstStmt", layer)?;
+        self.ident.display(f, layer + 1)?;
         if let Some(ty) = &self.ty {
-            ty.ty.display(layer + 1);
+            ty.ty.display(f, layer + 1)?;
         }
-        self.expr.display(layer + 1);
+        self.expr.display(f, layer + 1)?;
+        Ok(())
     }
 }
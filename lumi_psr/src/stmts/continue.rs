@@ -1,14 +1,17 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{Continue, Semicolon};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ContinueStmt {
-    span: Span,
+    pub(crate) span: Span,
 }
 
 span!(ContinueStmt);
@@ -24,7 +27,8 @@ impl Parse for ContinueStmt {
 }
 
 impl DisplayTree for ContinueStmt {
-    fn display(&self, layer: usize) {
-        branch("ContinueStmt", layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ContinueStmt", layer)?;
+        Ok(())
     }
 }
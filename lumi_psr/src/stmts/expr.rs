@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::exprs::Expr;
@@ -7,7 +10,7 @@ use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::symbols::Semicolon;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ExprStmt {
     pub(crate) span: Span,
     pub(crate) expr: Expr,
@@ -33,8 +36,9 @@ impl Parse for ExprStmt {
 }
 
 impl DisplayTree for ExprStmt {
-    fn display(&self, layer: usize) {
-        branch("ExprStmt", layer);
-        self.expr.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ExprStmt", layer)?;
+        self.expr.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::exprs::Expr;
@@ -10,12 +13,12 @@ use crate::symbols::{For, Ident, In};
 
 use super::block::BlockStmt;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ForStmt {
-    span: Span,
-    ident: Ident,
-    iter: Expr,
-    block: BlockStmt,
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) iter: Expr,
+    pub(crate) block: BlockStmt,
 }
 
 span!(ForStmt);
@@ -48,11 +51,12 @@ impl Parse for ForStmt {
 }
 
 impl DisplayTree for ForStmt {
-    fn display(&self, layer: usize) {
-        branch("ForStmt", layer);
-        self.ident.display(layer + 1);
-        branch("Iter", layer + 1);
-        self.iter.display(layer + 2);
-        self.block.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ForStmt", layer)?;
+        self.ident.display(f, layer + 1)?;
+        branch(f, "Iter", layer + 1)?;
+        self.iter.display(f, layer + 2)?;
+        self.block.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,7 +1,12 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
+use crate::docs::Docs;
+use crate::generics::Generics;
 use crate::param::Param;
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
@@ -10,18 +15,23 @@ use crate::ty::Type;
 
 use super::block::BlockStmt;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
 pub enum FunStmt {
     Default {
         span: Span,
+        docs: Docs,
         ident: Ident,
+        generics: Generics,
         params: Vec<Param>,
         return_ty: Option<Type>,
         block: BlockStmt,
     },
     Extern {
         span: Span,
+        docs: Docs,
         ident: Ident,
+        generics: Generics,
         params: Vec<Param>,
         return_ty: Option<Type>,
     },
@@ -35,6 +45,13 @@ impl FunStmt {
         }
     }
 
+    pub fn docs(&self) -> &Docs {
+        match self {
+            Self::Extern { docs, .. } => docs,
+            Self::Default { docs, .. } => docs,
+        }
+    }
+
     pub fn ident(&self) -> &Ident {
         match self {
             Self::Extern { ident, .. } => ident,
@@ -42,6 +59,13 @@ impl FunStmt {
         }
     }
 
+    pub fn generics(&self) -> &Generics {
+        match self {
+            Self::Extern { generics, .. } => generics,
+            Self::Default { generics, .. } => generics,
+        }
+    }
+
     pub fn params(&self) -> &Vec<Param> {
         match self {
             Self::Extern { params, .. } => params,
@@ -67,10 +91,12 @@ impl FunStmt {
 
 impl Parse for FunStmt {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let docs: Docs = input.parse()?;
         if input.peek().kind() == TokenKind::Extern {
             let r#extern: Extern = input.parse()?;
             let _fun: Fun = input.parse()?;
             let ident: Ident = input.parse()?;
+            let generics: Generics = input.parse()?;
             let _left_paren: LeftParen = input.parse()?;
             let params: Vec<Param> = input.parse()?;
             let _right_paren: RightParen = input.parse()?;
@@ -83,13 +109,16 @@ impl Parse for FunStmt {
             let semicolon: Semicolon = input.parse()?;
             Ok(FunStmt::Extern {
                 span: Span::range(r#extern.span(), semicolon.span()),
+                docs,
                 ident,
+                generics,
                 params,
                 return_ty,
             })
         } else {
             let r#fun: Fun = input.parse()?;
             let ident: Ident = input.parse()?;
+            let generics: Generics = input.parse()?;
             let _left_paren: LeftParen = input.parse()?;
             let params: Vec<Param> = input.parse()?;
             let _right_paren: RightParen = input.parse()?;
@@ -102,7 +131,9 @@ impl Parse for FunStmt {
             let block: BlockStmt = input.parse()?;
             Ok(FunStmt::Default {
                 span: Span::range(r#fun.span(), block.span()),
+                docs,
                 ident,
+                generics,
                 params,
                 return_ty,
                 block,
@@ -112,36 +143,45 @@ impl Parse for FunStmt {
 }
 
 impl DisplayTree for FunStmt {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         match self {
             Self::Default {
+                docs,
                 ident,
+                generics,
                 params,
                 return_ty,
                 block,
                 ..
             } => {
-                branch("FunStmt", layer);
-                ident.display(layer + 1);
-                params.display(layer + 1);
+                branch(f, "FunStmt", layer)?;
+                docs.display(f, layer + 1)?;
+                ident.display(f, layer + 1)?;
+                generics.display(f, layer + 1)?;
+                params.display(f, layer + 1)?;
                 if let Some(return_ty) = &return_ty {
-                    return_ty.display(layer + 1);
+                    return_ty.display(f, layer + 1)?;
                 }
-                block.display(layer + 1);
+                block.display(f, layer + 1)?;
             }
             Self::Extern {
+                docs,
                 ident,
+                generics,
                 params,
                 return_ty,
                 ..
             } => {
-                branch("ExternFunStmt", layer);
-                ident.display(layer + 1);
-                params.display(layer + 1);
+                branch(f, "ExternFunStmt", layer)?;
+                docs.display(f, layer + 1)?;
+                ident.display(f, layer + 1)?;
+                generics.display(f, layer + 1)?;
+                params.display(f, layer + 1)?;
                 if let Some(return_ty) = &return_ty {
-                    return_ty.display(layer + 1);
+                    return_ty.display(f, layer + 1)?;
                 }
             }
         }
+        Ok(())
     }
 }
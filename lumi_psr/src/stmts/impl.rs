@@ -1,22 +1,31 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
+use crate::docs::Docs;
+use crate::generics::Generics;
 use crate::param::Param;
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{
     Arrow, Extern, For, Fun, Ident, Impl, LeftBrace, LeftParen, RightBrace, RightParen, Semicolon,
+    Static,
 };
 use crate::ty::Type;
 
 use super::block::BlockStmt;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
 pub enum Method {
     Default {
         span: Span,
+        docs: Docs,
+        is_static: bool,
         ident: Ident,
         params: Vec<Param>,
         return_ty: Option<Type>,
@@ -24,6 +33,8 @@ pub enum Method {
     },
     Extern {
         span: Span,
+        docs: Docs,
+        is_static: bool,
         ident: Ident,
         params: Vec<Param>,
         return_ty: Option<Type>,
@@ -38,6 +49,13 @@ impl Method {
         }
     }
 
+    pub fn docs(&self) -> &Docs {
+        match self {
+            Self::Extern { docs, .. } => docs,
+            Self::Default { docs, .. } => docs,
+        }
+    }
+
     pub fn ident(&self) -> &Ident {
         match self {
             Self::Extern { ident, .. } => ident,
@@ -59,6 +77,15 @@ impl Method {
         }
     }
 
+    /// Checks whether the method is declared with `static`, which makes it
+    /// called on the class, as in `Point::new(1, 2)`, without `this`.
+    pub fn is_static(&self) -> bool {
+        match self {
+            Self::Extern { is_static, .. } => *is_static,
+            Self::Default { is_static, .. } => *is_static,
+        }
+    }
+
     pub fn is_default(&self) -> bool {
         matches!(self, Self::Default { .. })
     }
@@ -70,8 +97,10 @@ impl Method {
 
 impl Parse for Method {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let docs: Docs = input.parse()?;
         if input.peek().kind() == TokenKind::Extern {
             let r#extern: Extern = input.parse()?;
+            let r#static: Option<Static> = input.parse()?;
             let _fun: Fun = input.parse()?;
             let ident: Ident = input.parse()?;
             let _left_paren: LeftParen = input.parse()?;
@@ -86,11 +115,14 @@ impl Parse for Method {
             let semicolon: Semicolon = input.parse()?;
             Ok(Method::Extern {
                 span: Span::range(r#extern.span(), semicolon.span()),
+                docs,
+                is_static: r#static.is_some(),
                 ident,
                 params,
                 return_ty,
             })
         } else {
+            let r#static: Option<Static> = input.parse()?;
             let r#fun: Fun = input.parse()?;
             let ident: Ident = input.parse()?;
             let _left_paren: LeftParen = input.parse()?;
@@ -103,8 +135,11 @@ impl Parse for Method {
                 None
             };
             let block: BlockStmt = input.parse()?;
+            let start = r#static.as_ref().map_or(r#fun.span(), Static::span);
             Ok(Method::Default {
-                span: Span::range(r#fun.span(), block.span()),
+                span: Span::range(start, block.span()),
+                docs,
+                is_static: r#static.is_some(),
                 ident,
                 params,
                 return_ty,
@@ -115,37 +150,49 @@ impl Parse for Method {
 }
 
 impl DisplayTree for Method {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         match self {
             Self::Default {
+                docs,
+                is_static,
                 ident,
                 params,
                 return_ty,
                 block,
                 ..
             } => {
-                branch("Method", layer);
-                ident.display(layer + 1);
-                params.display(layer + 1);
+                branch(f, if *is_static { "StaticMethod" } else { "Method" }, layer)?;
+                docs.display(f, layer + 1)?;
+                ident.display(f, layer + 1)?;
+                params.display(f, layer + 1)?;
                 if let Some(return_ty) = &return_ty {
-                    return_ty.display(layer + 1);
+                    return_ty.display(f, layer + 1)?;
                 }
-                block.display(layer + 1);
+                block.display(f, layer + 1)?;
             }
             Self::Extern {
+                docs,
+                is_static,
                 ident,
                 params,
                 return_ty,
                 ..
             } => {
-                branch("ExternMethod", layer);
-                ident.display(layer + 1);
-                params.display(layer + 1);
+                let name = if *is_static {
+                    "ExternStaticMethod"
+                } else {
+                    "ExternMethod"
+                };
+                branch(f, name, layer)?;
+                docs.display(f, layer + 1)?;
+                ident.display(f, layer + 1)?;
+                params.display(f, layer + 1)?;
                 if let Some(return_ty) = &return_ty {
-                    return_ty.display(layer + 1);
+                    return_ty.display(f, layer + 1)?;
                 }
             }
         }
+        Ok(())
     }
 }
 
@@ -167,26 +214,32 @@ impl Parse for Vec<Method> {
 }
 
 impl DisplayTree for Vec<Method> {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         for method in self {
-            branch("Methods", layer);
-            method.display(layer + 1);
+            branch(f, "Methods", layer)?;
+            method.display(f, layer + 1)?;
         }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ImplStmt {
-    span: Span,
-    tr: Option<Ident>,
-    ty: Type,
-    methods: Vec<Method>,
+    pub(crate) span: Span,
+    pub(crate) generics: Generics,
+    pub(crate) tr: Option<Type>,
+    pub(crate) ty: Type,
+    pub(crate) methods: Vec<Method>,
 }
 
 span!(ImplStmt);
 
 impl ImplStmt {
-    pub fn tr(&self) -> Option<&Ident> {
+    pub fn generics(&self) -> &Generics {
+        &self.generics
+    }
+
+    pub fn tr(&self) -> Option<&Type> {
         self.tr.as_ref()
     }
 
@@ -202,21 +255,20 @@ impl ImplStmt {
 impl Parse for ImplStmt {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
         let r#impl: Impl = input.parse()?;
-
-        let tr = if input.peek2().kind() == TokenKind::For {
-            let tr: Ident = input.parse()?;
+        let generics: Generics = input.parse()?;
+        let ty: Type = input.parse()?;
+        let (tr, ty) = if input.peek().kind() == TokenKind::For {
             let _for: For = input.parse()?;
-            Some(tr)
+            (Some(ty), input.parse()?)
         } else {
-            None
+            (None, ty)
         };
-
-        let ty: Type = input.parse()?;
         let _left_brace: LeftBrace = input.parse()?;
         let methods: Vec<Method> = input.parse()?;
         let right_brace: RightBrace = input.parse()?;
         Ok(Self {
             span: Span::range(r#impl.span(), right_brace.span()),
+            generics,
             tr,
             ty,
             methods,
@@ -225,12 +277,14 @@ impl Parse for ImplStmt {
 }
 
 impl DisplayTree for ImplStmt {
-    fn display(&self, layer: usize) {
-        branch("ImplStmt", layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ImplStmt", layer)?;
+        self.generics.display(f, layer + 1)?;
         if let Some(tr) = &self.tr {
-            tr.display(layer + 1);
+            tr.display(f, layer + 1)?;
         }
-        self.ty.display(layer + 1);
-        self.methods.display(layer + 1);
+        self.ty.display(f, layer + 1)?;
+        self.methods.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,27 +1,34 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::exprs::Expr;
 use crate::ident;
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
-use crate::symbols::{Equal, Ident, Let, Semicolon};
+use crate::symbols::{Colon, Equal, Ident, Let, Semicolon};
 use crate::ty::Type;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct LetStmt {
-    span: Span,
-    ident: Ident,
-    ty: Option<Type>,
-    expr: Expr,
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) ty: Option<Type>,
+    pub(crate) expr: Expr,
 }
 
 span!(LetStmt);
 ident!(LetStmt);
 
 impl LetStmt {
+    pub fn ty(&self) -> Option<&Type> {
+        self.ty.as_ref()
+    }
+
     pub fn expr(&self) -> &Expr {
         &self.expr
     }
@@ -33,6 +40,7 @@ impl Parse for LetStmt {
         let ident: Ident = input.parse()?;
 
         let ty = if input.peek().kind() == TokenKind::Colon {
+            let _colon: Colon = input.parse()?;
             Some(input.parse()?)
         } else {
             None
@@ -51,12 +59,13 @@ impl Parse for LetStmt {
 }
 
 impl DisplayTree for LetStmt {
-    fn display(&self, layer: usize) {
-        branch("LetStmt", layer);
-        self.ident.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "LetStmt", layer)?;
+        self.ident.display(f, layer + 1)?;
         if let Some(ty) = &self.ty {
-            ty.display(layer + 1);
+            ty.display(f, layer + 1)?;
         }
-        self.expr.display(layer + 1);
+        self.expr.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::DisplayTree;
 use crate::exprs::Expr;
@@ -21,7 +24,9 @@ use self::r#impl::ImplStmt;
 use self::r#let::LetStmt;
 use self::r#return::ReturnStmt;
 use self::r#trait::TraitStmt;
+use self::r#try::TryStmt;
 use self::r#while::WhileStmt;
+use self::throw::ThrowStmt;
 
 pub mod block;
 pub mod r#break;
@@ -36,10 +41,13 @@ pub mod r#impl;
 pub mod r#let;
 pub mod println;
 pub mod r#return;
+pub mod throw;
 pub mod r#trait;
+pub mod r#try;
 pub mod r#while;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
 pub enum Stmt {
     Println(PrintlnStmt),
     Block(BlockStmt),
@@ -56,6 +64,32 @@ pub enum Stmt {
     Continue(ContinueStmt),
     Break(BreakStmt),
     Return(ReturnStmt),
+    Throw(ThrowStmt),
+    Try(TryStmt),
+}
+
+impl Stmt {
+    pub fn span(&self) -> &Span {
+        match self {
+            Stmt::Block(block) => block.span(),
+            Stmt::If(r#if) => r#if.span(),
+            Stmt::While(r#while) => r#while.span(),
+            Stmt::Println(println) => println.span(),
+            Stmt::Let(r#let) => r#let.span(),
+            Stmt::Const(r#const) => r#const.span(),
+            Stmt::For(r#for) => r#for.span(),
+            Stmt::Class(class) => class.span(),
+            Stmt::Trait(r#trait) => r#trait.span(),
+            Stmt::Impl(r#impl) => r#impl.span(),
+            Stmt::Fun(fun) => fun.span(),
+            Stmt::Expr(expr) => expr.span(),
+            Stmt::Continue(r#continue) => r#continue.span(),
+            Stmt::Break(r#break) => r#break.span(),
+            Stmt::Return(r#return) => r#return.span(),
+            Stmt::Throw(throw) => throw.span(),
+            Stmt::Try(r#try) => r#try.span(),
+        }
+    }
 }
 
 impl Parse for Stmt {
@@ -75,6 +109,8 @@ impl Parse for Stmt {
             TokenKind::Continue => Ok(Stmt::Continue(input.parse()?)),
             TokenKind::Break => Ok(Stmt::Break(input.parse()?)),
             TokenKind::Return => Ok(Stmt::Return(input.parse()?)),
+            TokenKind::Throw => Ok(Stmt::Throw(input.parse()?)),
+            TokenKind::Try => Ok(Stmt::Try(input.parse()?)),
             _ => {
                 let expr: Expr = input.parse()?;
                 if input.peek().kind() == TokenKind::Semicolon {
@@ -87,6 +123,7 @@ impl Parse for Stmt {
                     Ok(Stmt::Return(ReturnStmt {
                         span: expr.span().clone(),
                         expr: Some(expr),
+                        implicit: true,
                     }))
                 }
             }
@@ -95,23 +132,25 @@ impl Parse for Stmt {
 }
 
 impl DisplayTree for Stmt {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         match self {
-            Stmt::Block(block) => block.display(layer),
-            Stmt::If(r#if) => r#if.display(layer),
-            Stmt::While(r#while) => r#while.display(layer),
-            Stmt::Println(println) => println.display(layer),
-            Stmt::Let(r#let) => r#let.display(layer),
-            Stmt::Const(r#const) => r#const.display(layer),
-            Stmt::For(r#for) => r#for.display(layer),
-            Stmt::Class(class) => class.display(layer),
-            Stmt::Trait(r#trait) => r#trait.display(layer),
-            Stmt::Impl(r#impl) => r#impl.display(layer),
-            Stmt::Fun(fun) => fun.display(layer),
-            Stmt::Expr(expr) => expr.display(layer),
-            Stmt::Continue(r#continue) => r#continue.display(layer),
-            Stmt::Break(r#break) => r#break.display(layer),
-            Stmt::Return(r#return) => r#return.display(layer),
+            Stmt::Block(block) => block.display(f, layer),
+            Stmt::If(r#if) => r#if.display(f, layer),
+            Stmt::While(r#while) => r#while.display(f, layer),
+            Stmt::Println(println) => println.display(f, layer),
+            Stmt::Let(r#let) => r#let.display(f, layer),
+            Stmt::Const(r#const) => r#const.display(f, layer),
+            Stmt::For(r#for) => r#for.display(f, layer),
+            Stmt::Class(class) => class.display(f, layer),
+            Stmt::Trait(r#trait) => r#trait.display(f, layer),
+            Stmt::Impl(r#impl) => r#impl.display(f, layer),
+            Stmt::Fun(fun) => fun.display(f, layer),
+            Stmt::Expr(expr) => expr.display(f, layer),
+            Stmt::Continue(r#continue) => r#continue.display(f, layer),
+            Stmt::Break(r#break) => r#break.display(f, layer),
+            Stmt::Return(r#return) => r#return.display(f, layer),
+            Stmt::Throw(throw) => throw.display(f, layer),
+            Stmt::Try(r#try) => r#try.display(f, layer),
         }
     }
 }
@@ -127,9 +166,10 @@ impl Parse for Vec<Stmt> {
 }
 
 impl DisplayTree for Vec<Stmt> {
-    fn display(&self, layer: usize) {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
         for stmt in self {
-            stmt.display(layer);
+            stmt.display(f, layer)?;
         }
+        Ok(())
     }
 }
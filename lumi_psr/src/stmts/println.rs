@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::branch;
 use crate::display_tree::DisplayTree;
@@ -10,10 +13,10 @@ use crate::parser::ParseStream;
 use crate::symbols::Println;
 use crate::symbols::Semicolon;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct PrintlnStmt {
-    span: Span,
-    expr: Expr,
+    pub(crate) span: Span,
+    pub(crate) expr: Expr,
 }
 
 span!(PrintlnStmt);
@@ -37,8 +40,9 @@ impl Parse for PrintlnStmt {
 }
 
 impl DisplayTree for PrintlnStmt {
-    fn display(&self, layer: usize) {
-        branch("PrintlnStmt", layer);
-        self.expr.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "PrintlnStmt", layer)?;
+        self.expr.display(f, layer + 1)?;
+        Ok(())
     }
 }
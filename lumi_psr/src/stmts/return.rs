@@ -1,6 +1,9 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::exprs::Expr;
@@ -8,10 +11,11 @@ use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{Return, Semicolon};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ReturnStmt {
     pub(crate) span: Span,
     pub(crate) expr: Option<Expr>,
+    pub(crate) implicit: bool,
 }
 
 span!(ReturnStmt);
@@ -20,6 +24,12 @@ impl ReturnStmt {
     pub fn expr(&self) -> Option<&Expr> {
         self.expr.as_ref()
     }
+
+    /// Checks whether the value is returned by leaving out the semicolon
+    /// of the last expression, instead of using the `return` keyword.
+    pub fn is_implicit(&self) -> bool {
+        self.implicit
+    }
 }
 
 impl Parse for ReturnStmt {
@@ -34,15 +44,17 @@ impl Parse for ReturnStmt {
         Ok(Self {
             span: Span::range(r#return.span(), semicolon.span()),
             expr,
+            implicit: false,
         })
     }
 }
 
 impl DisplayTree for ReturnStmt {
-    fn display(&self, layer: usize) {
-        branch("ReturnStmt", layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ReturnStmt", layer)?;
         if let Some(expr) = &self.expr {
-            expr.display(layer + 1);
+            expr.display(f, layer + 1)?;
         }
+        Ok(())
     }
 }
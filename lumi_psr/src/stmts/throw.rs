@@ -0,0 +1,45 @@
+use std::io::{self, Write};
+
+use lumi_lxr::span;
+use lumi_lxr::span::Span;
+use serde::Serialize;
+
+use crate::display_tree::{branch, DisplayTree};
+use crate::exprs::Expr;
+use crate::parse::Parse;
+use crate::parser::{ParseError, ParseStream};
+use crate::symbols::{Semicolon, Throw};
+
+#[derive(Debug, Serialize)]
+pub struct ThrowStmt {
+    pub(crate) span: Span,
+    pub(crate) expr: Expr,
+}
+
+span!(ThrowStmt);
+
+impl ThrowStmt {
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl Parse for ThrowStmt {
+    fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let throw: Throw = input.parse()?;
+        let expr: Expr = input.parse()?;
+        let semicolon: Semicolon = input.parse()?;
+        Ok(Self {
+            span: Span::range(throw.span(), semicolon.span()),
+            expr,
+        })
+    }
+}
+
+impl DisplayTree for ThrowStmt {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ThrowStmt", layer)?;
+        self.expr.display(f, layer + 1)?;
+        Ok(())
+    }
+}
@@ -1,29 +1,42 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
+use crate::docs::Docs;
+use crate::generics::Generics;
 use crate::ident;
 use crate::param::Param;
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
 use crate::symbols::{
-    Arrow, Fun, Ident, LeftBrace, LeftParen, RightBrace, RightParen, Semicolon, Trait,
+    Arrow, Colon, Fun, Ident, LeftBrace, LeftParen, Plus, RightBrace, RightParen, Semicolon, Trait,
 };
 use crate::ty::Type;
 
-#[derive(Debug)]
+use super::block::BlockStmt;
+
+#[derive(Debug, Serialize)]
 pub struct ProtoMethod {
-    span: Span,
-    ident: Ident,
-    params: Vec<Param>,
-    return_ty: Option<Type>,
+    pub(crate) span: Span,
+    pub(crate) docs: Docs,
+    pub(crate) ident: Ident,
+    pub(crate) params: Vec<Param>,
+    pub(crate) return_ty: Option<Type>,
+    pub(crate) block: Option<BlockStmt>,
 }
 
 span!(ProtoMethod);
 ident!(ProtoMethod);
 
 impl ProtoMethod {
+    pub fn docs(&self) -> &Docs {
+        &self.docs
+    }
+
     pub fn params(&self) -> &Vec<Param> {
         &self.params
     }
@@ -31,10 +44,17 @@ impl ProtoMethod {
     pub fn return_ty(&self) -> Option<&Type> {
         self.return_ty.as_ref()
     }
+
+    /// Gets the default body of the method, inherited by the impl blocks
+    /// that don't override it.
+    pub fn block(&self) -> Option<&BlockStmt> {
+        self.block.as_ref()
+    }
 }
 
 impl Parse for ProtoMethod {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let docs: Docs = input.parse()?;
         let r#fun: Fun = input.parse()?;
         let ident: Ident = input.parse()?;
         let _left_paren: LeftParen = input.parse()?;
@@ -46,24 +66,38 @@ impl Parse for ProtoMethod {
         } else {
             None
         };
-        let semicolon: Semicolon = input.parse()?;
+        let (block, end) = if input.peek().kind() == TokenKind::LeftBrace {
+            let block: BlockStmt = input.parse()?;
+            let end = block.span().clone();
+            (Some(block), end)
+        } else {
+            let semicolon: Semicolon = input.parse()?;
+            (None, semicolon.span().clone())
+        };
         Ok(Self {
-            span: Span::range(r#fun.span(), semicolon.span()),
+            span: Span::range(r#fun.span(), &end),
+            docs,
             ident,
             params,
             return_ty,
+            block,
         })
     }
 }
 
 impl DisplayTree for ProtoMethod {
-    fn display(&self, layer: usize) {
-        branch("ProtoMethod", layer);
-        self.ident.display(layer + 1);
-        self.params.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "ProtoMethod", layer)?;
+        self.docs.display(f, layer + 1)?;
+        self.ident.display(f, layer + 1)?;
+        self.params.display(f, layer + 1)?;
         if let Some(return_ty) = &self.return_ty {
-            return_ty.display(layer + 1);
+            return_ty.display(f, layer + 1)?;
         }
+        if let Some(block) = &self.block {
+            block.display(f, layer + 1)?;
+        }
+        Ok(())
     }
 }
 
@@ -85,25 +119,43 @@ impl Parse for Vec<ProtoMethod> {
 }
 
 impl DisplayTree for Vec<ProtoMethod> {
-    fn display(&self, layer: usize) {
-        branch("Protos", layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "Protos", layer)?;
         for proto in self {
-            proto.display(layer + 1);
+            proto.display(f, layer + 1)?;
         }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct TraitStmt {
-    span: Span,
-    ident: Ident,
-    protos: Vec<ProtoMethod>,
+    pub(crate) span: Span,
+    pub(crate) docs: Docs,
+    pub(crate) ident: Ident,
+    pub(crate) generics: Generics,
+    pub(crate) supertraits: Vec<Ident>,
+    pub(crate) protos: Vec<ProtoMethod>,
 }
 
 span!(TraitStmt);
 ident!(TraitStmt);
 
 impl TraitStmt {
+    pub fn docs(&self) -> &Docs {
+        &self.docs
+    }
+
+    pub fn generics(&self) -> &Generics {
+        &self.generics
+    }
+
+    /// Gets the traits that must be implemented by every type implementing
+    /// this one, as in `trait Ord: Eq { ... }`.
+    pub fn supertraits(&self) -> &Vec<Ident> {
+        &self.supertraits
+    }
+
     pub fn protos(&self) -> &Vec<ProtoMethod> {
         &self.protos
     }
@@ -111,23 +163,46 @@ impl TraitStmt {
 
 impl Parse for TraitStmt {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let docs: Docs = input.parse()?;
         let r#trait: Trait = input.parse()?;
         let ident: Ident = input.parse()?;
+        let generics: Generics = input.parse()?;
+        let mut supertraits = vec![];
+        if input.peek().kind() == TokenKind::Colon {
+            let _colon: Colon = input.parse()?;
+            supertraits.push(input.parse()?);
+            while input.peek().kind() == TokenKind::Plus {
+                let _plus: Plus = input.parse()?;
+                supertraits.push(input.parse()?);
+            }
+        }
         let _left_brace: LeftBrace = input.parse()?;
         let protos: Vec<ProtoMethod> = input.parse()?;
         let right_brace: RightBrace = input.parse()?;
         Ok(Self {
             span: Span::range(r#trait.span(), right_brace.span()),
+            docs,
             ident,
+            generics,
+            supertraits,
             protos,
         })
     }
 }
 
 impl DisplayTree for TraitStmt {
-    fn display(&self, layer: usize) {
-        branch("TraitStmt", layer);
-        self.ident.display(layer + 1);
-        self.protos.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "TraitStmt", layer)?;
+        self.docs.display(f, layer + 1)?;
+        self.ident.display(f, layer + 1)?;
+        self.generics.display(f, layer + 1)?;
+        if !self.supertraits.is_empty() {
+            branch(f, "Supertraits", layer + 1)?;
+            for supertrait in &self.supertraits {
+                supertrait.display(f, layer + 2)?;
+            }
+        }
+        self.protos.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -0,0 +1,129 @@
+use std::io::{self, Write};
+
+use lumi_lxr::span;
+use lumi_lxr::span::Span;
+use lumi_lxr::token::TokenKind;
+use serde::Serialize;
+
+use crate::display_tree::{branch, DisplayTree};
+use crate::parse::Parse;
+use crate::parser::{ParseError, ParseStream};
+use crate::symbols::{Catch, Finally, Ident, LeftParen, RightParen, Try};
+
+use super::block::BlockStmt;
+
+#[derive(Debug, Serialize)]
+pub struct TryStmt {
+    pub(crate) span: Span,
+    pub(crate) block: BlockStmt,
+    pub(crate) catch: Option<CatchClause>,
+    pub(crate) finally: Option<BlockStmt>,
+}
+
+span!(TryStmt);
+
+impl TryStmt {
+    pub fn block(&self) -> &BlockStmt {
+        &self.block
+    }
+
+    pub fn catch(&self) -> Option<&CatchClause> {
+        self.catch.as_ref()
+    }
+
+    pub fn finally(&self) -> Option<&BlockStmt> {
+        self.finally.as_ref()
+    }
+}
+
+impl Parse for TryStmt {
+    fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let r#try: Try = input.parse()?;
+        let block: BlockStmt = input.parse()?;
+        let catch = if input.peek().kind() == TokenKind::Catch {
+            Some(input.parse::<CatchClause>()?)
+        } else {
+            None
+        };
+        let finally = if input.peek().kind() == TokenKind::Finally {
+            let _finally: Finally = input.parse()?;
+            Some(input.parse::<BlockStmt>()?)
+        } else {
+            None
+        };
+        let end = match (&catch, &finally) {
+            (_, Some(finally)) => finally.span(),
+            (Some(catch), None) => catch.span(),
+            (None, None) => {
+                return Err(ParseError {
+                    message: "Expected 'catch' or 'finally' after 'try' block".to_owned(),
+                    span: input.peek().span().clone(),
+                })
+            }
+        };
+        Ok(Self {
+            span: Span::range(r#try.span(), end),
+            block,
+            catch,
+            finally,
+        })
+    }
+}
+
+impl DisplayTree for TryStmt {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "TryStmt", layer)?;
+        self.block.display(f, layer + 1)?;
+        if let Some(catch) = &self.catch {
+            catch.display(f, layer + 1)?;
+        }
+        if let Some(finally) = &self.finally {
+            branch(f, "Finally", layer + 1)?;
+            finally.display(f, layer + 2)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatchClause {
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) block: BlockStmt,
+}
+
+span!(CatchClause);
+
+impl CatchClause {
+    pub fn ident(&self) -> &Ident {
+        &self.ident
+    }
+
+    pub fn block(&self) -> &BlockStmt {
+        &self.block
+    }
+}
+
+impl Parse for CatchClause {
+    fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let catch: Catch = input.parse()?;
+        let _left_paren: LeftParen = input.parse()?;
+        let ident: Ident = input.parse()?;
+        let _right_paren: RightParen = input.parse()?;
+        let block: BlockStmt = input.parse()?;
+        Ok(Self {
+            span: Span::range(catch.span(), block.span()),
+            ident,
+            block,
+        })
+    }
+}
+
+impl DisplayTree for CatchClause {
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "CatchClause", layer)?;
+        self.ident.display(f, layer + 1)?;
+        self.block.display(f, layer + 1)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::exprs::Expr;
@@ -10,11 +13,11 @@ use crate::symbols::While;
 use super::block::BlockStmt;
 use super::Stmt;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct WhileStmt {
-    span: Span,
-    cond: Expr,
-    block: BlockStmt,
+    pub(crate) span: Span,
+    pub(crate) cond: Expr,
+    pub(crate) block: BlockStmt,
 }
 
 span!(WhileStmt);
@@ -24,6 +27,10 @@ impl WhileStmt {
         &self.cond
     }
 
+    pub fn block(&self) -> &BlockStmt {
+        &self.block
+    }
+
     pub fn stmts(&self) -> &Vec<Stmt> {
         self.block.stmts()
     }
@@ -43,10 +50,11 @@ impl Parse for WhileStmt {
 }
 
 impl DisplayTree for WhileStmt {
-    fn display(&self, layer: usize) {
-        branch("WhileStmt", layer);
-        branch("Condition", layer + 1);
-        self.cond.display(layer + 2);
-        self.block.display(layer + 1);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, "WhileStmt", layer)?;
+        branch(f, "Condition", layer + 1)?;
+        self.cond.display(f, layer + 2)?;
+        self.block.display(f, layer + 1)?;
+        Ok(())
     }
 }
@@ -1,4 +1,9 @@
+use std::io::{self, Write};
+
+use lumi_lxr::span::Span;
+use lumi_lxr::symbol::Symbol;
 use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::parser::ParseStream;
 use crate::{parse::Parse, parser::ParseError};
@@ -23,10 +28,10 @@ macro_rules! syntax_symbol {
             }
         }
 
-        impl crate::parse::Parse for $struct_name {
+        impl $crate::parse::Parse for $struct_name {
             fn parse(
-                input: &mut crate::parser::ParseStream,
-            ) -> Result<Self, crate::parser::ParseError> {
+                input: &mut $crate::parser::ParseStream,
+            ) -> Result<Self, $crate::parser::ParseError> {
                 Ok($struct_name {
                     span: lumi_lxr::span::Span::from(input.expect($kind)?.span()),
                 })
@@ -36,6 +41,7 @@ macro_rules! syntax_symbol {
 }
 
 syntax_symbol!(Colon, TokenKind::Colon);
+syntax_symbol!(ColonColon, TokenKind::ColonColon);
 syntax_symbol!(Semicolon, TokenKind::Semicolon);
 syntax_symbol!(LeftBrace, TokenKind::LeftBrace);
 syntax_symbol!(RightBrace, TokenKind::RightBrace);
@@ -44,6 +50,12 @@ syntax_symbol!(LeftParen, TokenKind::LeftParen);
 syntax_symbol!(RightParen, TokenKind::RightParen);
 syntax_symbol!(Arrow, TokenKind::MinusGreater);
 syntax_symbol!(Dot, TokenKind::Dot);
+syntax_symbol!(InterrogationDot, TokenKind::InterrogationDot);
+syntax_symbol!(Interrogation, TokenKind::Interrogation);
+syntax_symbol!(Bang, TokenKind::Bang);
+syntax_symbol!(Plus, TokenKind::Plus);
+syntax_symbol!(Less, TokenKind::Less);
+syntax_symbol!(Greater, TokenKind::Greater);
 
 syntax_symbol!(Trait, TokenKind::Trait);
 syntax_symbol!(Class, TokenKind::Class);
@@ -88,12 +100,47 @@ syntax_symbol!(While, TokenKind::While);
 syntax_symbol!(Return, TokenKind::Return);
 syntax_symbol!(Break, TokenKind::Break);
 syntax_symbol!(Continue, TokenKind::Continue);
+syntax_symbol!(Throw, TokenKind::Throw);
+syntax_symbol!(Try, TokenKind::Try);
+syntax_symbol!(Catch, TokenKind::Catch);
+syntax_symbol!(Finally, TokenKind::Finally);
+
+#[derive(Debug, Serialize)]
+pub struct Ident {
+    pub(crate) span: Span,
+    pub(crate) symbol: Symbol,
+}
+
+impl Ident {
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn source_text(&self) -> String {
+        self.span.source_text()
+    }
 
-syntax_symbol!(Ident, TokenKind::Ident);
+    /// Gets the interned name of the identifier.
+    pub fn symbol(&self) -> Symbol {
+        self.symbol
+    }
+}
+
+impl Parse for Ident {
+    fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
+        let token = input.expect(TokenKind::Ident)?;
+        Ok(Self {
+            span: token.span().clone(),
+            symbol: token
+                .symbol()
+                .unwrap_or_else(|| Symbol::intern(token.span().as_str())),
+        })
+    }
+}
 
 impl DisplayTree for Ident {
-    fn display(&self, layer: usize) {
-        branch(&format!("Ident: {}", self.source_text()), layer)
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        branch(f, &format!("Ident: {}", self.symbol), layer)
     }
 }
 
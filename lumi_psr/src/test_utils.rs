@@ -0,0 +1,65 @@
+//! Helpers shared by the tests of the traversal traits.
+
+use lumi_lxr::lexer::Lexer;
+use lumi_lxr::source_code::SourceCode;
+
+use crate::ast::Ast;
+use crate::parser::ParseStream;
+
+/// A program containing every kind of node of the syntax tree.
+pub const EVERY_NODE: &str = r#"
+/// A trait.
+trait Shape<T>: Base {
+    /// Area.
+    fun area() -> Num;
+    fun name() -> Str {
+        return "shape";
+    }
+}
+
+/// A class.
+class Square<T> {
+    /// Side.
+    side: Num,
+}
+
+impl<T> Shape<T> for Square<T> {
+    fun area() -> Num {
+        return this.side * this.side;
+    }
+    extern fun name() -> Str;
+}
+
+const SIDE: Num = 2;
+
+fun main<T>(n: Num?) -> Num {
+    let square: Square<Num> = Square { side: (SIDE) };
+    println -square.area();
+    if !true {
+        throw "no";
+    }
+    while false {
+        continue;
+    }
+    for i in n {
+        break;
+    }
+    try {
+        {}
+    } catch (e) {
+        Shape::name();
+    } finally {
+        n!!;
+    }
+    return n?;
+}
+"#;
+
+pub fn parse(code: &str) -> Ast {
+    let tokens = Lexer::new(SourceCode::new("test.ls", code))
+        .tokens()
+        .unwrap();
+    ParseStream::new(tokens)
+        .parse::<Ast>()
+        .unwrap_or_else(|error| panic!("{}", error.message()))
+}
@@ -1,33 +1,79 @@
+use std::io::{self, Write};
+
 use lumi_lxr::span;
 use lumi_lxr::span::Span;
+use lumi_lxr::token::TokenKind;
+use serde::Serialize;
 
 use crate::display_tree::{branch, DisplayTree};
 use crate::ident;
 use crate::parse::Parse;
 use crate::parser::{ParseError, ParseStream};
-use crate::symbols::Ident;
+use crate::symbols::{Greater, Ident, Interrogation, Less};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Type {
-    span: Span,
-    ident: Ident,
+    pub(crate) span: Span,
+    pub(crate) ident: Ident,
+    pub(crate) args: Vec<Type>,
+    pub(crate) nullable: bool,
 }
 
 span!(Type);
 ident!(Type);
 
+impl Type {
+    /// Gets the type arguments, as `Num` in `List<Num>`.
+    pub fn args(&self) -> &Vec<Type> {
+        &self.args
+    }
+
+    /// Whether the type also accepts `nil`, as in `Num?`.
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+}
+
 impl Parse for Type {
     fn parse(input: &mut ParseStream) -> Result<Self, ParseError> {
         let ident: Ident = input.parse()?;
+        let mut span = ident.span().clone();
+        let mut args = vec![];
+        if input.peek().kind() == TokenKind::Less {
+            let _less: Less = input.parse()?;
+            args.push(input.parse()?);
+            while input.peek().kind() == TokenKind::Comma {
+                input.expect(TokenKind::Comma)?;
+                args.push(input.parse()?);
+            }
+            let greater: Greater = input.parse()?;
+            span = Span::range(ident.span(), greater.span());
+        }
+        let nullable = input.peek().kind() == TokenKind::Interrogation;
+        if nullable {
+            let interrogation: Interrogation = input.parse()?;
+            span = Span::range(ident.span(), interrogation.span());
+        }
         Ok(Self {
-            span: ident.span().clone(),
+            span,
             ident,
+            args,
+            nullable,
         })
     }
 }
 
 impl DisplayTree for Type {
-    fn display(&self, layer: usize) {
-        branch(&format!("Type: {}", self.ident.source_text(),), layer);
+    fn display(&self, f: &mut dyn Write, layer: usize) -> io::Result<()> {
+        let nullable = if self.nullable { "?" } else { "" };
+        branch(
+            f,
+            &format!("Type: {}{}", self.ident.source_text(), nullable),
+            layer,
+        )?;
+        for arg in &self.args {
+            arg.display(f, layer + 1)?;
+        }
+        Ok(())
     }
 }
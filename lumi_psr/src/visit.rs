@@ -0,0 +1,659 @@
+//! Read-only traversal of the syntax tree.
+//!
+//! Every method of `Visit` has a default implementation that calls the
+//! free function with the same name, which visits the children of the
+//! node. An implementation overrides the methods for the nodes it cares
+//! about and calls the free function to keep walking into the children.
+
+use crate::ast::Ast;
+use crate::docs::Docs;
+use crate::exprs::binary::{BinaryExpr, BinaryOp};
+use crate::exprs::call::CallExpr;
+use crate::exprs::class::{ClassExpr, Field as FieldExpr};
+use crate::exprs::get::GetExpr;
+use crate::exprs::ident::IdentExpr;
+use crate::exprs::lit::LitExpr;
+use crate::exprs::paren::ParenExpr;
+use crate::exprs::path::PathExpr;
+use crate::exprs::r#try::TryExpr;
+use crate::exprs::unary::{UnaryExpr, UnaryOp};
+use crate::exprs::unwrap::UnwrapExpr;
+use crate::exprs::Expr;
+use crate::generics::{GenericParam, Generics};
+use crate::param::Param;
+use crate::stmts::block::BlockStmt;
+use crate::stmts::class::{ClassStmt, Field};
+use crate::stmts::expr::ExprStmt;
+use crate::stmts::fun::FunStmt;
+use crate::stmts::println::PrintlnStmt;
+use crate::stmts::r#break::BreakStmt;
+use crate::stmts::r#const::ConstStmt;
+use crate::stmts::r#continue::ContinueStmt;
+use crate::stmts::r#for::ForStmt;
+use crate::stmts::r#if::IfStmt;
+use crate::stmts::r#impl::{ImplStmt, Method};
+use crate::stmts::r#let::LetStmt;
+use crate::stmts::r#return::ReturnStmt;
+use crate::stmts::r#trait::{ProtoMethod, TraitStmt};
+use crate::stmts::r#try::{CatchClause, TryStmt};
+use crate::stmts::r#while::WhileStmt;
+use crate::stmts::throw::ThrowStmt;
+use crate::stmts::Stmt;
+use crate::symbols::Ident;
+use crate::ty::Type;
+
+pub trait Visit<'ast> {
+    fn visit_ast(&mut self, node: &'ast Ast) {
+        visit_ast(self, node)
+    }
+
+    fn visit_stmt(&mut self, node: &'ast Stmt) {
+        visit_stmt(self, node)
+    }
+
+    fn visit_block_stmt(&mut self, node: &'ast BlockStmt) {
+        visit_block_stmt(self, node)
+    }
+
+    fn visit_println_stmt(&mut self, node: &'ast PrintlnStmt) {
+        visit_println_stmt(self, node)
+    }
+
+    fn visit_let_stmt(&mut self, node: &'ast LetStmt) {
+        visit_let_stmt(self, node)
+    }
+
+    fn visit_const_stmt(&mut self, node: &'ast ConstStmt) {
+        visit_const_stmt(self, node)
+    }
+
+    fn visit_if_stmt(&mut self, node: &'ast IfStmt) {
+        visit_if_stmt(self, node)
+    }
+
+    fn visit_while_stmt(&mut self, node: &'ast WhileStmt) {
+        visit_while_stmt(self, node)
+    }
+
+    fn visit_for_stmt(&mut self, node: &'ast ForStmt) {
+        visit_for_stmt(self, node)
+    }
+
+    fn visit_class_stmt(&mut self, node: &'ast ClassStmt) {
+        visit_class_stmt(self, node)
+    }
+
+    fn visit_field(&mut self, node: &'ast Field) {
+        visit_field(self, node)
+    }
+
+    fn visit_trait_stmt(&mut self, node: &'ast TraitStmt) {
+        visit_trait_stmt(self, node)
+    }
+
+    fn visit_proto_method(&mut self, node: &'ast ProtoMethod) {
+        visit_proto_method(self, node)
+    }
+
+    fn visit_impl_stmt(&mut self, node: &'ast ImplStmt) {
+        visit_impl_stmt(self, node)
+    }
+
+    fn visit_method(&mut self, node: &'ast Method) {
+        visit_method(self, node)
+    }
+
+    fn visit_expr_stmt(&mut self, node: &'ast ExprStmt) {
+        visit_expr_stmt(self, node)
+    }
+
+    fn visit_fun_stmt(&mut self, node: &'ast FunStmt) {
+        visit_fun_stmt(self, node)
+    }
+
+    fn visit_continue_stmt(&mut self, node: &'ast ContinueStmt) {
+        visit_continue_stmt(self, node)
+    }
+
+    fn visit_break_stmt(&mut self, node: &'ast BreakStmt) {
+        visit_break_stmt(self, node)
+    }
+
+    fn visit_return_stmt(&mut self, node: &'ast ReturnStmt) {
+        visit_return_stmt(self, node)
+    }
+
+    fn visit_throw_stmt(&mut self, node: &'ast ThrowStmt) {
+        visit_throw_stmt(self, node)
+    }
+
+    fn visit_try_stmt(&mut self, node: &'ast TryStmt) {
+        visit_try_stmt(self, node)
+    }
+
+    fn visit_catch_clause(&mut self, node: &'ast CatchClause) {
+        visit_catch_clause(self, node)
+    }
+
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        visit_expr(self, node)
+    }
+
+    fn visit_ident_expr(&mut self, node: &'ast IdentExpr) {
+        visit_ident_expr(self, node)
+    }
+
+    fn visit_lit_expr(&mut self, node: &'ast LitExpr) {
+        visit_lit_expr(self, node)
+    }
+
+    fn visit_paren_expr(&mut self, node: &'ast ParenExpr) {
+        visit_paren_expr(self, node)
+    }
+
+    fn visit_unary_expr(&mut self, node: &'ast UnaryExpr) {
+        visit_unary_expr(self, node)
+    }
+
+    fn visit_unary_op(&mut self, node: &'ast UnaryOp) {
+        visit_unary_op(self, node)
+    }
+
+    fn visit_binary_expr(&mut self, node: &'ast BinaryExpr) {
+        visit_binary_expr(self, node)
+    }
+
+    fn visit_binary_op(&mut self, node: &'ast BinaryOp) {
+        visit_binary_op(self, node)
+    }
+
+    fn visit_class_expr(&mut self, node: &'ast ClassExpr) {
+        visit_class_expr(self, node)
+    }
+
+    fn visit_field_expr(&mut self, node: &'ast FieldExpr) {
+        visit_field_expr(self, node)
+    }
+
+    fn visit_call_expr(&mut self, node: &'ast CallExpr) {
+        visit_call_expr(self, node)
+    }
+
+    fn visit_get_expr(&mut self, node: &'ast GetExpr) {
+        visit_get_expr(self, node)
+    }
+
+    fn visit_path_expr(&mut self, node: &'ast PathExpr) {
+        visit_path_expr(self, node)
+    }
+
+    fn visit_unwrap_expr(&mut self, node: &'ast UnwrapExpr) {
+        visit_unwrap_expr(self, node)
+    }
+
+    fn visit_try_expr(&mut self, node: &'ast TryExpr) {
+        visit_try_expr(self, node)
+    }
+
+    fn visit_param(&mut self, node: &'ast Param) {
+        visit_param(self, node)
+    }
+
+    fn visit_type(&mut self, node: &'ast Type) {
+        visit_type(self, node)
+    }
+
+    fn visit_generics(&mut self, node: &'ast Generics) {
+        visit_generics(self, node)
+    }
+
+    fn visit_generic_param(&mut self, node: &'ast GenericParam) {
+        visit_generic_param(self, node)
+    }
+
+    fn visit_ident(&mut self, node: &'ast Ident) {
+        visit_ident(self, node)
+    }
+
+    fn visit_docs(&mut self, node: &'ast Docs) {
+        visit_docs(self, node)
+    }
+}
+
+pub fn visit_ast<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Ast) {
+    for stmt in &node.stmts {
+        v.visit_stmt(stmt);
+    }
+}
+
+pub fn visit_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Stmt) {
+    match node {
+        Stmt::Println(println) => v.visit_println_stmt(println),
+        Stmt::Block(block) => v.visit_block_stmt(block),
+        Stmt::Let(r#let) => v.visit_let_stmt(r#let),
+        Stmt::Const(r#const) => v.visit_const_stmt(r#const),
+        Stmt::If(r#if) => v.visit_if_stmt(r#if),
+        Stmt::While(r#while) => v.visit_while_stmt(r#while),
+        Stmt::For(r#for) => v.visit_for_stmt(r#for),
+        Stmt::Class(class) => v.visit_class_stmt(class),
+        Stmt::Trait(r#trait) => v.visit_trait_stmt(r#trait),
+        Stmt::Impl(r#impl) => v.visit_impl_stmt(r#impl),
+        Stmt::Expr(expr) => v.visit_expr_stmt(expr),
+        Stmt::Fun(fun) => v.visit_fun_stmt(fun),
+        Stmt::Continue(r#continue) => v.visit_continue_stmt(r#continue),
+        Stmt::Break(r#break) => v.visit_break_stmt(r#break),
+        Stmt::Return(r#return) => v.visit_return_stmt(r#return),
+        Stmt::Throw(throw) => v.visit_throw_stmt(throw),
+        Stmt::Try(r#try) => v.visit_try_stmt(r#try),
+    }
+}
+
+pub fn visit_block_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast BlockStmt) {
+    for stmt in &node.stmts {
+        v.visit_stmt(stmt);
+    }
+}
+
+pub fn visit_println_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast PrintlnStmt) {
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_let_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast LetStmt) {
+    v.visit_ident(&node.ident);
+    if let Some(ty) = &node.ty {
+        v.visit_type(ty);
+    }
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_const_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ConstStmt) {
+    v.visit_ident(&node.ident);
+    if let Some(ty) = &node.ty {
+        v.visit_type(&ty.ty);
+    }
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_if_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast IfStmt) {
+    v.visit_expr(&node.cond);
+    v.visit_block_stmt(&node.block);
+}
+
+pub fn visit_while_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast WhileStmt) {
+    v.visit_expr(&node.cond);
+    v.visit_block_stmt(&node.block);
+}
+
+pub fn visit_for_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ForStmt) {
+    v.visit_ident(&node.ident);
+    v.visit_expr(&node.iter);
+    v.visit_block_stmt(&node.block);
+}
+
+pub fn visit_class_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ClassStmt) {
+    v.visit_docs(&node.docs);
+    v.visit_ident(&node.ident);
+    v.visit_generics(&node.generics);
+    for field in &node.fields {
+        v.visit_field(field);
+    }
+}
+
+pub fn visit_field<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Field) {
+    v.visit_docs(&node.docs);
+    v.visit_ident(&node.ident);
+    v.visit_type(&node.ty);
+}
+
+pub fn visit_trait_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast TraitStmt) {
+    v.visit_docs(&node.docs);
+    v.visit_ident(&node.ident);
+    v.visit_generics(&node.generics);
+    for supertrait in &node.supertraits {
+        v.visit_ident(supertrait);
+    }
+    for proto in &node.protos {
+        v.visit_proto_method(proto);
+    }
+}
+
+pub fn visit_proto_method<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ProtoMethod) {
+    v.visit_docs(&node.docs);
+    v.visit_ident(&node.ident);
+    for param in &node.params {
+        v.visit_param(param);
+    }
+    if let Some(return_ty) = &node.return_ty {
+        v.visit_type(return_ty);
+    }
+    if let Some(block) = &node.block {
+        v.visit_block_stmt(block);
+    }
+}
+
+pub fn visit_impl_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ImplStmt) {
+    v.visit_generics(&node.generics);
+    if let Some(tr) = &node.tr {
+        v.visit_type(tr);
+    }
+    v.visit_type(&node.ty);
+    for method in &node.methods {
+        v.visit_method(method);
+    }
+}
+
+pub fn visit_method<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Method) {
+    match node {
+        Method::Default {
+            docs,
+            ident,
+            params,
+            return_ty,
+            block,
+            ..
+        } => {
+            v.visit_docs(docs);
+            v.visit_ident(ident);
+            for param in params {
+                v.visit_param(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type(return_ty);
+            }
+            v.visit_block_stmt(block);
+        }
+        Method::Extern {
+            docs,
+            ident,
+            params,
+            return_ty,
+            ..
+        } => {
+            v.visit_docs(docs);
+            v.visit_ident(ident);
+            for param in params {
+                v.visit_param(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type(return_ty);
+            }
+        }
+    }
+}
+
+pub fn visit_expr_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ExprStmt) {
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_fun_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast FunStmt) {
+    match node {
+        FunStmt::Default {
+            docs,
+            ident,
+            generics,
+            params,
+            return_ty,
+            block,
+            ..
+        } => {
+            v.visit_docs(docs);
+            v.visit_ident(ident);
+            v.visit_generics(generics);
+            for param in params {
+                v.visit_param(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type(return_ty);
+            }
+            v.visit_block_stmt(block);
+        }
+        FunStmt::Extern {
+            docs,
+            ident,
+            generics,
+            params,
+            return_ty,
+            ..
+        } => {
+            v.visit_docs(docs);
+            v.visit_ident(ident);
+            v.visit_generics(generics);
+            for param in params {
+                v.visit_param(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type(return_ty);
+            }
+        }
+    }
+}
+
+pub fn visit_continue_stmt<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast ContinueStmt) {}
+
+pub fn visit_break_stmt<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast BreakStmt) {}
+
+pub fn visit_return_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ReturnStmt) {
+    if let Some(expr) = &node.expr {
+        v.visit_expr(expr);
+    }
+}
+
+pub fn visit_throw_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ThrowStmt) {
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_try_stmt<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast TryStmt) {
+    v.visit_block_stmt(&node.block);
+    if let Some(catch) = &node.catch {
+        v.visit_catch_clause(catch);
+    }
+    if let Some(finally) = &node.finally {
+        v.visit_block_stmt(finally);
+    }
+}
+
+pub fn visit_catch_clause<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast CatchClause) {
+    v.visit_ident(&node.ident);
+    v.visit_block_stmt(&node.block);
+}
+
+pub fn visit_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Expr) {
+    match node {
+        Expr::Ident(ident) => v.visit_ident_expr(ident),
+        Expr::Lit(lit) => v.visit_lit_expr(lit),
+        Expr::Paren(paren) => v.visit_paren_expr(paren),
+        Expr::Unary(unary) => v.visit_unary_expr(unary),
+        Expr::Binary(binary) => v.visit_binary_expr(binary),
+        Expr::Class(class) => v.visit_class_expr(class),
+        Expr::Call(call) => v.visit_call_expr(call),
+        Expr::Get(get) => v.visit_get_expr(get),
+        Expr::Path(path) => v.visit_path_expr(path),
+        Expr::Unwrap(unwrap) => v.visit_unwrap_expr(unwrap),
+        Expr::Try(r#try) => v.visit_try_expr(r#try),
+    }
+}
+
+pub fn visit_ident_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast IdentExpr) {
+    v.visit_ident(&node.ident);
+}
+
+pub fn visit_lit_expr<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast LitExpr) {}
+
+pub fn visit_paren_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ParenExpr) {
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_unary_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast UnaryExpr) {
+    v.visit_unary_op(&node.op);
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_unary_op<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast UnaryOp) {}
+
+pub fn visit_binary_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast BinaryExpr) {
+    v.visit_expr(&node.left);
+    v.visit_binary_op(&node.op);
+    v.visit_expr(&node.right);
+}
+
+pub fn visit_binary_op<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast BinaryOp) {}
+
+pub fn visit_class_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast ClassExpr) {
+    v.visit_expr(&node.cls);
+    for field in &node.fields {
+        v.visit_field_expr(field);
+    }
+}
+
+pub fn visit_field_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast FieldExpr) {
+    v.visit_ident(&node.ident);
+    if let Some(value) = &node.value {
+        v.visit_expr(value);
+    }
+}
+
+pub fn visit_call_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast CallExpr) {
+    v.visit_expr(&node.callee);
+    for arg in &node.args {
+        v.visit_expr(arg);
+    }
+}
+
+pub fn visit_get_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast GetExpr) {
+    v.visit_expr(&node.expr);
+    v.visit_ident(&node.ident);
+}
+
+pub fn visit_path_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast PathExpr) {
+    v.visit_ident(&node.ty);
+    v.visit_ident(&node.ident);
+}
+
+pub fn visit_unwrap_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast UnwrapExpr) {
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_try_expr<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast TryExpr) {
+    v.visit_expr(&node.expr);
+}
+
+pub fn visit_param<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Param) {
+    v.visit_ident(&node.ident);
+    v.visit_type(&node.ty);
+}
+
+pub fn visit_type<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Type) {
+    v.visit_ident(&node.ident);
+    for arg in &node.args {
+        v.visit_type(arg);
+    }
+}
+
+pub fn visit_generics<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast Generics) {
+    for param in &node.params {
+        v.visit_generic_param(param);
+    }
+}
+
+pub fn visit_generic_param<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast GenericParam) {
+    v.visit_ident(&node.ident);
+    for bound in &node.bounds {
+        v.visit_ident(bound);
+    }
+}
+
+pub fn visit_ident<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast Ident) {}
+
+pub fn visit_docs<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast Docs) {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::test_utils::{parse, EVERY_NODE};
+
+    use super::*;
+
+    /// Implements every method of `Visit` to record its name before
+    /// walking into the children of the node.
+    macro_rules! recorder {
+        ($($method:ident: $ty:ty),* $(,)?) => {
+            const METHODS: &[&str] = &[$(stringify!($method)),*];
+
+            impl<'ast> Visit<'ast> for Recorder {
+                $(
+                    fn $method(&mut self, node: &'ast $ty) {
+                        self.visited.insert(stringify!($method));
+                        $method(self, node)
+                    }
+                )*
+            }
+        };
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        visited: HashSet<&'static str>,
+    }
+
+    recorder! {
+        visit_ast: Ast,
+        visit_stmt: Stmt,
+        visit_block_stmt: BlockStmt,
+        visit_println_stmt: PrintlnStmt,
+        visit_let_stmt: LetStmt,
+        visit_const_stmt: ConstStmt,
+        visit_if_stmt: IfStmt,
+        visit_while_stmt: WhileStmt,
+        visit_for_stmt: ForStmt,
+        visit_class_stmt: ClassStmt,
+        visit_field: Field,
+        visit_trait_stmt: TraitStmt,
+        visit_proto_method: ProtoMethod,
+        visit_impl_stmt: ImplStmt,
+        visit_method: Method,
+        visit_expr_stmt: ExprStmt,
+        visit_fun_stmt: FunStmt,
+        visit_continue_stmt: ContinueStmt,
+        visit_break_stmt: BreakStmt,
+        visit_return_stmt: ReturnStmt,
+        visit_throw_stmt: ThrowStmt,
+        visit_try_stmt: TryStmt,
+        visit_catch_clause: CatchClause,
+        visit_expr: Expr,
+        visit_ident_expr: IdentExpr,
+        visit_lit_expr: LitExpr,
+        visit_paren_expr: ParenExpr,
+        visit_unary_expr: UnaryExpr,
+        visit_unary_op: UnaryOp,
+        visit_binary_expr: BinaryExpr,
+        visit_binary_op: BinaryOp,
+        visit_class_expr: ClassExpr,
+        visit_field_expr: FieldExpr,
+        visit_call_expr: CallExpr,
+        visit_get_expr: GetExpr,
+        visit_path_expr: PathExpr,
+        visit_unwrap_expr: UnwrapExpr,
+        visit_try_expr: TryExpr,
+        visit_param: Param,
+        visit_type: Type,
+        visit_generics: Generics,
+        visit_generic_param: GenericParam,
+        visit_ident: Ident,
+        visit_docs: Docs,
+    }
+
+    #[test]
+    fn visits_every_node_kind() {
+        let ast = parse(EVERY_NODE);
+        let mut recorder = Recorder::default();
+        recorder.visit_ast(&ast);
+        for method in METHODS {
+            assert!(
+                recorder.visited.contains(method),
+                "{} was not called",
+                method
+            );
+        }
+    }
+}
@@ -0,0 +1,660 @@
+//! In-place traversal of the syntax tree, for passes that change nodes
+//! without replacing them.
+//!
+//! Every method of `VisitMut` has a default implementation that calls the
+//! free function with the same name, which visits the children of the
+//! node. An implementation overrides the methods for the nodes it cares
+//! about and calls the free function to keep walking into the children.
+
+use crate::ast::Ast;
+use crate::docs::Docs;
+use crate::exprs::binary::{BinaryExpr, BinaryOp};
+use crate::exprs::call::CallExpr;
+use crate::exprs::class::{ClassExpr, Field as FieldExpr};
+use crate::exprs::get::GetExpr;
+use crate::exprs::ident::IdentExpr;
+use crate::exprs::lit::LitExpr;
+use crate::exprs::paren::ParenExpr;
+use crate::exprs::path::PathExpr;
+use crate::exprs::r#try::TryExpr;
+use crate::exprs::unary::{UnaryExpr, UnaryOp};
+use crate::exprs::unwrap::UnwrapExpr;
+use crate::exprs::Expr;
+use crate::generics::{GenericParam, Generics};
+use crate::param::Param;
+use crate::stmts::block::BlockStmt;
+use crate::stmts::class::{ClassStmt, Field};
+use crate::stmts::expr::ExprStmt;
+use crate::stmts::fun::FunStmt;
+use crate::stmts::println::PrintlnStmt;
+use crate::stmts::r#break::BreakStmt;
+use crate::stmts::r#const::ConstStmt;
+use crate::stmts::r#continue::ContinueStmt;
+use crate::stmts::r#for::ForStmt;
+use crate::stmts::r#if::IfStmt;
+use crate::stmts::r#impl::{ImplStmt, Method};
+use crate::stmts::r#let::LetStmt;
+use crate::stmts::r#return::ReturnStmt;
+use crate::stmts::r#trait::{ProtoMethod, TraitStmt};
+use crate::stmts::r#try::{CatchClause, TryStmt};
+use crate::stmts::r#while::WhileStmt;
+use crate::stmts::throw::ThrowStmt;
+use crate::stmts::Stmt;
+use crate::symbols::Ident;
+use crate::ty::Type;
+
+pub trait VisitMut {
+    fn visit_ast_mut(&mut self, node: &mut Ast) {
+        visit_ast_mut(self, node)
+    }
+
+    fn visit_stmt_mut(&mut self, node: &mut Stmt) {
+        visit_stmt_mut(self, node)
+    }
+
+    fn visit_block_stmt_mut(&mut self, node: &mut BlockStmt) {
+        visit_block_stmt_mut(self, node)
+    }
+
+    fn visit_println_stmt_mut(&mut self, node: &mut PrintlnStmt) {
+        visit_println_stmt_mut(self, node)
+    }
+
+    fn visit_let_stmt_mut(&mut self, node: &mut LetStmt) {
+        visit_let_stmt_mut(self, node)
+    }
+
+    fn visit_const_stmt_mut(&mut self, node: &mut ConstStmt) {
+        visit_const_stmt_mut(self, node)
+    }
+
+    fn visit_if_stmt_mut(&mut self, node: &mut IfStmt) {
+        visit_if_stmt_mut(self, node)
+    }
+
+    fn visit_while_stmt_mut(&mut self, node: &mut WhileStmt) {
+        visit_while_stmt_mut(self, node)
+    }
+
+    fn visit_for_stmt_mut(&mut self, node: &mut ForStmt) {
+        visit_for_stmt_mut(self, node)
+    }
+
+    fn visit_class_stmt_mut(&mut self, node: &mut ClassStmt) {
+        visit_class_stmt_mut(self, node)
+    }
+
+    fn visit_field_mut(&mut self, node: &mut Field) {
+        visit_field_mut(self, node)
+    }
+
+    fn visit_trait_stmt_mut(&mut self, node: &mut TraitStmt) {
+        visit_trait_stmt_mut(self, node)
+    }
+
+    fn visit_proto_method_mut(&mut self, node: &mut ProtoMethod) {
+        visit_proto_method_mut(self, node)
+    }
+
+    fn visit_impl_stmt_mut(&mut self, node: &mut ImplStmt) {
+        visit_impl_stmt_mut(self, node)
+    }
+
+    fn visit_method_mut(&mut self, node: &mut Method) {
+        visit_method_mut(self, node)
+    }
+
+    fn visit_expr_stmt_mut(&mut self, node: &mut ExprStmt) {
+        visit_expr_stmt_mut(self, node)
+    }
+
+    fn visit_fun_stmt_mut(&mut self, node: &mut FunStmt) {
+        visit_fun_stmt_mut(self, node)
+    }
+
+    fn visit_continue_stmt_mut(&mut self, node: &mut ContinueStmt) {
+        visit_continue_stmt_mut(self, node)
+    }
+
+    fn visit_break_stmt_mut(&mut self, node: &mut BreakStmt) {
+        visit_break_stmt_mut(self, node)
+    }
+
+    fn visit_return_stmt_mut(&mut self, node: &mut ReturnStmt) {
+        visit_return_stmt_mut(self, node)
+    }
+
+    fn visit_throw_stmt_mut(&mut self, node: &mut ThrowStmt) {
+        visit_throw_stmt_mut(self, node)
+    }
+
+    fn visit_try_stmt_mut(&mut self, node: &mut TryStmt) {
+        visit_try_stmt_mut(self, node)
+    }
+
+    fn visit_catch_clause_mut(&mut self, node: &mut CatchClause) {
+        visit_catch_clause_mut(self, node)
+    }
+
+    fn visit_expr_mut(&mut self, node: &mut Expr) {
+        visit_expr_mut(self, node)
+    }
+
+    fn visit_ident_expr_mut(&mut self, node: &mut IdentExpr) {
+        visit_ident_expr_mut(self, node)
+    }
+
+    fn visit_lit_expr_mut(&mut self, node: &mut LitExpr) {
+        visit_lit_expr_mut(self, node)
+    }
+
+    fn visit_paren_expr_mut(&mut self, node: &mut ParenExpr) {
+        visit_paren_expr_mut(self, node)
+    }
+
+    fn visit_unary_expr_mut(&mut self, node: &mut UnaryExpr) {
+        visit_unary_expr_mut(self, node)
+    }
+
+    fn visit_unary_op_mut(&mut self, node: &mut UnaryOp) {
+        visit_unary_op_mut(self, node)
+    }
+
+    fn visit_binary_expr_mut(&mut self, node: &mut BinaryExpr) {
+        visit_binary_expr_mut(self, node)
+    }
+
+    fn visit_binary_op_mut(&mut self, node: &mut BinaryOp) {
+        visit_binary_op_mut(self, node)
+    }
+
+    fn visit_class_expr_mut(&mut self, node: &mut ClassExpr) {
+        visit_class_expr_mut(self, node)
+    }
+
+    fn visit_field_expr_mut(&mut self, node: &mut FieldExpr) {
+        visit_field_expr_mut(self, node)
+    }
+
+    fn visit_call_expr_mut(&mut self, node: &mut CallExpr) {
+        visit_call_expr_mut(self, node)
+    }
+
+    fn visit_get_expr_mut(&mut self, node: &mut GetExpr) {
+        visit_get_expr_mut(self, node)
+    }
+
+    fn visit_path_expr_mut(&mut self, node: &mut PathExpr) {
+        visit_path_expr_mut(self, node)
+    }
+
+    fn visit_unwrap_expr_mut(&mut self, node: &mut UnwrapExpr) {
+        visit_unwrap_expr_mut(self, node)
+    }
+
+    fn visit_try_expr_mut(&mut self, node: &mut TryExpr) {
+        visit_try_expr_mut(self, node)
+    }
+
+    fn visit_param_mut(&mut self, node: &mut Param) {
+        visit_param_mut(self, node)
+    }
+
+    fn visit_type_mut(&mut self, node: &mut Type) {
+        visit_type_mut(self, node)
+    }
+
+    fn visit_generics_mut(&mut self, node: &mut Generics) {
+        visit_generics_mut(self, node)
+    }
+
+    fn visit_generic_param_mut(&mut self, node: &mut GenericParam) {
+        visit_generic_param_mut(self, node)
+    }
+
+    fn visit_ident_mut(&mut self, node: &mut Ident) {
+        visit_ident_mut(self, node)
+    }
+
+    fn visit_docs_mut(&mut self, node: &mut Docs) {
+        visit_docs_mut(self, node)
+    }
+}
+
+pub fn visit_ast_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Ast) {
+    for stmt in &mut node.stmts {
+        v.visit_stmt_mut(stmt);
+    }
+}
+
+pub fn visit_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Stmt) {
+    match node {
+        Stmt::Println(println) => v.visit_println_stmt_mut(println),
+        Stmt::Block(block) => v.visit_block_stmt_mut(block),
+        Stmt::Let(r#let) => v.visit_let_stmt_mut(r#let),
+        Stmt::Const(r#const) => v.visit_const_stmt_mut(r#const),
+        Stmt::If(r#if) => v.visit_if_stmt_mut(r#if),
+        Stmt::While(r#while) => v.visit_while_stmt_mut(r#while),
+        Stmt::For(r#for) => v.visit_for_stmt_mut(r#for),
+        Stmt::Class(class) => v.visit_class_stmt_mut(class),
+        Stmt::Trait(r#trait) => v.visit_trait_stmt_mut(r#trait),
+        Stmt::Impl(r#impl) => v.visit_impl_stmt_mut(r#impl),
+        Stmt::Expr(expr) => v.visit_expr_stmt_mut(expr),
+        Stmt::Fun(fun) => v.visit_fun_stmt_mut(fun),
+        Stmt::Continue(r#continue) => v.visit_continue_stmt_mut(r#continue),
+        Stmt::Break(r#break) => v.visit_break_stmt_mut(r#break),
+        Stmt::Return(r#return) => v.visit_return_stmt_mut(r#return),
+        Stmt::Throw(throw) => v.visit_throw_stmt_mut(throw),
+        Stmt::Try(r#try) => v.visit_try_stmt_mut(r#try),
+    }
+}
+
+pub fn visit_block_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut BlockStmt) {
+    for stmt in &mut node.stmts {
+        v.visit_stmt_mut(stmt);
+    }
+}
+
+pub fn visit_println_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut PrintlnStmt) {
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_let_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut LetStmt) {
+    v.visit_ident_mut(&mut node.ident);
+    if let Some(ty) = &mut node.ty {
+        v.visit_type_mut(ty);
+    }
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_const_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ConstStmt) {
+    v.visit_ident_mut(&mut node.ident);
+    if let Some(ty) = &mut node.ty {
+        v.visit_type_mut(&mut ty.ty);
+    }
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_if_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut IfStmt) {
+    v.visit_expr_mut(&mut node.cond);
+    v.visit_block_stmt_mut(&mut node.block);
+}
+
+pub fn visit_while_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut WhileStmt) {
+    v.visit_expr_mut(&mut node.cond);
+    v.visit_block_stmt_mut(&mut node.block);
+}
+
+pub fn visit_for_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ForStmt) {
+    v.visit_ident_mut(&mut node.ident);
+    v.visit_expr_mut(&mut node.iter);
+    v.visit_block_stmt_mut(&mut node.block);
+}
+
+pub fn visit_class_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ClassStmt) {
+    v.visit_docs_mut(&mut node.docs);
+    v.visit_ident_mut(&mut node.ident);
+    v.visit_generics_mut(&mut node.generics);
+    for field in &mut node.fields {
+        v.visit_field_mut(field);
+    }
+}
+
+pub fn visit_field_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Field) {
+    v.visit_docs_mut(&mut node.docs);
+    v.visit_ident_mut(&mut node.ident);
+    v.visit_type_mut(&mut node.ty);
+}
+
+pub fn visit_trait_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TraitStmt) {
+    v.visit_docs_mut(&mut node.docs);
+    v.visit_ident_mut(&mut node.ident);
+    v.visit_generics_mut(&mut node.generics);
+    for supertrait in &mut node.supertraits {
+        v.visit_ident_mut(supertrait);
+    }
+    for proto in &mut node.protos {
+        v.visit_proto_method_mut(proto);
+    }
+}
+
+pub fn visit_proto_method_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ProtoMethod) {
+    v.visit_docs_mut(&mut node.docs);
+    v.visit_ident_mut(&mut node.ident);
+    for param in &mut node.params {
+        v.visit_param_mut(param);
+    }
+    if let Some(return_ty) = &mut node.return_ty {
+        v.visit_type_mut(return_ty);
+    }
+    if let Some(block) = &mut node.block {
+        v.visit_block_stmt_mut(block);
+    }
+}
+
+pub fn visit_impl_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ImplStmt) {
+    v.visit_generics_mut(&mut node.generics);
+    if let Some(tr) = &mut node.tr {
+        v.visit_type_mut(tr);
+    }
+    v.visit_type_mut(&mut node.ty);
+    for method in &mut node.methods {
+        v.visit_method_mut(method);
+    }
+}
+
+pub fn visit_method_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Method) {
+    match node {
+        Method::Default {
+            docs,
+            ident,
+            params,
+            return_ty,
+            block,
+            ..
+        } => {
+            v.visit_docs_mut(docs);
+            v.visit_ident_mut(ident);
+            for param in params {
+                v.visit_param_mut(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type_mut(return_ty);
+            }
+            v.visit_block_stmt_mut(block);
+        }
+        Method::Extern {
+            docs,
+            ident,
+            params,
+            return_ty,
+            ..
+        } => {
+            v.visit_docs_mut(docs);
+            v.visit_ident_mut(ident);
+            for param in params {
+                v.visit_param_mut(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type_mut(return_ty);
+            }
+        }
+    }
+}
+
+pub fn visit_expr_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ExprStmt) {
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_fun_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut FunStmt) {
+    match node {
+        FunStmt::Default {
+            docs,
+            ident,
+            generics,
+            params,
+            return_ty,
+            block,
+            ..
+        } => {
+            v.visit_docs_mut(docs);
+            v.visit_ident_mut(ident);
+            v.visit_generics_mut(generics);
+            for param in params {
+                v.visit_param_mut(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type_mut(return_ty);
+            }
+            v.visit_block_stmt_mut(block);
+        }
+        FunStmt::Extern {
+            docs,
+            ident,
+            generics,
+            params,
+            return_ty,
+            ..
+        } => {
+            v.visit_docs_mut(docs);
+            v.visit_ident_mut(ident);
+            v.visit_generics_mut(generics);
+            for param in params {
+                v.visit_param_mut(param);
+            }
+            if let Some(return_ty) = return_ty {
+                v.visit_type_mut(return_ty);
+            }
+        }
+    }
+}
+
+pub fn visit_continue_stmt_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut ContinueStmt) {}
+
+pub fn visit_break_stmt_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut BreakStmt) {}
+
+pub fn visit_return_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ReturnStmt) {
+    if let Some(expr) = &mut node.expr {
+        v.visit_expr_mut(expr);
+    }
+}
+
+pub fn visit_throw_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ThrowStmt) {
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_try_stmt_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TryStmt) {
+    v.visit_block_stmt_mut(&mut node.block);
+    if let Some(catch) = &mut node.catch {
+        v.visit_catch_clause_mut(catch);
+    }
+    if let Some(finally) = &mut node.finally {
+        v.visit_block_stmt_mut(finally);
+    }
+}
+
+pub fn visit_catch_clause_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut CatchClause) {
+    v.visit_ident_mut(&mut node.ident);
+    v.visit_block_stmt_mut(&mut node.block);
+}
+
+pub fn visit_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Expr) {
+    match node {
+        Expr::Ident(ident) => v.visit_ident_expr_mut(ident),
+        Expr::Lit(lit) => v.visit_lit_expr_mut(lit),
+        Expr::Paren(paren) => v.visit_paren_expr_mut(paren),
+        Expr::Unary(unary) => v.visit_unary_expr_mut(unary),
+        Expr::Binary(binary) => v.visit_binary_expr_mut(binary),
+        Expr::Class(class) => v.visit_class_expr_mut(class),
+        Expr::Call(call) => v.visit_call_expr_mut(call),
+        Expr::Get(get) => v.visit_get_expr_mut(get),
+        Expr::Path(path) => v.visit_path_expr_mut(path),
+        Expr::Unwrap(unwrap) => v.visit_unwrap_expr_mut(unwrap),
+        Expr::Try(r#try) => v.visit_try_expr_mut(r#try),
+    }
+}
+
+pub fn visit_ident_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut IdentExpr) {
+    v.visit_ident_mut(&mut node.ident);
+}
+
+pub fn visit_lit_expr_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut LitExpr) {}
+
+pub fn visit_paren_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ParenExpr) {
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_unary_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut UnaryExpr) {
+    v.visit_unary_op_mut(&mut node.op);
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_unary_op_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut UnaryOp) {}
+
+pub fn visit_binary_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut BinaryExpr) {
+    v.visit_expr_mut(&mut node.left);
+    v.visit_binary_op_mut(&mut node.op);
+    v.visit_expr_mut(&mut node.right);
+}
+
+pub fn visit_binary_op_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut BinaryOp) {}
+
+pub fn visit_class_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ClassExpr) {
+    v.visit_expr_mut(&mut node.cls);
+    for field in &mut node.fields {
+        v.visit_field_expr_mut(field);
+    }
+}
+
+pub fn visit_field_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut FieldExpr) {
+    v.visit_ident_mut(&mut node.ident);
+    if let Some(value) = &mut node.value {
+        v.visit_expr_mut(value);
+    }
+}
+
+pub fn visit_call_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut CallExpr) {
+    v.visit_expr_mut(&mut node.callee);
+    for arg in &mut node.args {
+        v.visit_expr_mut(arg);
+    }
+}
+
+pub fn visit_get_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut GetExpr) {
+    v.visit_expr_mut(&mut node.expr);
+    v.visit_ident_mut(&mut node.ident);
+}
+
+pub fn visit_path_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut PathExpr) {
+    v.visit_ident_mut(&mut node.ty);
+    v.visit_ident_mut(&mut node.ident);
+}
+
+pub fn visit_unwrap_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut UnwrapExpr) {
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_try_expr_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TryExpr) {
+    v.visit_expr_mut(&mut node.expr);
+}
+
+pub fn visit_param_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Param) {
+    v.visit_ident_mut(&mut node.ident);
+    v.visit_type_mut(&mut node.ty);
+}
+
+pub fn visit_type_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Type) {
+    v.visit_ident_mut(&mut node.ident);
+    for arg in &mut node.args {
+        v.visit_type_mut(arg);
+    }
+}
+
+pub fn visit_generics_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Generics) {
+    for param in &mut node.params {
+        v.visit_generic_param_mut(param);
+    }
+}
+
+pub fn visit_generic_param_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut GenericParam) {
+    v.visit_ident_mut(&mut node.ident);
+    for bound in &mut node.bounds {
+        v.visit_ident_mut(bound);
+    }
+}
+
+pub fn visit_ident_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut Ident) {}
+
+pub fn visit_docs_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut Docs) {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::test_utils::{parse, EVERY_NODE};
+
+    use super::*;
+
+    /// Implements every method of `VisitMut` to record its name before
+    /// walking into the children of the node.
+    macro_rules! recorder {
+        ($($method:ident: $ty:ty),* $(,)?) => {
+            const METHODS: &[&str] = &[$(stringify!($method)),*];
+
+            impl VisitMut for Recorder {
+                $(
+                    fn $method(&mut self, node: &mut $ty) {
+                        self.visited.insert(stringify!($method));
+                        $method(self, node)
+                    }
+                )*
+            }
+        };
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        visited: HashSet<&'static str>,
+    }
+
+    recorder! {
+        visit_ast_mut: Ast,
+        visit_stmt_mut: Stmt,
+        visit_block_stmt_mut: BlockStmt,
+        visit_println_stmt_mut: PrintlnStmt,
+        visit_let_stmt_mut: LetStmt,
+        visit_const_stmt_mut: ConstStmt,
+        visit_if_stmt_mut: IfStmt,
+        visit_while_stmt_mut: WhileStmt,
+        visit_for_stmt_mut: ForStmt,
+        visit_class_stmt_mut: ClassStmt,
+        visit_field_mut: Field,
+        visit_trait_stmt_mut: TraitStmt,
+        visit_proto_method_mut: ProtoMethod,
+        visit_impl_stmt_mut: ImplStmt,
+        visit_method_mut: Method,
+        visit_expr_stmt_mut: ExprStmt,
+        visit_fun_stmt_mut: FunStmt,
+        visit_continue_stmt_mut: ContinueStmt,
+        visit_break_stmt_mut: BreakStmt,
+        visit_return_stmt_mut: ReturnStmt,
+        visit_throw_stmt_mut: ThrowStmt,
+        visit_try_stmt_mut: TryStmt,
+        visit_catch_clause_mut: CatchClause,
+        visit_expr_mut: Expr,
+        visit_ident_expr_mut: IdentExpr,
+        visit_lit_expr_mut: LitExpr,
+        visit_paren_expr_mut: ParenExpr,
+        visit_unary_expr_mut: UnaryExpr,
+        visit_unary_op_mut: UnaryOp,
+        visit_binary_expr_mut: BinaryExpr,
+        visit_binary_op_mut: BinaryOp,
+        visit_class_expr_mut: ClassExpr,
+        visit_field_expr_mut: FieldExpr,
+        visit_call_expr_mut: CallExpr,
+        visit_get_expr_mut: GetExpr,
+        visit_path_expr_mut: PathExpr,
+        visit_unwrap_expr_mut: UnwrapExpr,
+        visit_try_expr_mut: TryExpr,
+        visit_param_mut: Param,
+        visit_type_mut: Type,
+        visit_generics_mut: Generics,
+        visit_generic_param_mut: GenericParam,
+        visit_ident_mut: Ident,
+        visit_docs_mut: Docs,
+    }
+
+    #[test]
+    fn visits_every_node_kind() {
+        let mut ast = parse(EVERY_NODE);
+        let mut recorder = Recorder::default();
+        recorder.visit_ast_mut(&mut ast);
+        for method in METHODS {
+            assert!(
+                recorder.visited.contains(method),
+                "{} was not called",
+                method
+            );
+        }
+    }
+}
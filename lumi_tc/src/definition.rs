@@ -0,0 +1,125 @@
+use lumi_lxr::span::Span;
+use lumi_lxr::symbol::Symbol;
+
+use crate::ty::Ty;
+
+/// The `DefId` identifies a [`Definition`] of a [`TypeChecker`].
+///
+/// [`TypeChecker`]: crate::type_checker::TypeChecker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DefId(pub(crate) usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefKind {
+    Class,
+    Trait,
+    Fun,
+    Method,
+    Field,
+    Param,
+    Let,
+    Const,
+    TypeParam,
+}
+
+/// The `Signature` stores the parameter and return types of a function or
+/// method. Methods don't include `this` in their parameters.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub(crate) params: Vec<Ty>,
+    pub(crate) return_ty: Ty,
+}
+
+/// The `Definition` stores everything known about a declared symbol, such
+/// as a class, a function or a variable.
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub(crate) name: Symbol,
+    pub(crate) kind: DefKind,
+    pub(crate) span: Option<Span>,
+    pub(crate) ty: Ty,
+    pub(crate) detail: String,
+    pub(crate) docs: Option<String>,
+    pub(crate) signature: Option<Signature>,
+    pub(crate) members: Vec<DefId>,
+    /// The supertraits of a trait, or the bounds of a type parameter.
+    pub(crate) supertraits: Vec<DefId>,
+    /// The type parameters of a generic class, trait or function. Methods
+    /// declared by an `impl<T>` block take the parameters of the block, in
+    /// the order they're passed to the implemented class.
+    pub(crate) generics: Vec<DefId>,
+    /// The generic definition this one was instantiated from, such as
+    /// `Box` for `Box<Num>` and its fields, along with the type arguments
+    /// of instantiated classes.
+    pub(crate) origin: Option<DefId>,
+    pub(crate) args: Vec<Ty>,
+    /// Whether a trait method has a default body, inherited by the impl
+    /// blocks that don't override it.
+    pub(crate) default: bool,
+}
+
+impl Definition {
+    pub(crate) fn new(name: Symbol, kind: DefKind, span: Option<Span>, detail: String) -> Self {
+        Self {
+            name,
+            kind,
+            span,
+            ty: Ty::Unknown,
+            detail,
+            docs: None,
+            signature: None,
+            members: vec![],
+            supertraits: vec![],
+            generics: vec![],
+            origin: None,
+            args: vec![],
+            default: false,
+        }
+    }
+
+    pub fn name(&self) -> Symbol {
+        self.name
+    }
+
+    pub fn kind(&self) -> DefKind {
+        self.kind
+    }
+
+    /// Gets the span of the identifier that declares the symbol, or `None`
+    /// for built-in symbols such as `Num`.
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    pub fn ty(&self) -> Ty {
+        self.ty
+    }
+
+    /// Gets a short description of the symbol, such as the signature of a
+    /// function: `fun sum(a: Num, b: Num) -> Num`.
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+
+    pub fn docs(&self) -> Option<&str> {
+        self.docs.as_deref()
+    }
+}
+
+/// The `Reference` links a span of the source code to the definition it
+/// resolves to. Declarations are references to themselves.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub(crate) span: Span,
+    pub(crate) def: DefId,
+}
+
+impl Reference {
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn def(&self) -> DefId {
+        self.def
+    }
+}
@@ -1,9 +1,5 @@
-use lumi_psr::ast::Ast;
-
-pub struct TypeChecker;
-
-impl TypeChecker {
-    pub fn check(_ast: &Ast) {
-        todo!()
-    }
-}
+pub mod definition;
+mod scope;
+pub mod ty;
+pub mod type_checker;
+pub mod type_error;
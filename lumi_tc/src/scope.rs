@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet};
+
+use lumi_lxr::symbol::Symbol;
+
+use crate::definition::DefId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ScopeId(usize);
+
+#[derive(Debug, Clone)]
+struct InnerScope {
+    parent: Option<ScopeId>,
+    symbols: HashMap<Symbol, DefId>,
+    methods: HashMap<(DefId, Symbol), DefId>,
+    statics: HashMap<(DefId, Symbol), DefId>,
+    impls: HashSet<(DefId, DefId)>,
+}
+
+/// The `Scopes` mirror the scopes created by the VM, so symbols and scoped
+/// `impl` methods resolve the same way they do at runtime.
+#[derive(Debug, Clone)]
+pub(crate) struct Scopes {
+    scopes: Vec<InnerScope>,
+}
+
+impl Scopes {
+    pub(crate) fn new() -> Self {
+        Self {
+            scopes: vec![InnerScope {
+                parent: None,
+                symbols: HashMap::new(),
+                methods: HashMap::new(),
+                statics: HashMap::new(),
+                impls: HashSet::new(),
+            }],
+        }
+    }
+
+    pub(crate) fn root(&self) -> ScopeId {
+        ScopeId(0)
+    }
+
+    pub(crate) fn push(&mut self, parent: ScopeId) -> ScopeId {
+        self.scopes.push(InnerScope {
+            parent: Some(parent),
+            symbols: HashMap::new(),
+            methods: HashMap::new(),
+            statics: HashMap::new(),
+            impls: HashSet::new(),
+        });
+        ScopeId(self.scopes.len() - 1)
+    }
+
+    pub(crate) fn set_symbol(&mut self, scope: ScopeId, ident: Symbol, def: DefId) {
+        self.scopes[scope.0].symbols.insert(ident, def);
+    }
+
+    pub(crate) fn symbol(&self, scope: ScopeId, ident: Symbol) -> Option<DefId> {
+        let inner = &self.scopes[scope.0];
+        if let Some(def) = inner.symbols.get(&ident) {
+            Some(*def)
+        } else {
+            self.symbol(inner.parent?, ident)
+        }
+    }
+
+    pub(crate) fn set_method(&mut self, scope: ScopeId, class: DefId, ident: Symbol, def: DefId) {
+        self.scopes[scope.0].methods.insert((class, ident), def);
+    }
+
+    pub(crate) fn method(&self, scope: ScopeId, class: DefId, ident: Symbol) -> Option<DefId> {
+        let inner = &self.scopes[scope.0];
+        if let Some(def) = inner.methods.get(&(class, ident)) {
+            Some(*def)
+        } else {
+            self.method(inner.parent?, class, ident)
+        }
+    }
+
+    pub(crate) fn set_static(&mut self, scope: ScopeId, class: DefId, ident: Symbol, def: DefId) {
+        self.scopes[scope.0].statics.insert((class, ident), def);
+    }
+
+    pub(crate) fn static_method(
+        &self,
+        scope: ScopeId,
+        class: DefId,
+        ident: Symbol,
+    ) -> Option<DefId> {
+        let inner = &self.scopes[scope.0];
+        if let Some(def) = inner.statics.get(&(class, ident)) {
+            Some(*def)
+        } else {
+            self.static_method(inner.parent?, class, ident)
+        }
+    }
+
+    pub(crate) fn set_impl(&mut self, scope: ScopeId, class: DefId, r#trait: DefId) {
+        self.scopes[scope.0].impls.insert((class, r#trait));
+    }
+
+    /// Checks whether an `impl Trait for Class` block is visible from the
+    /// given scope.
+    pub(crate) fn implements(&self, scope: ScopeId, class: DefId, r#trait: DefId) -> bool {
+        let inner = &self.scopes[scope.0];
+        inner.impls.contains(&(class, r#trait))
+            || inner
+                .parent
+                .is_some_and(|parent| self.implements(parent, class, r#trait))
+    }
+}
@@ -0,0 +1,24 @@
+use crate::definition::DefId;
+
+/// The `Ty` is the type of an expression, as inferred by the type checker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ty {
+    /// An instance of a class, including the primitive ones (`Nil`, `Bool`
+    /// and `Num`).
+    Instance(DefId),
+    /// A class used as a value, such as `Point` in `Point { x: 1, y: 2 }`.
+    Class(DefId),
+    /// A function or a method.
+    Fun(DefId),
+    /// The type implementing a trait, written as `This` inside the trait.
+    This,
+    /// A type parameter, such as `T` in `fun max<T: Ord>(a: T, b: T) -> T`.
+    Param(DefId),
+    /// A type that also accepts `nil`, such as `Num?`. The definition
+    /// stores the wrapped type.
+    Nullable(DefId),
+    /// A type that could not be inferred, usually because of a previous
+    /// error. It is compatible with every other type so errors don't
+    /// cascade.
+    Unknown,
+}
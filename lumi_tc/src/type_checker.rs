@@ -0,0 +1,2238 @@
+use std::collections::HashMap;
+
+use lumi_lxr::span::Span;
+use lumi_lxr::symbol::{sym, Symbol};
+use lumi_psr::ast::Ast;
+use lumi_psr::exprs::binary::BinaryExpr;
+use lumi_psr::exprs::call::CallExpr;
+use lumi_psr::exprs::class::ClassExpr;
+use lumi_psr::exprs::get::GetExpr;
+use lumi_psr::exprs::lit::LitExpr;
+use lumi_psr::exprs::path::PathExpr;
+use lumi_psr::exprs::r#try::TryExpr;
+use lumi_psr::exprs::unary::UnaryExpr;
+use lumi_psr::exprs::Expr;
+use lumi_psr::generics::Generics;
+use lumi_psr::param::Param;
+use lumi_psr::stmts::block::BlockStmt;
+use lumi_psr::stmts::class::ClassStmt;
+use lumi_psr::stmts::fun::FunStmt;
+use lumi_psr::stmts::r#impl::{ImplStmt, Method};
+use lumi_psr::stmts::r#return::ReturnStmt;
+use lumi_psr::stmts::r#trait::TraitStmt;
+use lumi_psr::stmts::r#try::TryStmt;
+use lumi_psr::stmts::Stmt;
+use lumi_psr::symbols::Ident;
+use lumi_psr::ty::Type;
+
+use crate::definition::{DefId, DefKind, Definition, Reference, Signature};
+use crate::scope::{ScopeId, Scopes};
+use crate::ty::Ty;
+use crate::type_error::TypeError;
+
+/// A function or method body waiting to be checked.
+///
+/// At runtime a body sees every symbol declared in the scope where the
+/// function was declared, even the ones declared after it, so bodies are
+/// only checked once that scope has been fully declared.
+struct Body<'ast> {
+    scope: ScopeId,
+    def: DefId,
+    this: Option<Ty>,
+    r#trait: Option<DefId>,
+    r#static: bool,
+    params: &'ast [Param],
+    block: &'ast BlockStmt,
+}
+
+/// The `TypeChecker` resolves every symbol of a program following the
+/// same scoping rules as the VM and checks that the types of expressions
+/// match the declared ones.
+///
+/// Several files can be checked by the same instance, one after the other,
+/// sharing their declarations the same way they share a chunk.
+#[derive(Clone)]
+pub struct TypeChecker {
+    defs: Vec<Definition>,
+    references: Vec<Reference>,
+    scopes: Scopes,
+    scope: ScopeId,
+    this: Option<Ty>,
+    /// The trait whose default methods are being checked, which gives the
+    /// methods available on `This`.
+    r#trait: Option<DefId>,
+    /// Whether a static method is being checked, where `This` is available
+    /// but `this` isn't.
+    r#static: bool,
+    returns: Vec<Ty>,
+    errors: Vec<TypeError>,
+    /// The instantiations of generic classes, such as `Box<Num>`, so each
+    /// one is only created once and can be compared by its `DefId`.
+    instances: HashMap<(DefId, Vec<Ty>), DefId>,
+    /// The methods of generic classes and traits with their type
+    /// parameters, or `This`, replaced.
+    specializations: HashMap<(DefId, Vec<(Ty, Ty)>), DefId>,
+    /// The nullable versions of types, such as `Num?`, so each one is
+    /// only created once.
+    nullables: HashMap<Ty, DefId>,
+    nil: DefId,
+    bool: DefId,
+    num: DefId,
+    str: DefId,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        let mut scopes = Scopes::new();
+        let root = scopes.root();
+        let defs = [sym::Nil, sym::Bool, sym::Num, sym::Str]
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let def = DefId(index);
+                scopes.set_symbol(root, name, def);
+                Definition {
+                    ty: Ty::Class(def),
+                    ..Definition::new(name, DefKind::Class, None, format!("class {}", name))
+                }
+            })
+            .collect();
+        Self {
+            defs,
+            references: vec![],
+            scopes,
+            scope: root,
+            this: None,
+            r#trait: None,
+            r#static: false,
+            returns: vec![],
+            errors: vec![],
+            instances: HashMap::new(),
+            specializations: HashMap::new(),
+            nullables: HashMap::new(),
+            nil: DefId(0),
+            bool: DefId(1),
+            num: DefId(2),
+            str: DefId(3),
+        }
+    }
+
+    pub fn check(&mut self, ast: &Ast) -> Result<(), Vec<TypeError>> {
+        self.stmts(ast.stmts());
+        let errors = std::mem::take(&mut self.errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn definition(&self, def: DefId) -> &Definition {
+        &self.defs[def.0]
+    }
+
+    /// Gets every resolved symbol, in the order they were checked.
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    /// Gets a readable name for the given type, as used in error messages.
+    pub fn name(&self, ty: Ty) -> String {
+        match ty {
+            Ty::Instance(def) => {
+                let definition = &self.defs[def.0];
+                if definition.args.is_empty() {
+                    definition.name.to_string()
+                } else {
+                    let args = definition
+                        .args
+                        .iter()
+                        .map(|arg| self.name(*arg))
+                        .collect::<Vec<_>>();
+                    format!("{}<{}>", definition.name, args.join(", "))
+                }
+            }
+            Ty::Class(def) => format!("class {}", self.name(Ty::Instance(def))),
+            Ty::Fun(def) => self.defs[def.0].detail.clone(),
+            Ty::This => "This".to_owned(),
+            Ty::Param(def) => self.defs[def.0].name.to_string(),
+            Ty::Nullable(def) => format!("{}?", self.name(self.defs[def.0].ty)),
+            Ty::Unknown => "unknown".to_owned(),
+        }
+    }
+}
+
+impl TypeChecker {
+    fn error(&mut self, message: String, span: &Span) {
+        self.errors.push(TypeError::new(message, span));
+    }
+
+    fn reference(&mut self, span: &Span, def: DefId) {
+        let def = self.defs[def.0].origin.unwrap_or(def);
+        self.references.push(Reference {
+            span: span.clone(),
+            def,
+        });
+    }
+
+    fn define(&mut self, definition: Definition) -> DefId {
+        let def = DefId(self.defs.len());
+        let span = definition.span.clone();
+        self.defs.push(definition);
+        if let Some(span) = span {
+            self.reference(&span, def);
+        }
+        def
+    }
+
+    fn declare(&mut self, definition: Definition) -> DefId {
+        let name = definition.name;
+        let def = self.define(definition);
+        self.scopes.set_symbol(self.scope, name, def);
+        def
+    }
+
+    fn is_primitive(&self, ty: Ty) -> bool {
+        matches!(ty, Ty::Instance(def) | Ty::Class(def) if def == self.nil || def == self.bool || def == self.num || def == self.str)
+    }
+
+    /// Gets the type accepting both `nil` and the values of the given one.
+    fn nullable(&mut self, ty: Ty) -> Ty {
+        if matches!(ty, Ty::Nullable(_) | Ty::Unknown) || ty == Ty::Instance(self.nil) {
+            return ty;
+        }
+        if let Some(def) = self.nullables.get(&ty) {
+            return Ty::Nullable(*def);
+        }
+        let def = DefId(self.defs.len());
+        self.defs.push(Definition {
+            ty,
+            ..Definition::new(sym::Nil, DefKind::Class, None, String::new())
+        });
+        self.nullables.insert(ty, def);
+        Ty::Nullable(def)
+    }
+
+    /// Gets the type wrapped by a nullable one, as `Num` for `Num?`.
+    fn non_null(&self, ty: Ty) -> Ty {
+        match ty {
+            Ty::Nullable(def) => self.defs[def.0].ty,
+            _ => ty,
+        }
+    }
+
+    fn is_nullable(&self, ty: Ty) -> bool {
+        matches!(ty, Ty::Nullable(_)) || ty == Ty::Instance(self.nil)
+    }
+
+    fn assignable(&self, from: Ty, to: Ty) -> bool {
+        if from == to || from == Ty::Unknown || to == Ty::Unknown {
+            return true;
+        }
+        if let Ty::Nullable(def) = to {
+            return from == Ty::Instance(self.nil)
+                || self.assignable(self.non_null(from), self.defs[def.0].ty);
+        }
+        let (Ty::Instance(from), Ty::Instance(to)) = (from, to) else {
+            return false;
+        };
+        let ((from, from_args), (to, to_args)) = (self.parts(from), self.parts(to));
+        from == to
+            && !from_args.is_empty()
+            && from_args
+                .into_iter()
+                .zip(to_args)
+                .all(|(from, to)| self.assignable(from, to))
+    }
+
+    /// Splits an instantiated class into its generic class and type
+    /// arguments, as `Box` and `Num` for `Box<Num>`. Generic classes used
+    /// inside their own declaration take their parameters as arguments.
+    fn parts(&self, class: DefId) -> (DefId, Vec<Ty>) {
+        let definition = &self.defs[class.0];
+        match definition.origin {
+            Some(origin) => (origin, definition.args.clone()),
+            None => (
+                class,
+                definition
+                    .generics
+                    .iter()
+                    .map(|param| Ty::Param(*param))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn expect(&mut self, found: Ty, expected: Ty, span: &Span) {
+        if !self.assignable(found, expected) {
+            let message = format!(
+                "expected \"{}\", found \"{}\"",
+                self.name(expected),
+                self.name(found)
+            );
+            self.error(message, span);
+        }
+    }
+
+    fn ty(&mut self, ty: &Type) -> Ty {
+        let base = self.base(ty);
+        if ty.is_nullable() {
+            self.nullable(base)
+        } else {
+            base
+        }
+    }
+
+    fn base(&mut self, ty: &Type) -> Ty {
+        let ident = ty.ident();
+        if ident.symbol() == sym::This {
+            return match self.this {
+                Some(this) => this,
+                None => {
+                    let message = "\"This\" can only be used inside impl and trait blocks";
+                    self.error(message.to_owned(), ident.span());
+                    Ty::Unknown
+                }
+            };
+        }
+        match self.scopes.symbol(self.scope, ident.symbol()) {
+            Some(def) => {
+                self.reference(ident.span(), def);
+                let args = ty.args().iter().map(|arg| self.ty(arg)).collect::<Vec<_>>();
+                let kind = self.defs[def.0].kind;
+                match kind {
+                    DefKind::Class if self.arity(def, args.len(), ty.span()) => {
+                        let generics = self.defs[def.0].generics.clone();
+                        self.bounds(&generics, &args, ty.span());
+                        Ty::Instance(self.apply(def, args))
+                    }
+                    DefKind::TypeParam if self.arity(def, args.len(), ty.span()) => Ty::Param(def),
+                    DefKind::Class | DefKind::TypeParam => Ty::Unknown,
+                    _ => {
+                        self.error(
+                            format!("\"{}\" is not a type", ident.symbol()),
+                            ident.span(),
+                        );
+                        Ty::Unknown
+                    }
+                }
+            }
+            None => {
+                let message = format!("type \"{}\" was not found", ident.symbol());
+                self.error(message, ident.span());
+                Ty::Unknown
+            }
+        }
+    }
+
+    /// Checks that a generic class or trait is given as many type arguments
+    /// as it declares type parameters.
+    fn arity(&mut self, def: DefId, found: usize, span: &Span) -> bool {
+        let expected = self.defs[def.0].generics.len();
+        if expected != found {
+            let message = format!(
+                "expected {} type arguments for \"{}\", found {}",
+                expected, self.defs[def.0].name, found
+            );
+            self.error(message, span);
+        }
+        expected == found
+    }
+
+    /// Checks that the type arguments passed to generic parameters
+    /// implement the traits that bound them.
+    fn bounds(&mut self, generics: &[DefId], args: &[Ty], span: &Span) {
+        for (param, arg) in generics.iter().zip(args) {
+            for bound in self.defs[param.0].supertraits.clone() {
+                if !self.satisfies(*arg, bound) {
+                    let message = format!(
+                        "trait \"{}\" is not implemented for \"{}\"",
+                        self.defs[bound.0].name,
+                        self.name(*arg)
+                    );
+                    self.error(message, span);
+                }
+            }
+        }
+    }
+
+    /// Checks whether a type implements a trait, either through an impl
+    /// block in scope or through the bounds of a type parameter.
+    fn satisfies(&self, ty: Ty, r#trait: DefId) -> bool {
+        match ty {
+            Ty::Instance(class) => {
+                let (class, _) = self.parts(class);
+                self.scopes.implements(self.scope, class, r#trait)
+            }
+            Ty::Param(param) => self.defs[param.0]
+                .supertraits
+                .iter()
+                .any(|bound| self.extends(*bound, r#trait)),
+            Ty::This => self
+                .r#trait
+                .is_some_and(|current| self.extends(current, r#trait)),
+            Ty::Unknown => true,
+            Ty::Class(_) | Ty::Fun(_) | Ty::Nullable(_) => false,
+        }
+    }
+
+    /// Checks whether a trait is the given one or one of its supertraits.
+    fn extends(&self, r#trait: DefId, other: DefId) -> bool {
+        r#trait == other
+            || self.defs[r#trait.0]
+                .supertraits
+                .iter()
+                .any(|supertrait| self.extends(*supertrait, other))
+    }
+
+    /// Instantiates a generic class with the given type arguments, creating
+    /// the instantiation with its fields the first time it's used.
+    fn apply(&mut self, class: DefId, args: Vec<Ty>) -> DefId {
+        let (_, params) = self.parts(class);
+        if args.is_empty() || args == params {
+            return class;
+        }
+        if let Some(instance) = self.instances.get(&(class, args.clone())) {
+            return *instance;
+        }
+        let instance = DefId(self.defs.len());
+        self.defs.push(Definition {
+            ty: Ty::Class(instance),
+            generics: vec![],
+            origin: Some(class),
+            args: args.clone(),
+            ..self.defs[class.0].clone()
+        });
+        self.instances.insert((class, args), instance);
+        self.fields(instance);
+        instance
+    }
+
+    /// Copies the fields of a generic class to one of its instantiations,
+    /// with the type parameters replaced by the arguments.
+    fn fields(&mut self, instance: DefId) {
+        let (class, args) = self.parts(instance);
+        let map = self.map(&self.defs[class.0].generics.clone(), args);
+        let mut members = vec![];
+        for field in self.defs[class.0].members.clone() {
+            let ty = self.subst(self.defs[field.0].ty, &map);
+            members.push(DefId(self.defs.len()));
+            self.defs.push(Definition {
+                ty,
+                origin: Some(field),
+                ..self.defs[field.0].clone()
+            });
+        }
+        self.defs[instance.0].members = members;
+    }
+
+    fn map(&self, generics: &[DefId], args: Vec<Ty>) -> Vec<(Ty, Ty)> {
+        generics
+            .iter()
+            .map(|param| Ty::Param(*param))
+            .zip(args)
+            .collect()
+    }
+
+    /// Replaces the type parameters, or `This`, found in a type.
+    fn subst(&mut self, ty: Ty, map: &[(Ty, Ty)]) -> Ty {
+        if let Some((_, to)) = map.iter().find(|(from, _)| *from == ty) {
+            return *to;
+        }
+        match ty {
+            Ty::Instance(class) | Ty::Class(class) => {
+                let (class, args) = self.parts(class);
+                let args = args.into_iter().map(|arg| self.subst(arg, map)).collect();
+                let instance = self.apply(class, args);
+                if matches!(ty, Ty::Instance(_)) {
+                    Ty::Instance(instance)
+                } else {
+                    Ty::Class(instance)
+                }
+            }
+            Ty::Nullable(def) => {
+                let ty = self.subst(self.defs[def.0].ty, map);
+                self.nullable(ty)
+            }
+            _ => ty,
+        }
+    }
+
+    /// Copies a method with the type parameters, or `This`, replaced in its
+    /// signature. The parameters that are replaced are no longer generic.
+    fn specialize(&mut self, method: DefId, map: Vec<(Ty, Ty)>) -> DefId {
+        if map.iter().all(|(from, to)| from == to) {
+            return method;
+        }
+        if let Some(def) = self.specializations.get(&(method, map.clone())) {
+            return *def;
+        }
+        let signature = self.defs[method.0]
+            .signature
+            .clone()
+            .map(|signature| Signature {
+                params: signature
+                    .params
+                    .into_iter()
+                    .map(|param| self.subst(param, &map))
+                    .collect(),
+                return_ty: self.subst(signature.return_ty, &map),
+            });
+        let generics = self.defs[method.0]
+            .generics
+            .iter()
+            .copied()
+            .filter(|param| !map.iter().any(|(from, _)| *from == Ty::Param(*param)))
+            .collect();
+        let def = DefId(self.defs.len());
+        self.defs.push(Definition {
+            ty: Ty::Fun(def),
+            signature,
+            generics,
+            origin: Some(self.defs[method.0].origin.unwrap_or(method)),
+            ..self.defs[method.0].clone()
+        });
+        self.specializations.insert((method, map), def);
+        def
+    }
+
+    /// Infers the type arguments of a generic function from the types of
+    /// its arguments, matching them against the types of its parameters.
+    /// A parameter given both `nil` and another type is inferred as the
+    /// nullable version of that type.
+    fn unify(&mut self, param: Ty, arg: Ty, generics: &[DefId], map: &mut Vec<(Ty, Ty)>) {
+        match (param, arg) {
+            (Ty::Param(def), _) if generics.contains(&def) => {
+                let nil = Ty::Instance(self.nil);
+                match map.iter().position(|(from, _)| *from == param) {
+                    Some(index) => {
+                        let to = map[index].1;
+                        if to == Ty::Unknown {
+                            map[index].1 = arg;
+                        } else if to == nil && arg != nil && arg != Ty::Unknown {
+                            map[index].1 = self.nullable(arg);
+                        } else if arg == nil {
+                            map[index].1 = self.nullable(to);
+                        }
+                    }
+                    None => map.push((param, arg)),
+                }
+            }
+            (Ty::Nullable(param), _) if arg != Ty::Instance(self.nil) => {
+                let (param, arg) = (self.defs[param.0].ty, self.non_null(arg));
+                self.unify(param, arg, generics, map);
+            }
+            (Ty::Instance(param), Ty::Instance(arg)) => {
+                let ((param, params), (arg, args)) = (self.parts(param), self.parts(arg));
+                if param == arg {
+                    for (param, arg) in params.into_iter().zip(args) {
+                        self.unify(param, arg, generics, map);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Declares the type parameters of a generic declaration in the current
+    /// scope, along with the traits that bound them.
+    fn generics(&mut self, generics: &Generics) -> Vec<DefId> {
+        generics
+            .params()
+            .iter()
+            .map(|param| {
+                let bounds = param
+                    .bounds()
+                    .iter()
+                    .filter_map(|bound| self.trait_ref(bound))
+                    .collect();
+                let mut detail = param.ident().source_text();
+                for (index, bound) in param.bounds().iter().enumerate() {
+                    detail.push_str(if index == 0 { ": " } else { " + " });
+                    detail.push_str(&bound.source_text());
+                }
+                let span = param.ident().span().clone();
+                let def = self.declare(Definition {
+                    supertraits: bounds,
+                    ..Definition::new(
+                        param.ident().symbol(),
+                        DefKind::TypeParam,
+                        Some(span),
+                        detail,
+                    )
+                });
+                self.defs[def.0].ty = Ty::Param(def);
+                def
+            })
+            .collect()
+    }
+
+    /// Renders the type parameters of a declaration for its detail, as in
+    /// `<T: Ord, U>`.
+    fn generics_detail(&self, generics: &[DefId]) -> String {
+        if generics.is_empty() {
+            return String::new();
+        }
+        let params = generics
+            .iter()
+            .map(|param| self.defs[param.0].detail.as_str())
+            .collect::<Vec<_>>();
+        format!("<{}>", params.join(", "))
+    }
+
+    fn signature(&mut self, params: &[Param], return_ty: Option<&Type>) -> Signature {
+        Signature {
+            params: params.iter().map(|param| self.ty(param.ty())).collect(),
+            return_ty: return_ty.map_or(Ty::Instance(self.nil), |ty| self.ty(ty)),
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            if let Stmt::Trait(r#trait) = stmt {
+                self.declare_trait(r#trait);
+            }
+        }
+        let mut bodies = vec![];
+        for stmt in stmts {
+            self.stmt(stmt, &mut bodies);
+        }
+        for body in bodies {
+            self.body(body);
+        }
+    }
+
+    fn block(&mut self, block: &BlockStmt) {
+        let scope = self.scope;
+        self.scope = self.scopes.push(scope);
+        self.stmts(block.stmts());
+        self.scope = scope;
+    }
+
+    /// Checks the block of an `if` or a `while`, where the variable their
+    /// condition proves not to be nil is no longer nullable.
+    fn guarded(&mut self, block: &BlockStmt, narrowing: &[(Symbol, DefId, bool)]) {
+        let scope = self.scope;
+        self.scope = self.scopes.push(scope);
+        for (name, def, not_nil) in narrowing {
+            if *not_nil {
+                self.narrow(*name, *def);
+            }
+        }
+        self.stmts(block.stmts());
+        self.scope = scope;
+    }
+
+    /// Finds the nullable variables compared to `nil` by a condition such as
+    /// `x != nil`, along with whether the condition holds when they aren't
+    /// nil. Both sides of `&&` hold when it's true and neither side of `||`
+    /// holds when it's false.
+    fn narrowing(&self, cond: &Expr) -> Vec<(Symbol, DefId, bool)> {
+        let Expr::Binary(binary) = cond else {
+            return vec![];
+        };
+        let not_nil = match binary.op().span().as_str() {
+            "!=" => true,
+            "==" => false,
+            op @ ("&&" | "||") => {
+                let mut narrowing = self.narrowing(binary.left());
+                narrowing.extend(self.narrowing(binary.right()));
+                narrowing.retain(|(_, _, not_nil)| *not_nil == (op == "&&"));
+                return narrowing;
+            }
+            _ => return vec![],
+        };
+        let ident = match (binary.left(), binary.right()) {
+            (Expr::Ident(ident), Expr::Lit(LitExpr::Nil { .. }))
+            | (Expr::Lit(LitExpr::Nil { .. }), Expr::Ident(ident)) => ident.ident(),
+            _ => return vec![],
+        };
+        let Some(def) = self.scopes.symbol(self.scope, ident.symbol()) else {
+            return vec![];
+        };
+        let definition = &self.defs[def.0];
+        let variable = matches!(
+            definition.kind,
+            DefKind::Let | DefKind::Param | DefKind::Const
+        );
+        if variable && matches!(definition.ty, Ty::Nullable(_)) {
+            vec![(ident.symbol(), def, not_nil)]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Declares a nullable variable again in the current scope, with the
+    /// type it has once it's known not to be nil.
+    fn narrow(&mut self, name: Symbol, def: DefId) {
+        let narrowed = DefId(self.defs.len());
+        self.defs.push(Definition {
+            ty: self.non_null(self.defs[def.0].ty),
+            origin: Some(self.defs[def.0].origin.unwrap_or(def)),
+            ..self.defs[def.0].clone()
+        });
+        self.scopes.set_symbol(self.scope, name, narrowed);
+    }
+
+    fn body(&mut self, body: Body) {
+        let (scope, this, r#trait, r#static) = (self.scope, self.this, self.r#trait, self.r#static);
+        self.scope = self.scopes.push(body.scope);
+        self.this = body.this;
+        self.r#trait = body.r#trait;
+        self.r#static = body.r#static;
+        let signature = self.defs[body.def.0].signature.clone().unwrap();
+        for (param, ty) in body.params.iter().zip(signature.params) {
+            let detail = format!(
+                "{}: {}",
+                param.ident().source_text(),
+                param.ty().span().source_text()
+            );
+            let span = param.ident().span().clone();
+            self.declare(Definition {
+                ty,
+                ..Definition::new(param.ident().symbol(), DefKind::Param, Some(span), detail)
+            });
+        }
+        self.returns.push(signature.return_ty);
+        self.stmts(body.block.stmts());
+        self.returns.pop();
+        self.scope = scope;
+        self.this = this;
+        self.r#trait = r#trait;
+        self.r#static = r#static;
+    }
+
+    fn stmt<'ast>(&mut self, stmt: &'ast Stmt, bodies: &mut Vec<Body<'ast>>) {
+        match stmt {
+            Stmt::Println(println) => {
+                self.expr(println.expr());
+            }
+            Stmt::Block(block) => self.block(block),
+            Stmt::Let(r#let) => {
+                let ty = self.variable(r#let.expr(), r#let.ty());
+                let detail = format!("let {}: {}", r#let.ident().source_text(), self.name(ty));
+                let span = r#let.ident().span().clone();
+                self.declare(Definition {
+                    ty,
+                    ..Definition::new(r#let.ident().symbol(), DefKind::Let, Some(span), detail)
+                });
+            }
+            Stmt::Const(r#const) => {
+                let ty = self.variable(r#const.expr(), r#const.ty());
+                let detail = format!("const {}: {}", r#const.ident().source_text(), self.name(ty));
+                let span = r#const.ident().span().clone();
+                self.declare(Definition {
+                    ty,
+                    ..Definition::new(r#const.ident().symbol(), DefKind::Const, Some(span), detail)
+                });
+            }
+            Stmt::If(r#if) => {
+                let narrowing = self.narrowing(r#if.cond());
+                self.cond(r#if.cond());
+                self.guarded(r#if.block(), &narrowing);
+                if diverges(r#if.block()) {
+                    for (name, def, not_nil) in narrowing {
+                        if !not_nil {
+                            self.narrow(name, def);
+                        }
+                    }
+                }
+            }
+            Stmt::While(r#while) => {
+                let span = Span::range(r#while.span(), r#while.cond().span());
+                self.error("while loops are not supported yet".to_owned(), &span);
+                let narrowing = self.narrowing(r#while.cond());
+                self.cond(r#while.cond());
+                self.guarded(r#while.block(), &narrowing);
+            }
+            Stmt::For(r#for) => {
+                let span = Span::range(r#for.span(), r#for.iter().span());
+                self.error("for loops are not supported yet".to_owned(), &span);
+                self.expr(r#for.iter());
+                let ty = match r#for.iter() {
+                    Expr::Binary(binary) if matches!(binary.op().span().as_str(), ".." | "..=") => {
+                        Ty::Instance(self.num)
+                    }
+                    _ => Ty::Unknown,
+                };
+                let scope = self.scope;
+                self.scope = self.scopes.push(scope);
+                let detail = format!("let {}: {}", r#for.ident().source_text(), self.name(ty));
+                let span = r#for.ident().span().clone();
+                self.declare(Definition {
+                    ty,
+                    ..Definition::new(r#for.ident().symbol(), DefKind::Let, Some(span), detail)
+                });
+                self.stmts(r#for.block().stmts());
+                self.scope = scope;
+            }
+            Stmt::Class(class) => self.class(class),
+            Stmt::Trait(r#trait) => self.r#trait(r#trait, bodies),
+            Stmt::Impl(r#impl) => self.r#impl(r#impl, bodies),
+            Stmt::Expr(expr) => {
+                self.expr(expr.expr());
+            }
+            Stmt::Fun(fun) => self.fun(fun, bodies),
+            Stmt::Continue(r#continue) => {
+                let message = "continue statements are not supported yet";
+                self.error(message.to_owned(), r#continue.span());
+            }
+            Stmt::Break(r#break) => {
+                let message = "break statements are not supported yet";
+                self.error(message.to_owned(), r#break.span());
+            }
+            Stmt::Return(r#return) => self.r#return(r#return),
+            Stmt::Throw(throw) => {
+                let ty = self.expr(throw.expr());
+                let error = self.error_ty();
+                self.expect(ty, error, throw.expr().span());
+            }
+            Stmt::Try(r#try) => self.r#try(r#try),
+        }
+    }
+
+    fn variable(&mut self, expr: &Expr, ty: Option<&Type>) -> Ty {
+        let found = self.expr(expr);
+        match ty {
+            Some(ty) => {
+                let expected = self.ty(ty);
+                self.expect(found, expected, expr.span());
+                expected
+            }
+            None => found,
+        }
+    }
+
+    fn cond(&mut self, cond: &Expr) {
+        let ty = self.expr(cond);
+        if self.method(ty, sym::toBool).is_none() {
+            self.expect(ty, Ty::Instance(self.bool), cond.span());
+        }
+    }
+
+    fn r#try(&mut self, r#try: &TryStmt) {
+        self.block(r#try.block());
+        if let Some(catch) = r#try.catch() {
+            let scope = self.scope;
+            self.scope = self.scopes.push(scope);
+            let ty = self.error_ty();
+            let detail = format!("let {}: {}", catch.ident().source_text(), self.name(ty));
+            let span = catch.ident().span().clone();
+            self.declare(Definition {
+                ty,
+                ..Definition::new(catch.ident().symbol(), DefKind::Let, Some(span), detail)
+            });
+            self.stmts(catch.block().stmts());
+            self.scope = scope;
+        }
+        if let Some(finally) = r#try.finally() {
+            self.block(finally);
+        }
+    }
+
+    /// Gets the type of the values that can be thrown, the `Error` class
+    /// declared by `core.ls`.
+    fn error_ty(&self) -> Ty {
+        self.prelude(sym::Error).map_or(Ty::Unknown, Ty::Instance)
+    }
+
+    /// Finds a class declared by `core.ls`.
+    fn prelude(&self, name: Symbol) -> Option<DefId> {
+        self.scopes
+            .symbol(self.scopes.root(), name)
+            .filter(|def| self.defs[def.0].kind == DefKind::Class)
+    }
+
+    fn r#return(&mut self, r#return: &ReturnStmt) {
+        let (ty, span) = match r#return.expr() {
+            Some(expr) => (self.expr(expr), expr.span()),
+            None => (Ty::Instance(self.nil), r#return.span()),
+        };
+        if let Some(expected) = self.returns.last().copied() {
+            self.expect(ty, expected, span);
+        }
+    }
+
+    fn class(&mut self, class: &ClassStmt) {
+        let ident = class.ident();
+        let def = self.declare(Definition {
+            docs: class.docs().text(),
+            ..Definition::new(
+                ident.symbol(),
+                DefKind::Class,
+                Some(ident.span().clone()),
+                format!("class {}", ident.source_text()),
+            )
+        });
+        self.defs[def.0].ty = Ty::Class(def);
+        let scope = self.scope;
+        self.scope = self.scopes.push(scope);
+        let generics = self.generics(class.generics());
+        let detail = self.generics_detail(&generics);
+        self.defs[def.0].detail.push_str(&detail);
+        self.defs[def.0].generics = generics;
+        let members = class
+            .fields()
+            .iter()
+            .map(|field| {
+                let ty = self.ty(field.ty());
+                let detail = format!(
+                    "{}: {}",
+                    field.ident().source_text(),
+                    field.ty().span().source_text()
+                );
+                let span = field.ident().span().clone();
+                self.define(Definition {
+                    ty,
+                    docs: field.docs().text(),
+                    ..Definition::new(field.ident().symbol(), DefKind::Field, Some(span), detail)
+                })
+            })
+            .collect();
+        self.scope = scope;
+        self.defs[def.0].members = members;
+        let instances = self
+            .instances
+            .iter()
+            .filter(|((class, _), _)| *class == def)
+            .map(|(_, instance)| *instance)
+            .collect::<Vec<_>>();
+        for instance in instances {
+            self.fields(instance);
+        }
+    }
+
+    /// Declares a trait before the statements of its block are checked, so
+    /// supertraits and bounds can name traits declared further down.
+    fn declare_trait(&mut self, r#trait: &TraitStmt) -> DefId {
+        let ident = r#trait.ident();
+        self.declare(Definition {
+            docs: r#trait.docs().text(),
+            ..Definition::new(
+                ident.symbol(),
+                DefKind::Trait,
+                Some(ident.span().clone()),
+                format!("trait {}", ident.source_text()),
+            )
+        })
+    }
+
+    fn r#trait<'ast>(&mut self, r#trait: &'ast TraitStmt, bodies: &mut Vec<Body<'ast>>) {
+        let ident = r#trait.ident();
+        let scope = self.scope;
+        // A trait sharing its name with one declared later in the same block
+        // was shadowed by it, so it's declared again.
+        let def = self
+            .scopes
+            .symbol(scope, ident.symbol())
+            .filter(|def| self.defs[def.0].span.as_ref() == Some(ident.span()))
+            .unwrap_or_else(|| self.declare_trait(r#trait));
+        let inner = self.scopes.push(scope);
+        self.scope = inner;
+        let generics = self.generics(r#trait.generics());
+        self.scope = scope;
+        let mut supertraits = vec![];
+        for supertrait in r#trait.supertraits() {
+            let Some(found) = self.trait_ref(supertrait) else {
+                continue;
+            };
+            if self.extends(found, def) {
+                let message = format!("trait \"{}\" can't extend itself", ident.symbol());
+                self.error(message, supertrait.span());
+            } else {
+                supertraits.push(found);
+            }
+        }
+        let mut header = format!(
+            "trait {}{}",
+            ident.source_text(),
+            self.generics_detail(&generics)
+        );
+        for (index, supertrait) in r#trait.supertraits().iter().enumerate() {
+            header.push_str(if index == 0 { ": " } else { " + " });
+            header.push_str(&supertrait.source_text());
+        }
+        self.defs[def.0].detail = header;
+        self.defs[def.0].supertraits = supertraits;
+        self.defs[def.0].generics = generics;
+        self.scope = inner;
+        let this = self.this.replace(Ty::This);
+        let mut members = vec![];
+        for proto in r#trait.protos() {
+            let signature = self.signature(proto.params(), proto.return_ty());
+            let detail = detail(
+                &proto.ident().source_text(),
+                proto.params(),
+                proto.return_ty(),
+            );
+            let span = proto.ident().span().clone();
+            let method = self.define(Definition {
+                docs: proto.docs().text(),
+                signature: Some(signature),
+                default: proto.block().is_some(),
+                ..Definition::new(proto.ident().symbol(), DefKind::Method, Some(span), detail)
+            });
+            self.defs[method.0].ty = Ty::Fun(method);
+            members.push(method);
+            if let Some(block) = proto.block() {
+                bodies.push(Body {
+                    scope: self.scope,
+                    def: method,
+                    this: Some(Ty::This),
+                    r#trait: Some(def),
+                    r#static: false,
+                    params: proto.params(),
+                    block,
+                });
+            }
+        }
+        self.this = this;
+        self.scope = scope;
+        self.defs[def.0].members = members;
+    }
+
+    fn r#impl<'ast>(&mut self, r#impl: &'ast ImplStmt, bodies: &mut Vec<Body<'ast>>) {
+        let scope = self.scope;
+        self.scope = self.scopes.push(scope);
+        let generics = self.generics(r#impl.generics());
+        let r#trait = r#impl.tr().and_then(|tr| self.trait_ty(tr));
+        let Ty::Instance(target) = self.ty(r#impl.ty()) else {
+            self.scope = scope;
+            return;
+        };
+        let (class, args) = self.parts(target);
+        let params = args
+            .iter()
+            .map(|arg| match arg {
+                Ty::Param(param) if generics.contains(param) => Some(*param),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|params| {
+                params.len() == generics.len()
+                    && generics.iter().all(|param| params.contains(param))
+            });
+        let Some(params) = params else {
+            let message = format!(
+                "the type arguments of \"{}\" must be the type parameters of the impl block",
+                self.defs[class.0].name
+            );
+            self.error(message, r#impl.ty().span());
+            self.scope = scope;
+            return;
+        };
+        let this = self.this.replace(Ty::Instance(target));
+        let mut methods = vec![];
+        for method in r#impl.methods() {
+            let signature = self.signature(method.params(), method.return_ty());
+            let mut detail = detail(
+                &method.ident().source_text(),
+                method.params(),
+                method.return_ty(),
+            );
+            if method.is_static() {
+                detail.insert_str(0, "static ");
+            }
+            let span = method.ident().span().clone();
+            let def = self.define(Definition {
+                docs: method.docs().text(),
+                signature: Some(signature),
+                generics: params.clone(),
+                ..Definition::new(method.ident().symbol(), DefKind::Method, Some(span), detail)
+            });
+            self.defs[def.0].ty = Ty::Fun(def);
+            if method.is_static() {
+                self.scopes
+                    .set_static(scope, class, method.ident().symbol(), def);
+            } else {
+                self.scopes
+                    .set_method(scope, class, method.ident().symbol(), def);
+            }
+            methods.push(def);
+            if let Method::Default { params, block, .. } = method {
+                bodies.push(Body {
+                    scope: self.scope,
+                    def,
+                    this: self.this,
+                    r#trait: None,
+                    r#static: method.is_static(),
+                    params,
+                    block,
+                });
+            }
+        }
+        self.this = this;
+        if let (Some(tr), Some((r#trait, args))) = (r#impl.tr(), r#trait) {
+            let mut map = self.map(&self.defs[r#trait.0].generics.clone(), args);
+            map.push((Ty::This, Ty::Instance(target)));
+            if self.scopes.implements(scope, class, r#trait) {
+                let message = format!(
+                    "trait \"{}\" is already implemented for \"{}\"",
+                    tr.ident().symbol(),
+                    self.defs[class.0].name
+                );
+                self.error(message, r#impl.ty().span());
+            }
+            self.conformance(r#impl, tr, r#trait, &map, &methods);
+            self.inherit(r#impl, r#trait, &map, scope, class, &params);
+            for supertrait in self.defs[r#trait.0].supertraits.clone() {
+                if !self.scopes.implements(scope, class, supertrait) {
+                    let message = format!(
+                        "trait \"{}\" requires \"{}\" to be implemented for \"{}\"",
+                        tr.ident().symbol(),
+                        self.defs[supertrait.0].name,
+                        self.defs[class.0].name
+                    );
+                    self.error(message, r#impl.ty().span());
+                }
+            }
+            self.scopes.set_impl(scope, class, r#trait);
+        }
+        self.scope = scope;
+    }
+
+    /// Adds the default methods of a trait that an impl block doesn't
+    /// override to the implementing class, with `This` and the type
+    /// parameters of the trait replaced as given by `map`.
+    fn inherit(
+        &mut self,
+        r#impl: &ImplStmt,
+        r#trait: DefId,
+        map: &[(Ty, Ty)],
+        scope: ScopeId,
+        class: DefId,
+        params: &[DefId],
+    ) {
+        for proto in self.defs[r#trait.0].members.clone() {
+            let name = self.defs[proto.0].name;
+            if !self.defs[proto.0].default
+                || r#impl
+                    .methods()
+                    .iter()
+                    .any(|method| !method.is_static() && method.ident().symbol() == name)
+            {
+                continue;
+            }
+            let def = self.specialize(proto, map.to_vec());
+            self.defs[def.0].generics = params.to_vec();
+            self.scopes.set_method(scope, class, name, def);
+        }
+    }
+
+    /// Resolves a trait, such as a supertrait or the bound of a type
+    /// parameter.
+    fn trait_ref(&mut self, tr: &Ident) -> Option<DefId> {
+        match self.scopes.symbol(self.scope, tr.symbol()) {
+            Some(def) if self.defs[def.0].kind == DefKind::Trait => {
+                self.reference(tr.span(), def);
+                Some(def)
+            }
+            Some(def) => {
+                self.reference(tr.span(), def);
+                self.error(format!("\"{}\" is not a trait", tr.symbol()), tr.span());
+                None
+            }
+            None => {
+                let message = format!("trait \"{}\" was not found", tr.symbol());
+                self.error(message, tr.span());
+                None
+            }
+        }
+    }
+
+    /// Resolves the trait of an `impl Trait for Type` block along with its
+    /// type arguments.
+    fn trait_ty(&mut self, tr: &Type) -> Option<(DefId, Vec<Ty>)> {
+        let r#trait = self.trait_ref(tr.ident());
+        let args = tr.args().iter().map(|arg| self.ty(arg)).collect::<Vec<_>>();
+        let r#trait = r#trait?;
+        self.arity(r#trait, args.len(), tr.span())
+            .then_some((r#trait, args))
+    }
+
+    /// Checks that the methods of an `impl Trait for Type` block match the
+    /// ones declared by the trait, with `This` standing for `this`.
+    fn conformance(
+        &mut self,
+        r#impl: &ImplStmt,
+        tr: &Type,
+        r#trait: DefId,
+        map: &[(Ty, Ty)],
+        methods: &[DefId],
+    ) {
+        let tr = tr.ident().symbol();
+        let protos = self.defs[r#trait.0].members.clone();
+        for proto in &protos {
+            let name = self.defs[proto.0].name;
+            let found = r#impl
+                .methods()
+                .iter()
+                .zip(methods)
+                .find(|(method, _)| !method.is_static() && method.ident().symbol() == name);
+            let Some((method, def)) = found else {
+                if self.defs[proto.0].default {
+                    continue;
+                }
+                let message = format!("missing method \"{}\" of trait \"{}\"", name, tr);
+                self.error(message, r#impl.ty().span());
+                continue;
+            };
+            let expected = self.defs[proto.0].signature.clone().unwrap();
+            let found = self.defs[def.0].signature.clone().unwrap();
+            if expected.params.len() != found.params.len() {
+                let message = format!(
+                    "method \"{}\" of trait \"{}\" expects {} parameters, found {}",
+                    name,
+                    tr,
+                    expected.params.len(),
+                    found.params.len()
+                );
+                self.error(message, method.ident().span());
+                continue;
+            }
+            for ((expected, found), param) in expected
+                .params
+                .iter()
+                .zip(&found.params)
+                .zip(method.params())
+            {
+                let expected = self.subst(*expected, map);
+                self.conform(expected, *found, tr, param.ty().span());
+            }
+            let span = method.return_ty().map_or(method.ident().span(), Type::span);
+            let expected = self.subst(expected.return_ty, map);
+            self.conform(expected, found.return_ty, tr, span);
+        }
+        for method in r#impl.methods() {
+            let name = method.ident().symbol();
+            if method.is_static() || !protos.iter().any(|proto| self.defs[proto.0].name == name) {
+                let message = format!("method \"{}\" is not a member of trait \"{}\"", name, tr);
+                self.error(message, method.ident().span());
+            }
+        }
+    }
+
+    fn conform(&mut self, expected: Ty, found: Ty, tr: Symbol, span: &Span) {
+        if expected != found && expected != Ty::Unknown && found != Ty::Unknown {
+            let message = format!(
+                "expected \"{}\" as declared by trait \"{}\", found \"{}\"",
+                self.name(expected),
+                tr,
+                self.name(found)
+            );
+            self.error(message, span);
+        }
+    }
+
+    fn fun<'ast>(&mut self, fun: &'ast FunStmt, bodies: &mut Vec<Body<'ast>>) {
+        let scope = self.scope;
+        let inner = self.scopes.push(scope);
+        self.scope = inner;
+        let generics = self.generics(fun.generics());
+        let signature = self.signature(fun.params(), fun.return_ty());
+        self.scope = scope;
+        let name = format!(
+            "{}{}",
+            fun.ident().source_text(),
+            self.generics_detail(&generics)
+        );
+        let detail = detail(&name, fun.params(), fun.return_ty());
+        let span = fun.ident().span().clone();
+        let def = self.declare(Definition {
+            docs: fun.docs().text(),
+            signature: Some(signature),
+            generics,
+            ..Definition::new(fun.ident().symbol(), DefKind::Fun, Some(span), detail)
+        });
+        self.defs[def.0].ty = Ty::Fun(def);
+        if let FunStmt::Default { params, block, .. } = fun {
+            bodies.push(Body {
+                scope: inner,
+                def,
+                this: None,
+                r#trait: None,
+                r#static: false,
+                params,
+                block,
+            });
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Ty {
+        match expr {
+            Expr::Ident(ident) => self.ident(ident.ident()),
+            Expr::Lit(LitExpr::Num { .. }) => Ty::Instance(self.num),
+            Expr::Lit(LitExpr::Bool { .. }) => Ty::Instance(self.bool),
+            Expr::Lit(LitExpr::Nil { .. }) => Ty::Instance(self.nil),
+            Expr::Lit(LitExpr::Str { .. }) => Ty::Instance(self.str),
+            Expr::Paren(paren) => self.expr(paren.expr()),
+            Expr::Unary(unary) => self.unary(unary),
+            Expr::Binary(binary) => self.binary(binary),
+            Expr::Class(class) => self.instantiate(class),
+            Expr::Call(call) => self.call(call),
+            Expr::Get(get) => self.get(get),
+            Expr::Path(path) => self.path(path),
+            Expr::Unwrap(unwrap) => {
+                let ty = self.expr(unwrap.expr());
+                self.non_null(ty)
+            }
+            Expr::Try(r#try) => self.propagate(r#try),
+        }
+    }
+
+    /// Checks a `?`, which gives the value of a `Result` or an `Option` and
+    /// returns the error, or the missing value, from the enclosing function
+    /// otherwise. The function must return the same kind of type, with an
+    /// error type accepting the one returned early.
+    fn propagate(&mut self, r#try: &TryExpr) -> Ty {
+        let ty = self.expr(r#try.expr());
+        let Some(expected) = self.returns.last().copied() else {
+            let message = "the \"?\" operator can only be used inside functions";
+            self.error(message.to_owned(), r#try.span());
+            return Ty::Unknown;
+        };
+        let parts = match ty {
+            Ty::Instance(def) => Some(self.parts(def)),
+            _ => None,
+        };
+        let Some((class, args)) = parts.filter(|(class, _)| {
+            [sym::Result, sym::Option]
+                .into_iter()
+                .any(|name| self.prelude(name) == Some(*class))
+        }) else {
+            if ty != Ty::Unknown {
+                let message = format!(
+                    "the \"?\" operator can only be applied to a \"Result\" or an \"Option\", found \"{}\"",
+                    self.name(ty)
+                );
+                self.error(message, r#try.span());
+            }
+            return Ty::Unknown;
+        };
+        let value = args.first().copied().unwrap_or(Ty::Unknown);
+        let mut early = args;
+        if let Some(value) = early.first_mut() {
+            *value = Ty::Unknown;
+        }
+        let early = Ty::Instance(self.apply(class, early));
+        if !self.assignable(early, expected) {
+            let message = format!(
+                "the \"?\" operator may return \"{}\" early, but the function returns \"{}\"",
+                self.name(ty),
+                self.name(expected)
+            );
+            self.error(message, r#try.span());
+        }
+        value
+    }
+
+    fn ident(&mut self, ident: &Ident) -> Ty {
+        if ident.symbol() == sym::this {
+            return match self.this {
+                Some(_) if self.r#static => {
+                    let message = "\"this\" cannot be used inside static methods";
+                    self.error(message.to_owned(), ident.span());
+                    Ty::Unknown
+                }
+                Some(this) => this,
+                None => {
+                    let message = "\"this\" can only be used inside methods";
+                    self.error(message.to_owned(), ident.span());
+                    Ty::Unknown
+                }
+            };
+        }
+        if ident.symbol() == sym::This {
+            return match self.this {
+                Some(Ty::Instance(class)) => Ty::Class(class),
+                Some(_) => Ty::Unknown,
+                None => {
+                    let message = "\"This\" can only be used inside impl and trait blocks";
+                    self.error(message.to_owned(), ident.span());
+                    Ty::Unknown
+                }
+            };
+        }
+        match self.scopes.symbol(self.scope, ident.symbol()) {
+            Some(def) => {
+                self.reference(ident.span(), def);
+                match self.defs[def.0].kind {
+                    DefKind::Trait => {
+                        let message = format!("\"{}\" is a trait, not a value", ident.symbol());
+                        self.error(message, ident.span());
+                        Ty::Unknown
+                    }
+                    DefKind::TypeParam => {
+                        let message =
+                            format!("\"{}\" is a type parameter, not a value", ident.symbol());
+                        self.error(message, ident.span());
+                        Ty::Unknown
+                    }
+                    _ => self.defs[def.0].ty,
+                }
+            }
+            None => {
+                let message = format!("symbol \"{}\" was not found", ident.symbol());
+                self.error(message, ident.span());
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn unary(&mut self, unary: &UnaryExpr) -> Ty {
+        let ty = self.expr(unary.expr());
+        match unary.op().span().as_str() {
+            "!" if ty == Ty::Instance(self.bool) => ty,
+            "!" => self.operator(ty, sym::not, "Not", &[], unary.span()),
+            op if ty == Ty::Instance(self.num) || ty == Ty::Unknown => {
+                let message = format!("unary operator \"{}\" is not supported yet", op);
+                self.error(message, unary.span());
+                ty
+            }
+            _ => {
+                let message = format!("invalid operand \"{}\" to unary expression", self.name(ty));
+                self.error(message, unary.span());
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn binary(&mut self, binary: &BinaryExpr) -> Ty {
+        let op = binary.op().span().as_str();
+        if op == "=" {
+            return self.assign(binary);
+        }
+        if op == "&&" || op == "||" {
+            return self.logical(binary);
+        }
+        let left = self.expr(binary.left());
+        let right = self.expr(binary.right());
+        let (num, bool, nil) = (
+            Ty::Instance(self.num),
+            Ty::Instance(self.bool),
+            Ty::Instance(self.nil),
+        );
+        let operands = [(right, binary.right().span())];
+        match op {
+            "==" | "!=" if self.is_nullable(left) || self.is_nullable(right) => {
+                let (left, right) = (self.non_null(left), self.non_null(right));
+                if left != nil && right != nil && !(left == right && self.is_primitive(left)) {
+                    let operands = [(right, binary.right().span())];
+                    self.operator(left, sym::eq, "Eq", &operands, binary.span());
+                }
+                bool
+            }
+            "??" if left == nil => right,
+            "??" => {
+                let inner = self.non_null(left);
+                let ty = if self.is_nullable(right) {
+                    self.nullable(inner)
+                } else {
+                    inner
+                };
+                self.expect(right, ty, binary.right().span());
+                ty
+            }
+            "+" | "-" | "*" | "/" | "%" if left == num && right == num => num,
+            "<" | "<=" | ">" | ">=" | ".." | "..=" => {
+                let message = format!("operator \"{}\" is not supported yet", op);
+                self.error(message, binary.op().span());
+                match op {
+                    ".." | "..=" => Ty::Unknown,
+                    _ => bool,
+                }
+            }
+            "==" | "!=" if left == right && self.is_primitive(left) => bool,
+            "+" => self.operator(left, sym::add, "Add", &operands, binary.span()),
+            "-" => self.operator(left, sym::sub, "Sub", &operands, binary.span()),
+            "*" => self.operator(left, sym::mul, "Mul", &operands, binary.span()),
+            "/" => self.operator(left, sym::div, "Div", &operands, binary.span()),
+            "%" => self.operator(left, sym::rem, "Rem", &operands, binary.span()),
+            "==" | "!=" => {
+                self.operator(left, sym::eq, "Eq", &operands, binary.span());
+                bool
+            }
+            _ => {
+                if left != Ty::Unknown && right != Ty::Unknown {
+                    let message = "invalid operands to binary expression";
+                    self.error(message.to_owned(), binary.span());
+                }
+                Ty::Unknown
+            }
+        }
+    }
+
+    /// Checks `&&` and `||`, whose right operand is only evaluated once the
+    /// left one is known to be true or false, respectively.
+    fn logical(&mut self, binary: &BinaryExpr) -> Ty {
+        self.cond(binary.left());
+        let narrowing = self.narrowing(binary.left());
+        let and = binary.op().span().as_str() == "&&";
+        let scope = self.scope;
+        self.scope = self.scopes.push(scope);
+        for (name, def, not_nil) in narrowing {
+            if not_nil == and {
+                self.narrow(name, def);
+            }
+        }
+        self.cond(binary.right());
+        self.scope = scope;
+        Ty::Instance(self.bool)
+    }
+
+    /// Checks an operator implemented through the `method` of a trait, such
+    /// as `+` through `Add.add`.
+    fn operator(
+        &mut self,
+        ty: Ty,
+        method: Symbol,
+        r#trait: &str,
+        args: &[(Ty, &Span)],
+        span: &Span,
+    ) -> Ty {
+        match self.method(ty, method) {
+            Some(method) => {
+                let signature = self.defs[method.0].signature.clone().unwrap();
+                self.arguments(&signature, args, span);
+                signature.return_ty
+            }
+            None => {
+                if let Ty::Nullable(_) = ty {
+                    let message = format!("operand of type \"{}\" may be nil", self.name(ty));
+                    self.error(message, span);
+                } else if ty != Ty::Unknown {
+                    let message = format!(
+                        "trait \"{}\" is not implemented for \"{}\"",
+                        r#trait,
+                        self.name(ty)
+                    );
+                    self.error(message, span);
+                }
+                Ty::Unknown
+            }
+        }
+    }
+
+    /// Resolves the method `name` of a type, looking at the impl blocks in
+    /// scope for classes, at the trait and its supertraits for `This` and
+    /// at the bounds of type parameters.
+    fn method(&mut self, ty: Ty, name: Symbol) -> Option<DefId> {
+        match ty {
+            Ty::Instance(class) => self.member(class, name, false),
+            Ty::This => self.trait_method(self.r#trait?, name),
+            Ty::Param(param) => {
+                let method = self.defs[param.0]
+                    .supertraits
+                    .iter()
+                    .find_map(|bound| self.trait_method(*bound, name))?;
+                Some(self.specialize(method, vec![(Ty::This, ty)]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolves a method, or a static method, of a class. The methods of a
+    /// generic class are declared for every instantiation by `impl<T>`
+    /// blocks, so their type parameters are replaced by its arguments.
+    fn member(&mut self, class: DefId, name: Symbol, r#static: bool) -> Option<DefId> {
+        let (class, args) = match self.defs[class.0].origin {
+            Some(origin) => (origin, self.defs[class.0].args.clone()),
+            None => (class, vec![]),
+        };
+        let method = if r#static {
+            self.scopes.static_method(self.scope, class, name)?
+        } else {
+            self.scopes.method(self.scope, class, name)?
+        };
+        let map = self.map(&self.defs[method.0].generics.clone(), args);
+        Some(self.specialize(method, map))
+    }
+
+    fn trait_method(&self, r#trait: DefId, name: Symbol) -> Option<DefId> {
+        let definition = &self.defs[r#trait.0];
+        definition
+            .members
+            .iter()
+            .copied()
+            .find(|member| self.defs[member.0].name == name)
+            .or_else(|| {
+                definition
+                    .supertraits
+                    .iter()
+                    .find_map(|supertrait| self.trait_method(*supertrait, name))
+            })
+    }
+
+    fn arguments(&mut self, signature: &Signature, args: &[(Ty, &Span)], span: &Span) {
+        if signature.params.len() != args.len() {
+            let message = format!(
+                "expected {} arguments, found {}",
+                signature.params.len(),
+                args.len()
+            );
+            self.error(message, span);
+        }
+        for (param, (arg, span)) in signature.params.iter().zip(args) {
+            self.expect(*arg, *param, span);
+        }
+    }
+
+    fn assign(&mut self, binary: &BinaryExpr) -> Ty {
+        let right = self.expr(binary.right());
+        let mut target = binary.left();
+        while let Expr::Paren(paren) = target {
+            target = paren.expr();
+        }
+        let left = match target {
+            Expr::Ident(ident) => {
+                let ident = ident.ident();
+                match self.scopes.symbol(self.scope, ident.symbol()) {
+                    Some(def) => {
+                        self.reference(ident.span(), def);
+                        match self.defs[def.0].kind {
+                            DefKind::Let | DefKind::Param => {
+                                // A narrowed variable can still be given any
+                                // value of its declared type, and is only
+                                // known not to be nil while it's given such
+                                // values.
+                                let declared = self.defs[def.0].origin.unwrap_or(def);
+                                let ty = self.defs[declared.0].ty;
+                                if declared != def {
+                                    self.defs[def.0].ty = if self.is_nullable(right) {
+                                        ty
+                                    } else {
+                                        self.non_null(ty)
+                                    };
+                                }
+                                ty
+                            }
+                            DefKind::Const => {
+                                let message =
+                                    format!("cannot assign to constant \"{}\"", ident.symbol());
+                                self.error(message, ident.span());
+                                Ty::Unknown
+                            }
+                            _ => {
+                                let message = "invalid assignment target";
+                                self.error(message.to_owned(), ident.span());
+                                Ty::Unknown
+                            }
+                        }
+                    }
+                    None => {
+                        let message = format!("symbol \"{}\" was not found", ident.symbol());
+                        self.error(message, ident.span());
+                        Ty::Unknown
+                    }
+                }
+            }
+            Expr::Get(get) if !get.is_safe() => self.get(get),
+            _ => {
+                self.error("invalid assignment target".to_owned(), target.span());
+                Ty::Unknown
+            }
+        };
+        self.expect(right, left, binary.right().span());
+        right
+    }
+
+    fn get(&mut self, get: &GetExpr) -> Ty {
+        let (ty, nullable) = self.property(get);
+        if nullable {
+            self.nullable(ty)
+        } else {
+            ty
+        }
+    }
+
+    /// Resolves the property of a get expression, also telling whether it's
+    /// skipped by `?.` when the object is nil.
+    fn property(&mut self, get: &GetExpr) -> (Ty, bool) {
+        let ty = self.expr(get.expr());
+        let ident = get.ident();
+        let nullable = matches!(ty, Ty::Nullable(_));
+        if nullable && !get.is_safe() {
+            let message = format!(
+                "cannot read property \"{}\" of \"{}\" because it may be nil",
+                ident.symbol(),
+                self.name(ty)
+            );
+            self.error(message, ident.span());
+            return (Ty::Unknown, false);
+        }
+        let ty = self.non_null(ty);
+        let field = match ty {
+            Ty::Instance(class) => self.defs[class.0]
+                .members
+                .iter()
+                .copied()
+                .find(|field| self.defs[field.0].name == ident.symbol()),
+            _ => None,
+        };
+        let ty = if let Some(field) = field {
+            self.reference(ident.span(), field);
+            self.defs[field.0].ty
+        } else if let Some(method) = self.method(ty, ident.symbol()) {
+            self.reference(ident.span(), method);
+            Ty::Fun(method)
+        } else if ty == Ty::Unknown {
+            Ty::Unknown
+        } else {
+            let message = format!(
+                "cannot read property \"{}\" of \"{}\"",
+                ident.symbol(),
+                self.name(ty)
+            );
+            self.error(message, ident.span());
+            Ty::Unknown
+        };
+        (ty, nullable)
+    }
+
+    fn path(&mut self, path: &PathExpr) -> Ty {
+        let ident = path.ident();
+        let ty = match self.ident(path.ty()) {
+            Ty::Class(class) => class,
+            Ty::Unknown => return Ty::Unknown,
+            _ => {
+                let message = format!("\"{}\" is not a class", path.ty().symbol());
+                self.error(message, path.ty().span());
+                return Ty::Unknown;
+            }
+        };
+        match self.member(ty, ident.symbol(), true) {
+            Some(method) => {
+                self.reference(ident.span(), method);
+                Ty::Fun(method)
+            }
+            None => {
+                let message = format!(
+                    "class \"{}\" has no static method \"{}\"",
+                    self.defs[ty.0].name,
+                    ident.symbol()
+                );
+                self.error(message, ident.span());
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn call(&mut self, call: &CallExpr) -> Ty {
+        // A method called through `?.` isn't called at all when the object
+        // is nil, so the call can be nil too.
+        let (callee, nullable) = match call.callee() {
+            Expr::Get(get) => self.property(get),
+            callee => (self.expr(callee), false),
+        };
+        let args = call
+            .args()
+            .iter()
+            .map(|arg| (self.expr(arg), arg.span()))
+            .collect::<Vec<_>>();
+        match callee {
+            Ty::Fun(def) => {
+                let mut signature = self.defs[def.0].signature.clone().unwrap();
+                let generics = self.defs[def.0].generics.clone();
+                if !generics.is_empty() {
+                    let mut inferred = vec![];
+                    for (param, (arg, _)) in signature.params.iter().zip(&args) {
+                        self.unify(*param, *arg, &generics, &mut inferred);
+                    }
+                    let args = generics
+                        .iter()
+                        .map(|param| {
+                            inferred
+                                .iter()
+                                .find(|(from, _)| *from == Ty::Param(*param))
+                                .map_or(Ty::Unknown, |(_, to)| *to)
+                        })
+                        .collect::<Vec<_>>();
+                    self.bounds(&generics, &args, call.span());
+                    let map = self.map(&generics, args);
+                    signature = Signature {
+                        params: signature
+                            .params
+                            .into_iter()
+                            .map(|param| self.subst(param, &map))
+                            .collect(),
+                        return_ty: self.subst(signature.return_ty, &map),
+                    };
+                }
+                self.arguments(&signature, &args, call.span());
+                if nullable {
+                    self.nullable(signature.return_ty)
+                } else {
+                    signature.return_ty
+                }
+            }
+            Ty::Unknown => Ty::Unknown,
+            _ => {
+                let message = format!("\"{}\" is not a function", self.name(callee));
+                self.error(message, call.callee().span());
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn instantiate(&mut self, class: &ClassExpr) -> Ty {
+        let ty = self.expr(class.cls());
+        let values = class
+            .fields()
+            .iter()
+            .map(|field| match field.value() {
+                Some(value) => (self.expr(value), value.span()),
+                None => (self.ident(field.ident()), field.ident().span()),
+            })
+            .collect::<Vec<_>>();
+        let Ty::Class(def) = ty else {
+            if ty != Ty::Unknown {
+                let message = "only classes can be instantiated";
+                self.error(message.to_owned(), class.cls().span());
+            }
+            return Ty::Unknown;
+        };
+        if self.is_primitive(ty) {
+            let message = "cannot instantiate a primitive type";
+            self.error(message.to_owned(), class.cls().span());
+            return Ty::Unknown;
+        }
+        let generics = self.defs[def.0].generics.clone();
+        let def = if generics.is_empty() {
+            def
+        } else {
+            let mut inferred = vec![];
+            for (field, (value, _)) in class.fields().iter().zip(&values) {
+                let member = self.defs[def.0]
+                    .members
+                    .iter()
+                    .find(|member| self.defs[member.0].name == field.ident().symbol());
+                if let Some(member) = member {
+                    self.unify(self.defs[member.0].ty, *value, &generics, &mut inferred);
+                }
+            }
+            let args = generics
+                .iter()
+                .map(|param| {
+                    inferred
+                        .iter()
+                        .find(|(from, _)| *from == Ty::Param(*param))
+                        .map_or(Ty::Unknown, |(_, to)| *to)
+                })
+                .collect::<Vec<_>>();
+            self.bounds(&generics, &args, class.cls().span());
+            self.apply(def, args)
+        };
+        let members = self.defs[def.0].members.clone();
+        for (field, (value, span)) in class.fields().iter().zip(values) {
+            let member = members
+                .iter()
+                .copied()
+                .find(|member| self.defs[member.0].name == field.ident().symbol());
+            match member {
+                Some(member) => {
+                    if field.value().is_some() {
+                        self.reference(field.ident().span(), member);
+                    }
+                    self.expect(value, self.defs[member.0].ty, span);
+                }
+                None => {
+                    let message = format!(
+                        "class \"{}\" has no field \"{}\"",
+                        self.defs[def.0].name,
+                        field.ident().symbol()
+                    );
+                    self.error(message, field.ident().span());
+                }
+            }
+        }
+        for member in members {
+            let name = self.defs[member.0].name;
+            if !class
+                .fields()
+                .iter()
+                .any(|field| field.ident().symbol() == name)
+            {
+                let message = format!(
+                    "missing field \"{}\" of \"{}\"",
+                    name, self.defs[def.0].name
+                );
+                self.error(message, class.cls().span());
+            }
+        }
+        Ty::Instance(def)
+    }
+}
+
+/// Builds the signature shown for a function, such as
+/// `fun sum(a: Num, b: Num) -> Num`.
+fn detail(name: &str, params: &[Param], return_ty: Option<&Type>) -> String {
+    let params = params
+        .iter()
+        .map(|param| {
+            format!(
+                "{}: {}",
+                param.ident().source_text(),
+                param.ty().span().source_text()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    match return_ty {
+        Some(ty) => format!("fun {}({}) -> {}", name, params, ty.span().source_text()),
+        None => format!("fun {}({})", name, params),
+    }
+}
+
+/// Checks whether a block always leaves the enclosing function or loop.
+fn diverges(block: &BlockStmt) -> bool {
+    block.stmts().iter().any(|stmt| {
+        matches!(
+            stmt,
+            Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) | Stmt::Throw(_)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use lumi_lxr::lexer::Lexer;
+    use lumi_lxr::source_code::SourceCode;
+    use lumi_psr::parser::ParseStream;
+
+    use super::*;
+
+    const PRELUDE: [(&str, &str); 2] = [
+        ("core.ls", include_str!("../../core.ls")),
+        ("std.ls", include_str!("../../std.ls")),
+    ];
+
+    fn parse(path: &str, code: &str) -> Ast {
+        let tokens = Lexer::new(SourceCode::new(path, code)).tokens().unwrap();
+        ParseStream::new(tokens)
+            .parse::<Ast>()
+            .unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+
+    /// Checks `code` after the prelude, returning the messages of the
+    /// errors found in it.
+    fn check(code: &str) -> Vec<String> {
+        let mut type_checker = TypeChecker::new();
+        for (path, prelude) in PRELUDE {
+            if let Err(errors) = type_checker.check(&parse(path, prelude)) {
+                panic!("{}", errors[0].message());
+            }
+        }
+        match type_checker.check(&parse("test.ls", code)) {
+            Ok(()) => vec![],
+            Err(errors) => errors
+                .iter()
+                .map(|error| error.message().to_owned())
+                .collect(),
+        }
+    }
+
+    /// Implementations of traits and their methods.
+    mod traits {
+        use super::check;
+
+        #[test]
+        fn unknown_trait() {
+            let code = "class A { v: Num, }\nimpl Missing for A {}";
+            assert_eq!(check(code), ["trait \"Missing\" was not found"]);
+        }
+
+        #[test]
+        fn missing_method() {
+            let code = r#"
+trait Shape { fun area() -> Num; }
+class A { v: Num, }
+impl Shape for A {}
+"#;
+            assert_eq!(check(code), ["missing method \"area\" of trait \"Shape\""]);
+        }
+
+        #[test]
+        fn extra_method() {
+            let code = r#"
+trait Shape { fun area() -> Num; }
+class A { v: Num, }
+impl Shape for A {
+    fun area() -> Num { 1 }
+    fun perimeter() -> Num { 2 }
+}
+"#;
+            assert_eq!(
+                check(code),
+                ["method \"perimeter\" is not a member of trait \"Shape\""]
+            );
+        }
+
+        #[test]
+        fn return_type_mismatch() {
+            let code = r#"
+trait Shape { fun area() -> Num; }
+class A { v: Num, }
+impl Shape for A {
+    fun area() -> Str { "1" }
+}
+"#;
+            assert_eq!(
+                check(code),
+                ["expected \"Num\" as declared by trait \"Shape\", found \"Str\""]
+            );
+        }
+
+        #[test]
+        fn parameter_type_mismatch() {
+            let code = r#"
+trait Shape { fun scale(by: Num) -> Num; }
+class A { v: Num, }
+impl Shape for A {
+    fun scale(by: Str) -> Num { 1 }
+}
+"#;
+            assert_eq!(
+                check(code),
+                ["expected \"Num\" as declared by trait \"Shape\", found \"Str\""]
+            );
+        }
+    }
+
+    /// Default bodies, supertraits and duplicate implementations.
+    mod supertraits {
+        use super::check;
+
+        #[test]
+        fn default_bodies_are_inherited() {
+            let code = r#"
+trait Named {
+    fun name() -> Str { "named" }
+}
+class A { v: Num, }
+impl Named for A {}
+let name: Str = A { v: 1 }.name();
+"#;
+            assert_eq!(check(code), [] as [&str; 0]);
+        }
+
+        #[test]
+        fn default_bodies_are_checked() {
+            let code = "trait Named {\n    fun name() -> Str { 1 }\n}";
+            assert_eq!(check(code), ["expected \"Str\", found \"Num\""]);
+        }
+
+        #[test]
+        fn supertrait_must_be_implemented() {
+            let code = r#"
+trait Base { fun base() -> Num; }
+trait Derived: Base {}
+class A { v: Num, }
+impl Derived for A {}
+"#;
+            assert_eq!(
+                check(code),
+                ["trait \"Derived\" requires \"Base\" to be implemented for \"A\""]
+            );
+        }
+
+        #[test]
+        fn supertraits_can_be_declared_later() {
+            let code = r#"
+trait Derived: Base {}
+trait Base { fun base() -> Num; }
+class A { v: Num, }
+impl Base for A { fun base() -> Num { 1 } }
+impl Derived for A {}
+"#;
+            assert_eq!(check(code), [] as [&str; 0]);
+        }
+
+        #[test]
+        fn supertrait_cycle() {
+            let code = "trait A: B {}\ntrait B: A {}";
+            assert_eq!(check(code), ["trait \"B\" can't extend itself"]);
+        }
+
+        #[test]
+        fn duplicate_impl() {
+            let code = r#"
+trait Named { fun name() -> Str; }
+class A { v: Num, }
+impl Named for A { fun name() -> Str { "a" } }
+impl Named for A { fun name() -> Str { "b" } }
+"#;
+            assert_eq!(
+                check(code),
+                ["trait \"Named\" is already implemented for \"A\""]
+            );
+        }
+    }
+
+    /// Bounds of type parameters and numbers of arguments.
+    mod generics {
+        use super::check;
+
+        #[test]
+        fn bound_violation() {
+            let code = r#"
+trait Named { fun name() -> Str; }
+fun show<T: Named>(value: T) -> Str { value.name() }
+show(1);
+"#;
+            assert_eq!(
+                check(code),
+                ["trait \"Named\" is not implemented for \"Num\""]
+            );
+        }
+
+        #[test]
+        fn bound_satisfied() {
+            let code = r#"
+trait Named { fun name() -> Str; }
+class A { v: Num, }
+impl Named for A { fun name() -> Str { "a" } }
+fun show<T: Named>(value: T) -> Str { value.name() }
+let name: Str = show(A { v: 1 });
+"#;
+            assert_eq!(check(code), [] as [&str; 0]);
+        }
+
+        #[test]
+        fn argument_count() {
+            let code = "fun add(a: Num, b: Num) -> Num { a + b }\nadd(1);";
+            assert_eq!(check(code), ["expected 2 arguments, found 1"]);
+        }
+
+        #[test]
+        fn type_argument_count() {
+            let code = r#"
+class Pair<A, B> { a: A, b: B, }
+let pair: Pair<Num> = Pair { a: 1, b: 2 };
+"#;
+            assert_eq!(
+                check(code),
+                ["expected 2 type arguments for \"Pair\", found 1"]
+            );
+        }
+    }
+
+    /// Optional types, narrowing, `?.` and `??`.
+    mod nil {
+        use super::check;
+
+        #[test]
+        fn optional_operand() {
+            let code = "fun f(n: Num?) -> Num { n + 1 }";
+            assert_eq!(check(code), ["operand of type \"Num?\" may be nil"]);
+        }
+
+        #[test]
+        fn narrowed_by_a_nil_check() {
+            let code = r#"
+fun f(n: Num?) -> Num {
+    if n != nil {
+        return n + 1;
+    }
+    0
+}
+"#;
+            assert_eq!(check(code), [] as [&str; 0]);
+        }
+
+        #[test]
+        fn coalescing() {
+            let code = "fun f(n: Num?) -> Num { n ?? 0 }\nfun g(n: Num?) -> Str { n ?? 0 }";
+            assert_eq!(check(code), ["expected \"Str\", found \"Num\""]);
+        }
+
+        #[test]
+        fn optional_chaining() {
+            let code = r#"
+class A { v: Num, }
+fun f(a: A?) -> Num? { a?.v }
+fun g(a: A?) -> Num { a?.v }
+"#;
+            assert_eq!(check(code), ["expected \"Num\", found \"Num?\""]);
+        }
+    }
+
+    /// Conditions converted with `ToBool`.
+    mod conditions {
+        use super::check;
+
+        #[test]
+        fn conditions_implementing_to_bool() {
+            let code = r#"
+class A { v: Num, }
+impl ToBool for A { fun toBool() -> Bool { true } }
+let a = A { v: 1 };
+if a { println(1); }
+if 1 { println(1); }
+if true && 1 { println(1); }
+"#;
+            assert_eq!(check(code), [] as [&str; 0]);
+        }
+
+        #[test]
+        fn conditions_not_implementing_to_bool() {
+            let code = r#"
+class A { v: Num, }
+let a = A { v: 1 };
+if a { println(1); }
+if "s" || true { println(1); }
+"#;
+            assert_eq!(
+                check(code),
+                [
+                    "expected \"Bool\", found \"A\"",
+                    "expected \"Bool\", found \"Str\""
+                ]
+            );
+        }
+    }
+
+    /// Early returns of the `?` operator.
+    mod propagation {
+        use super::check;
+
+        #[test]
+        fn compatible_return_type() {
+            let code = r#"
+fun parse() -> Result<Num, Str> { Result::err("no") }
+fun use() -> Result<Num, Str> {
+    let n = parse()?;
+    Result::ok(n)
+}
+"#;
+            assert_eq!(check(code), [] as [&str; 0]);
+        }
+
+        #[test]
+        fn incompatible_return_type() {
+            let code = r#"
+fun parse() -> Result<Num, Str> { Result::err("no") }
+fun find() -> Option<Num> { Option::none() }
+fun first() -> Num {
+    parse()?
+}
+fun second() -> Result<Num, Str> {
+    let n = find()?;
+    Result::ok(n)
+}
+"#;
+            assert_eq!(
+                check(code),
+                [
+                    "the \"?\" operator may return \"Result<Num, Str>\" early, but the function returns \"Num\"",
+                    "the \"?\" operator may return \"Option<Num>\" early, but the function returns \"Result<Num, Str>\""
+                ]
+            );
+        }
+    }
+}
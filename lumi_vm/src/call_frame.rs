@@ -2,17 +2,19 @@ use std::collections::HashMap;
 
 use std::rc::Rc;
 
+use lumi_lxr::symbol::Symbol;
+
 use crate::scope::Scope;
 
 #[derive(Debug)]
 pub(crate) struct CallFrame {
     pub(crate) instructions_ptr: usize,
     return_scope: Option<Rc<Scope>>,
-    slots: HashMap<String, usize>,
+    slots: HashMap<Symbol, usize>,
 }
 
 impl CallFrame {
-    pub(crate) fn new(return_scope: Option<Rc<Scope>>, slots: HashMap<String, usize>) -> Self {
+    pub(crate) fn new(return_scope: Option<Rc<Scope>>, slots: HashMap<Symbol, usize>) -> Self {
         Self {
             instructions_ptr: 0,
             return_scope,
@@ -21,10 +23,10 @@ impl CallFrame {
     }
 
     pub(crate) fn return_scope(&self) -> Option<Rc<Scope>> {
-        self.return_scope.as_ref().map(|scope| Rc::clone(&scope))
+        self.return_scope.as_ref().map(Rc::clone)
     }
 
-    pub(crate) fn slots(&self) -> HashMap<String, usize> {
+    pub(crate) fn slots(&self) -> HashMap<Symbol, usize> {
         self.slots.clone()
     }
 }
@@ -57,4 +59,14 @@ impl CallStack {
     pub(crate) fn pop(&mut self) {
         self.stack.pop();
     }
+
+    /// Gets the number of calls on the stack, without counting the root
+    /// frame.
+    pub(crate) fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub(crate) fn truncate(&mut self, depth: usize) {
+        self.stack.truncate(depth);
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use lumi_lxr::span::Span;
+use lumi_lxr::symbol::Symbol;
 
 macro_rules! define_bytecodes {
     ($($name:ident),*) => {
@@ -31,10 +32,17 @@ define_bytecodes!(
     DeclareClass,
     DeclareFunction,
     DeclareMethod,
+    DeclareStaticMethod,
+    DeclareExternFunction,
+    DeclareExternMethod,
+    DeclareTrait,
+    DeclareTraitMethod,
+    ImplementTrait,
     PrintLn,
     GetSymbol,
     SetVariable,
     GetProperty,
+    GetStatic,
     SetProperty,
     CallFunction,
     Instantiate,
@@ -43,13 +51,24 @@ define_bytecodes!(
     Subtract,
     Multiply,
     Divide,
+    Remainder,
     Negate,
     Not,
     Equals,
     Greater,
     Less,
     JumpIfFalse,
+    JumpIfFalseOrPop,
+    JumpIfTrueOrPop,
+    ToBool,
+    JumpIfNil,
+    JumpIfNotNil,
+    Unwrap,
+    Propagate,
     Jump,
+    PushHandler,
+    PopHandler,
+    Throw,
     Else,
     While,
     Return,
@@ -61,29 +80,16 @@ pub enum Constant {
     Nil,
     Bool(bool),
     Number(f64),
-    String(String),
+    Str(String),
+    Symbol(Symbol),
     Size(usize),
 }
 
 impl Constant {
-    pub(crate) fn as_bool(&self) -> bool {
+    pub(crate) fn as_symbol(&self) -> Symbol {
         match self {
-            Self::Bool(value) => *value,
-            _ => panic!("Cannot constant to bool"),
-        }
-    }
-
-    pub(crate) fn as_number(&self) -> f64 {
-        match self {
-            Self::Number(value) => *value,
-            _ => panic!("Cannot constant to number"),
-        }
-    }
-
-    pub(crate) fn as_string(&self) -> String {
-        match self {
-            Self::String(value) => value.clone(),
-            _ => panic!("Cannot constant to string"),
+            Self::Symbol(value) => *value,
+            _ => panic!("Cannot constant to symbol"),
         }
     }
 
@@ -95,7 +101,7 @@ impl Constant {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct Chunk {
     instructions: Vec<u8>,
     constant_pool: Vec<Constant>,
@@ -104,11 +110,7 @@ pub struct Chunk {
 
 impl Chunk {
     pub fn new() -> Self {
-        Self {
-            instructions: vec![],
-            constant_pool: vec![],
-            source_map: HashMap::new(),
-        }
+        Self::default()
     }
 
     pub(crate) fn span(&self, index: usize) -> &Span {
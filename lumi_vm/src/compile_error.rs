@@ -0,0 +1,61 @@
+use std::fmt::Display;
+
+use colored::Colorize;
+use lumi_lxr::span::Span;
+
+/// An error found while emitting the bytecode of a program, such as an
+/// `extern` declaration without a native implementation.
+pub struct CompileError {
+    pub(crate) message: String,
+    pub(crate) span: Span,
+}
+
+impl CompileError {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line = self.span.start().line();
+        let column = self.span.start().column();
+        let line_content = self
+            .span
+            .source_code()
+            .code()
+            .lines()
+            .nth(line - 1)
+            .unwrap_or_default();
+        let output = format!(
+            "{}: {} \
+            \n{} {}:{}:{} \
+            \n{: >5} {} \
+            \n{: >5} {} {} \
+            \n{: >5} {}{}{} \
+            ",
+            "compile error".red().bold(),
+            self.message,
+            "-->".blue().bold(),
+            self.span.source_code().file_path(),
+            line,
+            column,
+            " ",
+            "|".blue().bold(),
+            line.to_string().blue().bold(),
+            "|".blue().bold(),
+            line_content,
+            " ",
+            "|".blue().bold(),
+            " ".repeat(column),
+            "^".repeat(self.span.end().column() - self.span.start().column())
+                .red()
+                .bold(),
+        );
+        write!(f, "{}", output)
+    }
+}
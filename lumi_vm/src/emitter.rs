@@ -1,80 +1,169 @@
+use lumi_lxr::{number::parse_number, span::Span, string::parse_string, symbol::Symbol};
 use lumi_psr::{
     ast::Ast,
     exprs::{lit::LitExpr, Expr},
-    stmts::{fun::FunStmt, r#impl::Method, Stmt},
+    param::Param,
+    stmts::{
+        block::BlockStmt,
+        fun::FunStmt,
+        r#impl::{ImplStmt, Method},
+        Stmt,
+    },
+    visit::{self, Visit},
 };
 
 use crate::chunk::{Bytecode, Chunk, Constant};
+use crate::compile_error::CompileError;
+use crate::native::Natives;
 
+/// Emits a node, whose bytecode may depend on the code around it.
 pub(crate) trait Emitter {
-    fn emit(&self, chunk: &mut Chunk);
+    fn emit<'ast>(&'ast self, context: &mut Context<'ast>, chunk: &mut Chunk);
+}
+
+/// What the emitter knows about the code around the node being emitted.
+#[derive(Default)]
+pub(crate) struct Context<'ast> {
+    /// The regions of the `try` statements around the node, in the current
+    /// function, which a `return` or a `?` leaves. The innermost is the last
+    /// one.
+    protected: Vec<Protected<'ast>>,
+}
+
+/// The try or catch block of a `try` statement.
+#[derive(Clone, Copy)]
+struct Protected<'ast> {
+    /// The number of handlers pushed for the block, popped when leaving it.
+    handlers: usize,
+    finally: Option<&'ast BlockStmt>,
 }
 
 pub struct BytecodeEmitter;
 
 impl BytecodeEmitter {
-    pub fn emit(ast: &Ast, chunk: &mut Chunk) {
-        ast.emit(chunk);
+    /// Emits the bytecode of a program into the chunk, once every `extern`
+    /// declaration it makes is known to have a native implementation.
+    pub fn emit(ast: &Ast, chunk: &mut Chunk) -> Result<(), Vec<CompileError>> {
+        let mut externs = Externs {
+            natives: Natives::new(),
+            class: None,
+            errors: vec![],
+        };
+        externs.visit_ast(ast);
+        if !externs.errors.is_empty() {
+            return Err(externs.errors);
+        }
+        ast.emit(&mut Context::default(), chunk);
+        Ok(())
+    }
+}
+
+/// Resolves the `extern` declarations of a program against the natives,
+/// the same way the VM binds them when declaring them.
+struct Externs {
+    natives: Natives,
+    /// The class of the impl block being visited, whose extern methods are
+    /// named `Class.method`.
+    class: Option<Symbol>,
+    errors: Vec<CompileError>,
+}
+
+impl Externs {
+    fn resolve(&mut self, name: String, params: usize, span: &Span) {
+        let message = match self.natives.get(&name) {
+            None => format!("extern \"{}\" has no native implementation", name),
+            Some(native) if native.params() != params => format!(
+                "extern \"{}\" declares {} parameters, but its native implementation takes {}",
+                name,
+                params,
+                native.params()
+            ),
+            Some(_) => return,
+        };
+        self.errors.push(CompileError {
+            message,
+            span: span.clone(),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for Externs {
+    fn visit_impl_stmt(&mut self, node: &'ast ImplStmt) {
+        let class = self.class.replace(node.ty().ident().symbol());
+        visit::visit_impl_stmt(self, node);
+        self.class = class;
+    }
+
+    fn visit_method(&mut self, node: &'ast Method) {
+        if let (Method::Extern { ident, params, .. }, Some(class)) = (node, self.class) {
+            self.resolve(
+                format!("{}.{}", class, ident.symbol()),
+                params.len(),
+                ident.span(),
+            );
+        }
+        visit::visit_method(self, node);
+    }
+
+    fn visit_fun_stmt(&mut self, node: &'ast FunStmt) {
+        if let FunStmt::Extern { ident, params, .. } = node {
+            self.resolve(ident.symbol().to_string(), params.len(), ident.span());
+        }
+        visit::visit_fun_stmt(self, node);
     }
 }
 
 impl Emitter for Ast {
-    fn emit(&self, chunk: &mut Chunk) {
+    fn emit<'ast>(&'ast self, context: &mut Context<'ast>, chunk: &mut Chunk) {
         for stmt in self.stmts() {
-            stmt.emit(chunk);
+            stmt.emit(context, chunk);
         }
     }
 }
 
 impl Emitter for Stmt {
-    fn emit(&self, chunk: &mut Chunk) {
+    fn emit<'ast>(&'ast self, context: &mut Context<'ast>, chunk: &mut Chunk) {
         match self {
             Self::Expr(expr) => {
-                expr.expr().emit(chunk);
+                expr.expr().emit(context, chunk);
                 chunk.push_instruction(Bytecode::Pop, expr.span().clone());
             }
             Self::Println(println) => {
-                println.expr().emit(chunk);
+                println.expr().emit(context, chunk);
                 chunk.push_instruction(Bytecode::PrintLn, println.expr().span().clone());
             }
-            Self::Block(block) => {
-                chunk.push_instruction(Bytecode::BeginScope, block.span().clone());
-                for stmt in block.stmts() {
-                    stmt.emit(chunk);
-                }
-                chunk.push_instruction(Bytecode::EndScope, block.span().clone());
-            }
+            Self::Block(block) => emit_block(block.stmts(), block.span(), context, chunk),
             Self::Let(r#let) => {
-                r#let.expr().emit(chunk);
+                r#let.expr().emit(context, chunk);
                 chunk.push_constant(
-                    Constant::String(r#let.ident().source_text()),
+                    Constant::Symbol(r#let.ident().symbol()),
                     r#let.ident().span().clone(),
                 );
                 chunk.push_instruction(Bytecode::DeclareVariable, r#let.expr().span().clone());
             }
             Self::Const(r#const) => {
-                r#const.expr().emit(chunk);
+                r#const.expr().emit(context, chunk);
                 chunk.push_constant(
-                    Constant::String(r#const.ident().source_text()),
+                    Constant::Symbol(r#const.ident().symbol()),
                     r#const.ident().span().clone(),
                 );
                 chunk.push_instruction(Bytecode::DeclareVariable, r#const.expr().span().clone());
             }
             Self::Class(class) => {
                 chunk.push_constant(
-                    Constant::String(class.ident().span().source_text()),
+                    Constant::Symbol(class.ident().symbol()),
                     class.ident().span().clone(),
                 );
                 chunk.push_instruction(Bytecode::DeclareClass, class.ident().span().clone());
             }
             Self::Return(r#return) => {
                 if let Some(expr) = r#return.expr() {
-                    expr.emit(chunk);
+                    expr.emit(context, chunk);
                 } else {
                     chunk.push_constant(Constant::Nil, r#return.span().clone());
                     chunk.push_instruction(Bytecode::ConvertConstant, r#return.span().clone());
                 }
-                chunk.push_instruction(Bytecode::Return, r#return.span().clone());
+                emit_return(r#return.span(), context, chunk);
             }
             Self::Fun(fun) => {
                 if let FunStmt::Default {
@@ -87,24 +176,15 @@ impl Emitter for Stmt {
                     chunk.push_constant(Constant::Size(usize::MAX), span.clone());
                     for param in fun.params().iter().rev() {
                         chunk.push_constant(
-                            Constant::String(param.ident().span().source_text()),
+                            Constant::Symbol(param.ident().symbol()),
                             param.ident().span().clone(),
                         );
                     }
                     chunk.push_constant(Constant::Size(fun.params().len()), span.clone());
-                    chunk.push_constant(
-                        Constant::String(ident.span().source_text()),
-                        ident.span().clone(),
-                    );
+                    chunk.push_constant(Constant::Symbol(ident.symbol()), ident.span().clone());
                     chunk.push_instruction(Bytecode::DeclareFunction, span.clone());
                     let start = chunk.len();
-                    chunk.push_instruction(Bytecode::BeginScope, block.span().clone());
-                    for stmt in block.stmts() {
-                        stmt.emit(chunk);
-                    }
-                    chunk.push_constant(Constant::Nil, span.clone());
-                    chunk.push_instruction(Bytecode::ConvertConstant, span.clone());
-                    chunk.push_instruction(Bytecode::Return, span.clone());
+                    emit_body(block, span, context, chunk);
                     let end = chunk.len();
                     if let Some(constant) = chunk.constant_mut(function_start) {
                         *constant = Constant::Size(start);
@@ -112,12 +192,20 @@ impl Emitter for Stmt {
                     if let Some(constant) = chunk.constant_mut(function_end) {
                         *constant = Constant::Size(end);
                     }
+                } else {
+                    emit_params(fun.params(), chunk);
+                    chunk.push_constant(Constant::Size(fun.params().len()), fun.span().clone());
+                    chunk.push_constant(
+                        Constant::Symbol(fun.ident().symbol()),
+                        fun.ident().span().clone(),
+                    );
+                    chunk.push_instruction(Bytecode::DeclareExternFunction, fun.span().clone());
                 }
             }
             Self::Impl(r#impl) => {
                 for method in r#impl.methods() {
                     chunk.push_constant(
-                        Constant::String(r#impl.ty().ident().source_text()),
+                        Constant::Symbol(r#impl.ty().ident().symbol()),
                         r#impl.ty().ident().span().clone(),
                     );
                     if let Method::Default {
@@ -130,24 +218,20 @@ impl Emitter for Stmt {
                         chunk.push_constant(Constant::Size(usize::MAX), span.clone());
                         for param in method.params().iter().rev() {
                             chunk.push_constant(
-                                Constant::String(param.ident().span().source_text()),
+                                Constant::Symbol(param.ident().symbol()),
                                 param.ident().span().clone(),
                             );
                         }
                         chunk.push_constant(Constant::Size(method.params().len()), span.clone());
-                        chunk.push_constant(
-                            Constant::String(ident.span().source_text()),
-                            ident.span().clone(),
-                        );
-                        chunk.push_instruction(Bytecode::DeclareMethod, r#impl.ty().span().clone());
+                        chunk.push_constant(Constant::Symbol(ident.symbol()), ident.span().clone());
+                        let bytecode = if method.is_static() {
+                            Bytecode::DeclareStaticMethod
+                        } else {
+                            Bytecode::DeclareMethod
+                        };
+                        chunk.push_instruction(bytecode, r#impl.ty().span().clone());
                         let start = chunk.len();
-                        chunk.push_instruction(Bytecode::BeginScope, block.span().clone());
-                        for stmt in block.stmts() {
-                            stmt.emit(chunk);
-                        }
-                        chunk.push_constant(Constant::Nil, span.clone());
-                        chunk.push_instruction(Bytecode::ConvertConstant, span.clone());
-                        chunk.push_instruction(Bytecode::Return, span.clone());
+                        emit_body(block, span, context, chunk);
                         let end = chunk.len();
                         if let Some(constant) = chunk.constant_mut(function_start) {
                             *constant = Constant::Size(start);
@@ -155,18 +239,55 @@ impl Emitter for Stmt {
                         if let Some(constant) = chunk.constant_mut(function_end) {
                             *constant = Constant::Size(end);
                         }
+                    } else {
+                        chunk.push_constant(
+                            Constant::Bool(method.is_static()),
+                            method.span().clone(),
+                        );
+                        emit_params(method.params(), chunk);
+                        chunk.push_constant(
+                            Constant::Size(method.params().len()),
+                            method.span().clone(),
+                        );
+                        chunk.push_constant(
+                            Constant::Symbol(method.ident().symbol()),
+                            method.ident().span().clone(),
+                        );
+                        chunk
+                            .push_instruction(Bytecode::DeclareExternMethod, method.span().clone());
                     }
                 }
+                if let Some(tr) = r#impl.tr() {
+                    chunk.push_constant(
+                        Constant::Symbol(r#impl.ty().ident().symbol()),
+                        r#impl.ty().ident().span().clone(),
+                    );
+                    chunk.push_constant(Constant::Symbol(tr.ident().symbol()), tr.span().clone());
+                    let methods = r#impl
+                        .methods()
+                        .iter()
+                        .filter(|method| !method.is_static())
+                        .collect::<Vec<_>>();
+                    for method in &methods {
+                        chunk.push_constant(
+                            Constant::Symbol(method.ident().symbol()),
+                            method.ident().span().clone(),
+                        );
+                    }
+                    chunk.push_constant(Constant::Size(methods.len()), tr.span().clone());
+                    chunk.push_instruction(Bytecode::ImplementTrait, tr.span().clone());
+                }
             }
             Self::If(r#if) => {
-                r#if.cond().emit(chunk);
+                r#if.cond().emit(context, chunk);
+                chunk.push_instruction(Bytecode::ToBool, r#if.cond().span().clone());
                 let then_jump = chunk.len();
                 chunk.push_constant(Constant::Size(usize::MAX), r#if.span().clone());
                 chunk.push_instruction(Bytecode::JumpIfFalse, r#if.span().clone());
                 let start = chunk.len();
                 chunk.push_instruction(Bytecode::BeginScope, r#if.span().clone());
                 for stmt in r#if.stmts() {
-                    stmt.emit(chunk);
+                    stmt.emit(context, chunk);
                 }
                 chunk.push_instruction(Bytecode::EndScope, r#if.span().clone());
                 let end = chunk.len();
@@ -175,117 +296,297 @@ impl Emitter for Stmt {
                     *constant = Constant::Size(offset);
                 }
             }
-            Self::Trait(_) => {}
+            Self::Trait(r#trait) => {
+                chunk.push_constant(
+                    Constant::Symbol(r#trait.ident().symbol()),
+                    r#trait.ident().span().clone(),
+                );
+                chunk.push_instruction(Bytecode::DeclareTrait, r#trait.ident().span().clone());
+                for proto in r#trait.protos() {
+                    let Some(block) = proto.block() else {
+                        continue;
+                    };
+                    let span = proto.span();
+                    chunk.push_constant(
+                        Constant::Symbol(r#trait.ident().symbol()),
+                        r#trait.ident().span().clone(),
+                    );
+                    let function_end = chunk.len();
+                    chunk.push_constant(Constant::Size(usize::MAX), span.clone());
+                    let function_start = chunk.len();
+                    chunk.push_constant(Constant::Size(usize::MAX), span.clone());
+                    for param in proto.params().iter().rev() {
+                        chunk.push_constant(
+                            Constant::Symbol(param.ident().symbol()),
+                            param.ident().span().clone(),
+                        );
+                    }
+                    chunk.push_constant(Constant::Size(proto.params().len()), span.clone());
+                    chunk.push_constant(
+                        Constant::Symbol(proto.ident().symbol()),
+                        proto.ident().span().clone(),
+                    );
+                    chunk.push_instruction(
+                        Bytecode::DeclareTraitMethod,
+                        r#trait.ident().span().clone(),
+                    );
+                    let start = chunk.len();
+                    emit_body(block, span, context, chunk);
+                    let end = chunk.len();
+                    if let Some(constant) = chunk.constant_mut(function_start) {
+                        *constant = Constant::Size(start);
+                    }
+                    if let Some(constant) = chunk.constant_mut(function_end) {
+                        *constant = Constant::Size(end);
+                    }
+                }
+            }
+            Self::Throw(throw) => {
+                throw.expr().emit(context, chunk);
+                chunk.push_instruction(Bytecode::Throw, throw.span().clone());
+            }
+            Self::Try(r#try) => {
+                let span = r#try.span();
+                let finally_jump = r#try
+                    .finally()
+                    .map(|_| push_jump(chunk, Bytecode::PushHandler, span));
+                let finally = Protected {
+                    handlers: usize::from(finally_jump.is_some()),
+                    finally: r#try.finally(),
+                };
+                if let Some(catch) = r#try.catch() {
+                    let catch_jump = push_jump(chunk, Bytecode::PushHandler, span);
+                    context.protected.push(Protected {
+                        handlers: finally.handlers + 1,
+                        ..finally
+                    });
+                    emit_block(r#try.block().stmts(), r#try.block().span(), context, chunk);
+                    context.protected.pop();
+                    chunk.push_instruction(Bytecode::PopHandler, span.clone());
+                    let end_jump = push_jump(chunk, Bytecode::Jump, span);
+                    patch_jump(chunk, catch_jump);
+                    chunk.push_instruction(Bytecode::BeginScope, catch.span().clone());
+                    chunk.push_constant(
+                        Constant::Symbol(catch.ident().symbol()),
+                        catch.ident().span().clone(),
+                    );
+                    chunk.push_instruction(Bytecode::DeclareVariable, catch.ident().span().clone());
+                    context.protected.push(finally);
+                    for stmt in catch.block().stmts() {
+                        stmt.emit(context, chunk);
+                    }
+                    context.protected.pop();
+                    chunk.push_instruction(Bytecode::EndScope, catch.span().clone());
+                    patch_jump(chunk, end_jump);
+                } else {
+                    context.protected.push(finally);
+                    emit_block(r#try.block().stmts(), r#try.block().span(), context, chunk);
+                    context.protected.pop();
+                }
+                if let (Some(finally), Some(finally_jump)) = (r#try.finally(), finally_jump) {
+                    // The finally block is emitted twice: once when the try
+                    // block completes, and once to run it before rethrowing
+                    // the error that left it.
+                    chunk.push_instruction(Bytecode::PopHandler, span.clone());
+                    emit_block(finally.stmts(), finally.span(), context, chunk);
+                    let end_jump = push_jump(chunk, Bytecode::Jump, span);
+                    patch_jump(chunk, finally_jump);
+                    emit_block(finally.stmts(), finally.span(), context, chunk);
+                    chunk.push_instruction(Bytecode::Throw, finally.span().clone());
+                    patch_jump(chunk, end_jump);
+                }
+            }
             _ => todo!(),
         }
     }
 }
 
 impl Emitter for Expr {
-    fn emit(&self, chunk: &mut Chunk) {
+    fn emit<'ast>(&'ast self, context: &mut Context<'ast>, chunk: &mut Chunk) {
         match self {
             Self::Ident(ident) => {
-                let ident_name = ident.ident().source_text();
-                chunk.push_constant(Constant::String(ident_name), ident.ident().span().clone());
+                chunk.push_constant(
+                    Constant::Symbol(ident.ident().symbol()),
+                    ident.ident().span().clone(),
+                );
                 chunk.push_instruction(Bytecode::GetSymbol, ident.span().clone());
             }
             Self::Lit(lit) => match lit {
                 LitExpr::Num { span } => {
-                    let num = span.source_text().parse::<f64>().unwrap();
+                    let num = parse_number(span.as_str())
+                        .expect("number literals are validated by the lexer");
                     chunk.push_constant(Constant::Number(num), span.clone());
                     chunk.push_instruction(Bytecode::ConvertConstant, span.clone());
                 }
                 LitExpr::Bool { span } => {
-                    let num = span.source_text().parse::<bool>().unwrap();
+                    let num = span.as_str().parse::<bool>().unwrap();
                     chunk.push_constant(Constant::Bool(num), span.clone());
                     chunk.push_instruction(Bytecode::ConvertConstant, span.clone());
                 }
+                LitExpr::Str { span } => {
+                    let value = parse_string(span.as_str())
+                        .expect("string literals are validated by the lexer");
+                    chunk.push_constant(Constant::Str(value), span.clone());
+                    chunk.push_instruction(Bytecode::ConvertConstant, span.clone());
+                }
                 LitExpr::Nil { span } => {
                     chunk.push_constant(Constant::Nil, span.clone());
                     chunk.push_instruction(Bytecode::ConvertConstant, span.clone());
                 }
             },
+            Self::Path(path) => {
+                chunk.push_constant(
+                    Constant::Symbol(path.ty().symbol()),
+                    path.ty().span().clone(),
+                );
+                chunk.push_instruction(Bytecode::GetSymbol, path.ty().span().clone());
+                chunk.push_constant(
+                    Constant::Symbol(path.ident().symbol()),
+                    path.ident().span().clone(),
+                );
+                chunk.push_instruction(Bytecode::GetStatic, path.ident().span().clone());
+            }
             Self::Get(get) => {
-                get.expr().emit(chunk);
+                get.expr().emit(context, chunk);
+                let jump = get
+                    .is_safe()
+                    .then(|| push_jump(chunk, Bytecode::JumpIfNil, get.span()));
                 chunk.push_constant(
-                    Constant::String(get.ident().source_text()),
+                    Constant::Symbol(get.ident().symbol()),
                     get.ident().span().clone(),
                 );
                 chunk.push_instruction(Bytecode::GetProperty, get.ident().span().clone());
+                if let Some(jump) = jump {
+                    patch_jump(chunk, jump);
+                }
             }
             Self::Call(call) => {
-                call.callee().emit(chunk);
+                // A method called through `?.` is skipped along with its
+                // arguments when the object is nil.
+                let jump = match call.callee() {
+                    Expr::Get(get) if get.is_safe() => {
+                        get.expr().emit(context, chunk);
+                        let jump = push_jump(chunk, Bytecode::JumpIfNil, get.span());
+                        chunk.push_constant(
+                            Constant::Symbol(get.ident().symbol()),
+                            get.ident().span().clone(),
+                        );
+                        chunk.push_instruction(Bytecode::GetProperty, get.ident().span().clone());
+                        Some(jump)
+                    }
+                    callee => {
+                        callee.emit(context, chunk);
+                        None
+                    }
+                };
                 for arg in call.args().iter().rev() {
-                    arg.emit(chunk);
+                    arg.emit(context, chunk);
                 }
                 chunk.push_constant(Constant::Size(call.args().len()), call.span().clone());
                 chunk.push_instruction(Bytecode::CallFunction, call.callee().span().clone());
+                if let Some(jump) = jump {
+                    patch_jump(chunk, jump);
+                }
+            }
+            Self::Unwrap(unwrap) => {
+                unwrap.expr().emit(context, chunk);
+                chunk.push_instruction(Bytecode::Unwrap, unwrap.span().clone());
+            }
+            Self::Try(r#try) => {
+                // An `Err` or a `None` is returned the same way as with a
+                // `return`, which the value of an `Ok` or a `Some` skips.
+                r#try.expr().emit(context, chunk);
+                let jump = push_jump(chunk, Bytecode::Propagate, r#try.span());
+                emit_return(r#try.span(), context, chunk);
+                patch_jump(chunk, jump);
             }
             Self::Paren(paren) => {
-                paren.expr().emit(chunk);
+                paren.expr().emit(context, chunk);
             }
             Self::Unary(unary) => {
-                unary.expr().emit(chunk);
-                let op = &unary.op().source_text()[..];
+                unary.expr().emit(context, chunk);
+                let op = unary.op().span().as_str();
                 match op {
                     "-" => chunk.push_instruction(Bytecode::Negate, unary.span().clone()),
                     "!" => chunk.push_instruction(Bytecode::Not, unary.span().clone()),
                     _ => panic!("Operator not implemented yet"),
                 }
             }
+            Self::Binary(binary) if matches!(binary.op().span().as_str(), "&&" | "||") => {
+                let jump = if binary.op().span().as_str() == "&&" {
+                    Bytecode::JumpIfFalseOrPop
+                } else {
+                    Bytecode::JumpIfTrueOrPop
+                };
+                binary.left().emit(context, chunk);
+                chunk.push_instruction(Bytecode::ToBool, binary.left().span().clone());
+                let jump = push_jump(chunk, jump, binary.span());
+                binary.right().emit(context, chunk);
+                chunk.push_instruction(Bytecode::ToBool, binary.right().span().clone());
+                patch_jump(chunk, jump);
+            }
+            Self::Binary(binary) if binary.op().span().as_str() == "??" => {
+                binary.left().emit(context, chunk);
+                let jump = push_jump(chunk, Bytecode::JumpIfNotNil, binary.span());
+                chunk.push_instruction(Bytecode::Pop, binary.span().clone());
+                binary.right().emit(context, chunk);
+                patch_jump(chunk, jump);
+            }
+            Self::Binary(binary) if binary.op().span().as_str() == "=" => {
+                binary.right().emit(context, chunk);
+                let mut expr = binary.left();
+                while let Expr::Paren(paren) = expr {
+                    expr = paren.expr();
+                }
+                if let Expr::Ident(ident) = expr {
+                    chunk.push_constant(
+                        Constant::Symbol(ident.ident().symbol()),
+                        ident.ident().span().clone(),
+                    );
+                    chunk.push_instruction(Bytecode::SetVariable, binary.span().clone());
+                } else if let Expr::Get(get) = expr {
+                    chunk.push_constant(
+                        Constant::Symbol(get.ident().symbol()),
+                        get.ident().span().clone(),
+                    );
+                    get.expr().emit(context, chunk);
+                    chunk.push_instruction(Bytecode::SetProperty, binary.span().clone());
+                }
+            }
             Self::Binary(binary) => {
-                binary.left().emit(chunk);
-                binary.right().emit(chunk);
-                let op = &binary.op().source_text()[..];
+                binary.left().emit(context, chunk);
+                binary.right().emit(context, chunk);
+                let op = binary.op().span().as_str();
                 match op {
                     "+" => chunk.push_instruction(Bytecode::Add, binary.span().clone()),
                     "-" => chunk.push_instruction(Bytecode::Subtract, binary.span().clone()),
                     "*" => chunk.push_instruction(Bytecode::Multiply, binary.span().clone()),
                     "/" => chunk.push_instruction(Bytecode::Divide, binary.span().clone()),
+                    "%" => chunk.push_instruction(Bytecode::Remainder, binary.span().clone()),
                     "==" => chunk.push_instruction(Bytecode::Equals, binary.span().clone()),
                     "!=" => {
                         chunk.push_instruction(Bytecode::Equals, binary.span().clone());
                         chunk.push_instruction(Bytecode::Not, binary.span().clone());
                     }
-                    "=" => {
-                        binary.right().emit(chunk);
-                        let mut expr = binary.left();
-                        while let Expr::Paren(paren) = expr {
-                            expr = paren.expr();
-                        }
-                        if let Expr::Ident(ident) = expr {
-                            chunk.push_constant(
-                                Constant::String(ident.ident().span().source_text()),
-                                ident.ident().span().clone(),
-                            );
-                            chunk.push_instruction(Bytecode::SetVariable, binary.span().clone());
-                        } else if let Expr::Get(get) = expr {
-                            chunk.push_constant(
-                                Constant::String(get.ident().span().source_text()),
-                                get.ident().span().clone(),
-                            );
-                            get.expr().emit(chunk);
-                            chunk.push_instruction(Bytecode::SetProperty, binary.span().clone());
-                        }
-                    }
                     _ => todo!(),
                 }
             }
             Self::Class(class) => {
-                class.cls().emit(chunk);
+                class.cls().emit(context, chunk);
                 for field in class.fields().iter().rev() {
                     if let Some(value) = field.value() {
-                        value.emit(chunk);
+                        value.emit(context, chunk);
                     } else {
-                        let ident_name = field.ident().source_text();
                         chunk.push_constant(
-                            Constant::String(ident_name),
+                            Constant::Symbol(field.ident().symbol()),
                             class.cls().span().clone(),
                         );
                         chunk.push_instruction(Bytecode::GetSymbol, class.cls().span().clone());
                     }
-                    let field_name = field.ident().source_text();
-                    chunk.push_constant(Constant::String(field_name), field.ident().span().clone());
+                    chunk.push_constant(
+                        Constant::Symbol(field.ident().symbol()),
+                        field.ident().span().clone(),
+                    );
                 }
                 chunk.push_constant(
                     Constant::Size(class.fields().len()),
@@ -296,3 +597,82 @@ impl Emitter for Expr {
         }
     }
 }
+
+/// Pushes a jump whose offset is set by [`patch_jump`] once the instructions
+/// it skips are emitted, returning where the offset and those instructions
+/// start.
+fn push_jump(chunk: &mut Chunk, jump: Bytecode, span: &Span) -> (usize, usize) {
+    let offset = chunk.len();
+    chunk.push_constant(Constant::Size(usize::MAX), span.clone());
+    chunk.push_instruction(jump, span.clone());
+    (offset, chunk.len())
+}
+
+fn patch_jump(chunk: &mut Chunk, (offset, start): (usize, usize)) {
+    let end = chunk.len();
+    if let Some(constant) = chunk.constant_mut(offset) {
+        *constant = Constant::Size(end - start);
+    }
+}
+
+/// Returns the value on top of the stack from the current function. The try
+/// statements being left get their handlers popped and their finally blocks
+/// run, each one outside of the region it protects.
+fn emit_return<'ast>(span: &Span, context: &mut Context<'ast>, chunk: &mut Chunk) {
+    let protected = std::mem::take(&mut context.protected);
+    for (index, region) in protected.iter().enumerate().rev() {
+        for _ in 0..region.handlers {
+            chunk.push_instruction(Bytecode::PopHandler, span.clone());
+        }
+        if let Some(finally) = region.finally {
+            context.protected = protected[..index].to_vec();
+            emit_block(finally.stmts(), finally.span(), context, chunk);
+        }
+    }
+    context.protected = protected;
+    chunk.push_instruction(Bytecode::Return, span.clone());
+}
+
+/// Emits statements in their own scope.
+fn emit_block<'ast>(
+    stmts: &'ast [Stmt],
+    span: &Span,
+    context: &mut Context<'ast>,
+    chunk: &mut Chunk,
+) {
+    chunk.push_instruction(Bytecode::BeginScope, span.clone());
+    for stmt in stmts {
+        stmt.emit(context, chunk);
+    }
+    chunk.push_instruction(Bytecode::EndScope, span.clone());
+}
+
+/// Emits the body of a function, which runs outside of the `try` statements
+/// around its declaration, returning `nil` when it ends without a `return`.
+fn emit_body<'ast>(
+    block: &'ast BlockStmt,
+    span: &Span,
+    context: &mut Context<'ast>,
+    chunk: &mut Chunk,
+) {
+    let protected = std::mem::take(&mut context.protected);
+    chunk.push_instruction(Bytecode::BeginScope, block.span().clone());
+    for stmt in block.stmts() {
+        stmt.emit(context, chunk);
+    }
+    chunk.push_constant(Constant::Nil, span.clone());
+    chunk.push_instruction(Bytecode::ConvertConstant, span.clone());
+    chunk.push_instruction(Bytecode::Return, span.clone());
+    context.protected = protected;
+}
+
+/// Pushes the names of the parameters of a function in reverse, so they're
+/// popped in order.
+fn emit_params(params: &[Param], chunk: &mut Chunk) {
+    for param in params.iter().rev() {
+        chunk.push_constant(
+            Constant::Symbol(param.ident().symbol()),
+            param.ident().span().clone(),
+        );
+    }
+}
@@ -0,0 +1,122 @@
+/// A monomorphic cache entry, valid while the receiver class, the method
+/// table and the epoch it was resolved with stay the same.
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    class_id: usize,
+    method_table_id: usize,
+    epoch: usize,
+    method: usize,
+}
+
+/// The `InlineCache` stores, for every instruction of a chunk, the last
+/// method resolved by that instruction.
+///
+/// Entries are invalidated as a whole by bumping the epoch whenever a
+/// method table changes.
+#[derive(Debug)]
+pub(crate) struct InlineCache {
+    epoch: usize,
+    entries: Vec<Option<CacheEntry>>,
+}
+
+impl InlineCache {
+    /// Creates an empty cache for a chunk with `size` instructions.
+    pub(crate) fn new(size: usize) -> Self {
+        Self {
+            epoch: 0,
+            entries: vec![None; size],
+        }
+    }
+
+    /// Makes room for the instructions appended to the chunk, up to `size`.
+    pub(crate) fn grow(&mut self, size: usize) {
+        if size > self.entries.len() {
+            self.entries.resize(size, None);
+        }
+    }
+
+    /// Gets the method cached by the instruction at `index`, if the entry
+    /// is still valid for the given class and method table.
+    pub(crate) fn get(
+        &self,
+        index: usize,
+        class_id: usize,
+        method_table_id: usize,
+    ) -> Option<usize> {
+        match self.entries.get(index) {
+            Some(Some(entry))
+                if entry.class_id == class_id
+                    && entry.method_table_id == method_table_id
+                    && entry.epoch == self.epoch =>
+            {
+                Some(entry.method)
+            }
+            _ => None,
+        }
+    }
+
+    /// Caches the method resolved by the instruction at `index`.
+    pub(crate) fn set(
+        &mut self,
+        index: usize,
+        class_id: usize,
+        method_table_id: usize,
+        method: usize,
+    ) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            *entry = Some(CacheEntry {
+                class_id,
+                method_table_id,
+                epoch: self.epoch,
+                method,
+            });
+        }
+    }
+
+    /// Invalidates every entry of the cache.
+    pub(crate) fn invalidate(&mut self) {
+        self.epoch += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits_with_the_same_class_and_method_table() {
+        let mut cache = InlineCache::new(4);
+        cache.set(1, 10, 20, 30);
+        assert_eq!(cache.get(1, 10, 20), Some(30));
+    }
+
+    #[test]
+    fn misses_with_another_class_method_table_or_instruction() {
+        let mut cache = InlineCache::new(4);
+        assert_eq!(cache.get(1, 10, 20), None);
+        cache.set(1, 10, 20, 30);
+        assert_eq!(cache.get(1, 11, 20), None);
+        assert_eq!(cache.get(1, 10, 21), None);
+        assert_eq!(cache.get(2, 10, 20), None);
+    }
+
+    #[test]
+    fn misses_after_an_invalidation() {
+        let mut cache = InlineCache::new(4);
+        cache.set(1, 10, 20, 30);
+        cache.invalidate();
+        assert_eq!(cache.get(1, 10, 20), None);
+        cache.set(1, 10, 20, 31);
+        assert_eq!(cache.get(1, 10, 20), Some(31));
+    }
+
+    #[test]
+    fn grows_for_appended_instructions() {
+        let mut cache = InlineCache::new(1);
+        cache.set(3, 10, 20, 30);
+        assert_eq!(cache.get(3, 10, 20), None);
+        cache.grow(4);
+        cache.set(3, 10, 20, 30);
+        assert_eq!(cache.get(3, 10, 20), Some(30));
+    }
+}
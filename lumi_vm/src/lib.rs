@@ -1,9 +1,19 @@
+#![allow(clippy::result_large_err)]
+
 mod call_frame;
 pub mod chunk;
+pub mod compile_error;
 pub mod emitter;
+mod inline_cache;
+pub mod limits;
 mod memory;
+mod native;
 mod object;
+pub mod permissions;
 mod runtime_error;
 mod scope;
 mod stack_trace;
 pub mod vm;
+
+#[cfg(test)]
+mod test_utils;
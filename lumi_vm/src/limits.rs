@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+/// The resources a script can use before the [`Vm`](crate::vm::Vm) stops it,
+/// meant for running untrusted code. Every limit is off by default.
+///
+/// Exceeding a limit returns a runtime error that scripts can't catch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VmLimits {
+    /// The maximum number of instructions executed.
+    pub max_steps: Option<usize>,
+    /// The maximum wall-clock duration of the run.
+    pub timeout: Option<Duration>,
+    /// The maximum number of objects in memory. There's no garbage
+    /// collector yet, so every object allocated, including the classes and
+    /// functions declared by the prelude, counts.
+    pub max_heap: Option<usize>,
+}
@@ -1,5 +1,6 @@
 use crate::object::Object;
 
+#[derive(Clone)]
 pub(crate) struct Memory {
     heap: Vec<Object>,
 }
@@ -14,6 +15,10 @@ impl Memory {
         self.heap.len() - 1
     }
 
+    pub(crate) fn len(&self) -> usize {
+        self.heap.len()
+    }
+
     pub(crate) fn get(&self, index: usize) -> &Object {
         self.heap.get(index).unwrap()
     }
@@ -0,0 +1,386 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, Write},
+    path::Path,
+    rc::Rc,
+    time::Instant,
+};
+
+use lumi_lxr::symbol::sym;
+
+use crate::{
+    object::{NativeFunction, Object, Primitive},
+    permissions::Permission,
+    runtime_error::RuntimeError,
+    vm::Vm,
+};
+
+/// A Rust function that an `extern` declaration binds to.
+pub(crate) struct Native {
+    params: usize,
+    fun: NativeFunction,
+}
+
+impl Native {
+    /// Gets the number of parameters the function takes, without counting
+    /// `this` for methods.
+    pub(crate) fn params(&self) -> usize {
+        self.params
+    }
+
+    pub(crate) fn fun(&self) -> &NativeFunction {
+        &self.fun
+    }
+}
+
+/// The `Natives` registry stores the Rust functions available to `extern`
+/// declarations, keyed by their qualified name: `clock` for a function and
+/// `Num.add` for a method.
+///
+/// Natives receive their arguments in order, preceded by `this` for
+/// methods.
+pub(crate) struct Natives {
+    natives: HashMap<String, Native>,
+}
+
+impl Natives {
+    /// Creates a registry with the natives declared by `core.ls` and
+    /// `std.ls`.
+    pub(crate) fn new() -> Self {
+        let mut natives = Self {
+            natives: HashMap::new(),
+        };
+        let start = Instant::now();
+        natives.register("clock", 0, move |_, _| {
+            let diff = (Instant::now() - start).as_millis();
+            Ok(Object::Primitive(Primitive::new(2, diff as f64)))
+        });
+        natives.register("env", 1, |vm, args| {
+            let name = string(vm, args[0])?;
+            vm.permit(Permission::Env, name)?;
+            match std::env::var(name) {
+                Ok(value) => Ok(Object::Str(value)),
+                Err(_) => Ok(Object::Primitive(Primitive::new(0, 0.0))),
+            }
+        });
+        natives.register("args", 0, |vm, _| {
+            let args = vm.args().iter().cloned().map(Object::Str).collect();
+            vm.list(args)
+        });
+        natives.register("exit", 1, |vm, args| {
+            let code = number(vm, args[0])?;
+            vm.exit(code as i32);
+            Ok(Object::Primitive(Primitive::new(0, 0.0)))
+        });
+        natives.register("assert", 1, |vm, args| {
+            if number(vm, args[0])? != 0.0 {
+                Ok(Object::Primitive(Primitive::new(0, 0.0)))
+            } else {
+                Err(RuntimeError::AssertionFailed {
+                    reason: None,
+                    span: vm.span().clone(),
+                    stack_trace: vm.stack_trace().clone(),
+                })
+            }
+        });
+        natives.register("assertEq", 2, |vm, args| {
+            let equal = match (vm.get_object(args[0]), vm.get_object(args[1])) {
+                (Object::Primitive(actual), Object::Primitive(expected)) => {
+                    actual.class() == expected.class() && actual.value() == expected.value()
+                }
+                (Object::Str(actual), Object::Str(expected)) => actual == expected,
+                _ => args[0] == args[1],
+            };
+            if equal {
+                Ok(Object::Primitive(Primitive::new(0, 0.0)))
+            } else {
+                Err(RuntimeError::AssertionFailed {
+                    reason: Some(format!(
+                        "expected \"{}\", got \"{}\"",
+                        vm.display(args[1]),
+                        vm.display(args[0])
+                    )),
+                    span: vm.span().clone(),
+                    stack_trace: vm.stack_trace().clone(),
+                })
+            }
+        });
+        natives.register("readFile", 1, |vm, args| {
+            let path = string(vm, args[0])?;
+            vm.permit(Permission::Read, path)?;
+            match fs::read_to_string(path) {
+                Ok(content) => Ok(Object::Str(content)),
+                Err(error) => Err(io_error(vm, format!("read \"{}\"", path), error)),
+            }
+        });
+        natives.register("writeFile", 2, |vm, args| {
+            let path = string(vm, args[0])?;
+            let content = string(vm, args[1])?;
+            vm.permit(Permission::Write, path)?;
+            match fs::write(path, content) {
+                Ok(()) => Ok(Object::Primitive(Primitive::new(0, 0.0))),
+                Err(error) => Err(io_error(vm, format!("write \"{}\"", path), error)),
+            }
+        });
+        natives.register("appendFile", 2, |vm, args| {
+            let path = string(vm, args[0])?;
+            let content = string(vm, args[1])?;
+            vm.permit(Permission::Write, path)?;
+            let result = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(content.as_bytes()));
+            match result {
+                Ok(()) => Ok(Object::Primitive(Primitive::new(0, 0.0))),
+                Err(error) => Err(io_error(vm, format!("append to \"{}\"", path), error)),
+            }
+        });
+        natives.register("listDir", 1, |vm, args| {
+            let path = string(vm, args[0])?;
+            vm.permit(Permission::Read, path)?;
+            let entries = fs::read_dir(path).and_then(|entries| {
+                entries
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                    .collect::<io::Result<Vec<_>>>()
+            });
+            match entries {
+                Ok(mut entries) => {
+                    entries.sort();
+                    vm.list(entries.into_iter().map(Object::Str).collect())
+                }
+                Err(error) => Err(io_error(vm, format!("list \"{}\"", path), error)),
+            }
+        });
+        natives.register("exists", 1, |vm, args| {
+            let path = string(vm, args[0])?;
+            vm.permit(Permission::Read, path)?;
+            match Path::new(path).try_exists() {
+                Ok(exists) => Ok(Object::Primitive(Primitive::new(
+                    1,
+                    if exists { 1.0 } else { 0.0 },
+                ))),
+                Err(error) => Err(io_error(vm, format!("check \"{}\"", path), error)),
+            }
+        });
+        natives.register("removeFile", 1, |vm, args| {
+            let path = string(vm, args[0])?;
+            vm.permit(Permission::Write, path)?;
+            match fs::remove_file(path) {
+                Ok(()) => Ok(Object::Primitive(Primitive::new(0, 0.0))),
+                Err(error) => Err(io_error(vm, format!("remove \"{}\"", path), error)),
+            }
+        });
+        natives.register("readLine", 0, |vm, _| {
+            let mut line = String::new();
+            match io::stdin().lock().read_line(&mut line) {
+                Ok(0) => Ok(Object::Primitive(Primitive::new(0, 0.0))),
+                Ok(_) => {
+                    let len = line.trim_end_matches(['\n', '\r']).len();
+                    line.truncate(len);
+                    Ok(Object::Str(line))
+                }
+                Err(error) => Err(io_error(vm, "read from stdin".to_owned(), error)),
+            }
+        });
+        natives.register("print", 1, |vm, args| {
+            let value = string(vm, args[0])?;
+            let mut stdout = io::stdout().lock();
+            match write!(stdout, "{}", value).and_then(|()| stdout.flush()) {
+                Ok(()) => Ok(Object::Primitive(Primitive::new(0, 0.0))),
+                Err(error) => Err(io_error(vm, "write to stdout".to_owned(), error)),
+            }
+        });
+        natives.register("Num.add", 1, |vm, args| {
+            let (this, other) = operands(vm, args)?;
+            Ok(Object::Primitive(Primitive::new(2, this + other)))
+        });
+        natives.register("Num.sub", 1, |vm, args| {
+            let (this, other) = operands(vm, args)?;
+            Ok(Object::Primitive(Primitive::new(2, this - other)))
+        });
+        natives.register("Num.mul", 1, |vm, args| {
+            let (this, other) = operands(vm, args)?;
+            Ok(Object::Primitive(Primitive::new(2, this * other)))
+        });
+        natives.register("Num.div", 1, |vm, args| {
+            let (this, other) = operands(vm, args)?;
+            Ok(Object::Primitive(Primitive::new(2, this / other)))
+        });
+        natives.register("Num.rem", 1, |vm, args| {
+            let (this, other) = operands(vm, args)?;
+            Ok(Object::Primitive(Primitive::new(2, this % other)))
+        });
+        natives.register("Num.eq", 1, |vm, args| {
+            let (this, other) = operands(vm, args)?;
+            Ok(Object::Primitive(Primitive::new(
+                1,
+                if this == other { 1.0 } else { 0.0 },
+            )))
+        });
+        // The math functions are both methods of `Num` and free functions,
+        // which receive the same arguments since `this` comes first.
+        let unary = [
+            ("sqrt", f64::sqrt as fn(f64) -> f64),
+            ("abs", f64::abs),
+            ("floor", f64::floor),
+            ("ceil", f64::ceil),
+            ("round", f64::round),
+            ("sin", f64::sin),
+            ("cos", f64::cos),
+            ("tan", f64::tan),
+            ("asin", f64::asin),
+            ("acos", f64::acos),
+            ("atan", f64::atan),
+            ("exp", f64::exp),
+            ("log", f64::ln),
+            ("log2", f64::log2),
+            ("log10", f64::log10),
+        ];
+        for (name, fun) in unary {
+            let native = move |vm: &mut Vm, args: &[usize]| {
+                let value = number(vm, args[0])?;
+                Ok(Object::Primitive(Primitive::new(2, fun(value))))
+            };
+            natives.register(&format!("Num.{}", name), 0, native);
+            natives.register(name, 1, native);
+        }
+        let binary = [
+            ("pow", f64::powf as fn(f64, f64) -> f64),
+            ("min", f64::min),
+            ("max", f64::max),
+            ("atan2", f64::atan2),
+        ];
+        for (name, fun) in binary {
+            let native = move |vm: &mut Vm, args: &[usize]| {
+                let (this, other) = operands(vm, args)?;
+                Ok(Object::Primitive(Primitive::new(2, fun(this, other))))
+            };
+            natives.register(&format!("Num.{}", name), 1, native);
+            natives.register(name, 2, native);
+        }
+        natives.register("Random.next", 0, |vm, args| {
+            let Object::Instance(this) = vm.get_object(args[0]) else {
+                unreachable!()
+            };
+            let state = this.field(sym::state).unwrap_or(0);
+            let (state, value) = mulberry32(number(vm, state)? as i64 as u32);
+            vm.set_field(
+                args[0],
+                sym::state,
+                Object::Primitive(Primitive::new(2, state as f64)),
+            );
+            Ok(Object::Primitive(Primitive::new(2, value)))
+        });
+        natives.register("Str.add", 1, |vm, args| {
+            let (this, other) = strings(vm, args)?;
+            Ok(Object::Str(format!("{}{}", this, other)))
+        });
+        natives.register("Str.eq", 1, |vm, args| {
+            let (this, other) = strings(vm, args)?;
+            Ok(Object::Primitive(Primitive::new(
+                1,
+                if this == other { 1.0 } else { 0.0 },
+            )))
+        });
+        natives.register("Bool.not", 0, |vm, args| {
+            let Object::Primitive(this) = vm.get_object(args[0]) else {
+                unreachable!()
+            };
+            Ok(Object::Primitive(Primitive::new(
+                1,
+                if this.value() == 0.0 { 1.0 } else { 0.0 },
+            )))
+        });
+        natives
+    }
+
+    pub(crate) fn register(
+        &mut self,
+        name: &str,
+        params: usize,
+        fun: impl Fn(&mut Vm, &[usize]) -> Result<Object, RuntimeError> + 'static,
+    ) {
+        let native = Native {
+            params,
+            fun: Rc::new(fun),
+        };
+        self.natives.insert(name.to_owned(), native);
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Native> {
+        self.natives.get(name)
+    }
+}
+
+/// Reads the values of `this` and of the only argument of a binary operator
+/// implemented by a primitive.
+fn operands(vm: &Vm, args: &[usize]) -> Result<(f64, f64), RuntimeError> {
+    match (vm.get_object(args[0]), vm.get_object(args[1])) {
+        (Object::Primitive(this), Object::Primitive(other)) => Ok((this.value(), other.value())),
+        _ => Err(RuntimeError::InvalidBinaryOperands {
+            span: vm.span().clone(),
+            stack_trace: vm.stack_trace().clone(),
+        }),
+    }
+}
+
+/// Reads an argument declared as a `Num`, which is only needed when the
+/// types weren't checked.
+fn number(vm: &Vm, object_id: usize) -> Result<f64, RuntimeError> {
+    match vm.get_object(object_id) {
+        Object::Primitive(value) => Ok(value.value()),
+        _ => Err(RuntimeError::Custom {
+            message: "expected a \"Num\"".to_owned(),
+            span: vm.span().clone(),
+            stack_trace: vm.stack_trace().clone(),
+        }),
+    }
+}
+
+/// Advances the state of the Mulberry32 generator, returning the new state
+/// and a number between 0, inclusive, and 1, exclusive. A 32 bits state is
+/// stored exactly by a `Num`, so `Random` can keep it in a field.
+fn mulberry32(state: u32) -> (u32, f64) {
+    let state = state.wrapping_add(0x6D2B_79F5);
+    let mut value = (state ^ (state >> 15)).wrapping_mul(state | 1);
+    value ^= value.wrapping_add((value ^ (value >> 7)).wrapping_mul(value | 61));
+    value ^= value >> 14;
+    (state, value as f64 / 4_294_967_296.0)
+}
+
+/// Reads `this` and the only argument of a binary operator implemented by
+/// `Str`.
+fn strings<'vm>(vm: &'vm Vm, args: &[usize]) -> Result<(&'vm str, &'vm str), RuntimeError> {
+    match (vm.get_object(args[0]), vm.get_object(args[1])) {
+        (Object::Str(this), Object::Str(other)) => Ok((this, other)),
+        _ => Err(RuntimeError::InvalidBinaryOperands {
+            span: vm.span().clone(),
+            stack_trace: vm.stack_trace().clone(),
+        }),
+    }
+}
+
+/// Reads an argument declared as a `Str`, which is only needed when the
+/// types weren't checked.
+fn string(vm: &Vm, object_id: usize) -> Result<&str, RuntimeError> {
+    match vm.get_object(object_id) {
+        Object::Str(value) => Ok(value),
+        _ => Err(RuntimeError::Custom {
+            message: "expected a \"Str\"".to_owned(),
+            span: vm.span().clone(),
+            stack_trace: vm.stack_trace().clone(),
+        }),
+    }
+}
+
+/// Creates the runtime error of a native that failed to access the system.
+fn io_error(vm: &Vm, action: String, error: io::Error) -> RuntimeError {
+    RuntimeError::Io {
+        action,
+        error,
+        span: vm.span().clone(),
+        stack_trace: vm.stack_trace().clone(),
+    }
+}
@@ -1,51 +1,87 @@
 use std::{collections::HashMap, fmt::Debug, ops::Range, rc::Rc};
 
+use lumi_lxr::symbol::Symbol;
+
 use crate::{runtime_error::RuntimeError, scope::Scope, vm::Vm};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum Object {
     Class(Class),
     Primitive(Primitive),
     Instance(Instance),
     Function(Function),
+    Trait(Trait),
+    Str(String),
 }
 
+/// The id of the `Str` class, allocated after `Nil`, `Bool` and `Num`.
+pub(crate) const STR_CLASS: usize = 3;
+
 impl Object {
     pub(crate) fn class_id(&self) -> Option<usize> {
         match self {
             Self::Class(_) => None,
             Self::Function(_) => None,
+            Self::Trait(_) => None,
             Self::Instance(instance) => Some(instance.class),
             Self::Primitive(primitive) => Some(primitive.class),
+            Self::Str(_) => Some(STR_CLASS),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Class {
-    name: String,
+    name: Symbol,
 }
 
 impl Class {
-    pub(crate) fn new(name: &str) -> Self {
+    pub(crate) fn new(name: Symbol) -> Self {
+        Self { name }
+    }
+
+    pub(crate) fn name(&self) -> Symbol {
+        self.name
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Trait {
+    name: Symbol,
+    methods: HashMap<Symbol, usize>,
+}
+
+impl Trait {
+    pub(crate) fn new(name: Symbol) -> Self {
         Self {
-            name: name.to_owned(),
+            name,
+            methods: HashMap::new(),
         }
     }
 
-    pub(crate) fn name(&self) -> String {
-        self.name.clone()
+    pub(crate) fn name(&self) -> Symbol {
+        self.name
+    }
+
+    /// Gets the methods with a default body, which are copied to the
+    /// classes implementing the trait.
+    pub(crate) fn methods(&self) -> &HashMap<Symbol, usize> {
+        &self.methods
+    }
+
+    pub(crate) fn set_method(&mut self, name: Symbol, method: usize) {
+        self.methods.insert(name, method);
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Instance {
     class: usize,
-    fields: HashMap<String, usize>,
+    fields: HashMap<Symbol, usize>,
 }
 
 impl Instance {
-    pub(crate) fn new(class: usize, fields: HashMap<String, usize>) -> Self {
+    pub(crate) fn new(class: usize, fields: HashMap<Symbol, usize>) -> Self {
         Self { class, fields }
     }
 
@@ -53,16 +89,16 @@ impl Instance {
         self.class
     }
 
-    pub(crate) fn field(&self, key: &str) -> Option<usize> {
-        self.fields.get(key).map(|value| *value)
+    pub(crate) fn field(&self, key: Symbol) -> Option<usize> {
+        self.fields.get(&key).copied()
     }
 
-    pub(crate) fn set_field(&mut self, key: &str, value: usize) {
-        self.fields.insert(key.to_owned(), value);
+    pub(crate) fn set_field(&mut self, key: Symbol, value: usize) {
+        self.fields.insert(key, value);
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Primitive {
     class: usize,
     value: f64,
@@ -82,17 +118,18 @@ impl Primitive {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Function {
-    name: String,
-    params: Vec<String>,
+    name: Symbol,
+    params: Vec<Symbol>,
     class: Option<usize>,
+    is_static: bool,
     inner: InnerFunction,
 }
 
-pub(crate) type NativeFunction =
-    Box<dyn Fn(&Vm, HashMap<String, usize>) -> Result<Object, RuntimeError>>;
+pub(crate) type NativeFunction = Rc<dyn Fn(&mut Vm, &[usize]) -> Result<Object, RuntimeError>>;
 
+#[derive(Clone)]
 pub(crate) enum InnerFunction {
     Native {
         fun: NativeFunction,
@@ -118,24 +155,39 @@ impl Debug for InnerFunction {
 
 impl Function {
     pub(crate) fn new(
-        name: &str,
-        params: &[String],
+        name: Symbol,
+        params: &[Symbol],
         class: Option<usize>,
         inner: InnerFunction,
     ) -> Self {
         Self {
-            name: name.to_owned(),
+            name,
             params: params.to_vec(),
             class,
+            is_static: false,
             inner,
         }
     }
 
-    pub(crate) fn name(&self) -> String {
-        self.name.clone()
+    /// Creates a static method of `class`, which sees `This` but isn't
+    /// bound to an instance.
+    pub(crate) fn new_static(
+        name: Symbol,
+        params: &[Symbol],
+        class: usize,
+        inner: InnerFunction,
+    ) -> Self {
+        Self {
+            is_static: true,
+            ..Self::new(name, params, Some(class), inner)
+        }
+    }
+
+    pub(crate) fn name(&self) -> Symbol {
+        self.name
     }
 
-    pub(crate) fn params(&self) -> &Vec<String> {
+    pub(crate) fn params(&self) -> &Vec<Symbol> {
         &self.params
     }
 
@@ -146,6 +198,10 @@ impl Function {
     pub(crate) fn class(&self) -> Option<usize> {
         self.class
     }
+
+    pub(crate) fn is_static(&self) -> bool {
+        self.is_static
+    }
 }
 
 impl InnerFunction {
@@ -0,0 +1,237 @@
+use std::fmt::Display;
+use std::path::{self, Component, PathBuf};
+
+/// A kind of access to the system that natives need to be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Reading files and directories.
+    Read,
+    /// Creating, changing and removing files.
+    Write,
+    /// Reading environment variables.
+    Env,
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Write => write!(f, "write"),
+            Self::Env => write!(f, "env"),
+        }
+    }
+}
+
+/// What a [`Permission`] is granted for.
+#[derive(Debug, Clone, Default)]
+pub enum Grant {
+    #[default]
+    Denied,
+    All,
+    /// Only the given paths, and what's inside them, for `Read` and
+    /// `Write`, and the given variables for `Env`.
+    Only(Vec<String>),
+}
+
+/// The accesses to the system granted to a script, everything being denied
+/// by default.
+#[derive(Debug, Clone, Default)]
+pub struct Permissions {
+    pub read: Grant,
+    pub write: Grant,
+    pub env: Grant,
+}
+
+impl Permissions {
+    /// Grants every permission.
+    pub fn all() -> Self {
+        Self {
+            read: Grant::All,
+            write: Grant::All,
+            env: Grant::All,
+        }
+    }
+
+    /// Checks whether the permission is granted for `target`, a path or
+    /// the name of an environment variable.
+    pub fn allows(&self, permission: Permission, target: &str) -> bool {
+        let grant = match permission {
+            Permission::Read => &self.read,
+            Permission::Write => &self.write,
+            Permission::Env => &self.env,
+        };
+        match grant {
+            Grant::Denied => false,
+            Grant::All => true,
+            Grant::Only(targets) => match permission {
+                Permission::Read | Permission::Write => {
+                    let target = resolve(target);
+                    targets
+                        .iter()
+                        .any(|allowed| target.starts_with(resolve(allowed)))
+                }
+                Permission::Env => targets.iter().any(|allowed| allowed == target),
+            },
+        }
+    }
+}
+
+/// Resolves a path the way the system does when it's accessed, following
+/// the links of the longest part of it that exists, so a link inside an
+/// allowed directory can't lead out of it. The rest of the path, which
+/// doesn't exist yet, is appended without its `.` and `..` components.
+fn resolve(path: &str) -> PathBuf {
+    let path = path::absolute(path).unwrap_or_else(|_| PathBuf::from(path));
+    let mut existing = path.as_path();
+    let mut rest = vec![];
+    let mut resolved = loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            break canonical;
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(component)) => {
+                rest.push(component);
+                existing = parent;
+            }
+            _ => break PathBuf::new(),
+        }
+    };
+    for component in rest.into_iter().rev() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::process;
+
+    use super::*;
+
+    /// A directory of the system's temporary directory, removed when
+    /// dropped, holding an `allowed` and a `secret` directory.
+    struct Sandbox(PathBuf);
+
+    impl Sandbox {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("lumi-{}-{}", name, process::id()));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(root.join("allowed")).unwrap();
+            fs::create_dir_all(root.join("allowed-too")).unwrap();
+            fs::create_dir_all(root.join("secret")).unwrap();
+            fs::write(root.join("allowed/file.txt"), "").unwrap();
+            fs::write(root.join("secret/file.txt"), "").unwrap();
+            Self(root)
+        }
+
+        fn path(&self, path: &str) -> String {
+            self.0.join(path).to_string_lossy().into_owned()
+        }
+
+        fn read(&self) -> Permissions {
+            Permissions {
+                read: Grant::Only(vec![self.path("allowed")]),
+                ..Permissions::default()
+            }
+        }
+    }
+
+    impl Drop for Sandbox {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn everything_is_denied_by_default() {
+        let permissions = Permissions::default();
+        assert!(!permissions.allows(Permission::Read, "file.txt"));
+        assert!(!permissions.allows(Permission::Write, "file.txt"));
+        assert!(!permissions.allows(Permission::Env, "HOME"));
+    }
+
+    #[test]
+    fn all_grants_everything() {
+        let permissions = Permissions::all();
+        assert!(permissions.allows(Permission::Read, "/etc/passwd"));
+        assert!(permissions.allows(Permission::Write, "file.txt"));
+        assert!(permissions.allows(Permission::Env, "HOME"));
+    }
+
+    #[test]
+    fn env_is_granted_by_name() {
+        let permissions = Permissions {
+            env: Grant::Only(vec!["HOME".to_owned()]),
+            ..Permissions::default()
+        };
+        assert!(permissions.allows(Permission::Env, "HOME"));
+        assert!(!permissions.allows(Permission::Env, "HOMEPATH"));
+        assert!(!permissions.allows(Permission::Read, "HOME"));
+    }
+
+    #[test]
+    fn paths_inside_allowed_directories() {
+        let sandbox = Sandbox::new("inside");
+        let permissions = sandbox.read();
+        assert!(permissions.allows(Permission::Read, &sandbox.path("allowed")));
+        assert!(permissions.allows(Permission::Read, &sandbox.path("allowed/file.txt")));
+        assert!(permissions.allows(Permission::Read, &sandbox.path("allowed/new.txt")));
+        assert!(!permissions.allows(Permission::Read, &sandbox.path("allowed-too/file.txt")));
+        assert!(!permissions.allows(Permission::Read, &sandbox.path("secret/file.txt")));
+        assert!(!permissions.allows(Permission::Write, &sandbox.path("allowed/file.txt")));
+    }
+
+    #[test]
+    fn parent_components_are_resolved() {
+        let sandbox = Sandbox::new("parents");
+        let permissions = sandbox.read();
+        assert!(!permissions.allows(
+            Permission::Read,
+            &sandbox.path("allowed/../secret/file.txt")
+        ));
+        assert!(permissions.allows(Permission::Read, &sandbox.path("allowed/new/../file.txt")));
+        assert!(permissions.allows(Permission::Read, &sandbox.path("allowed/new/./other.txt")));
+        assert!(!permissions.allows(
+            Permission::Read,
+            &sandbox.path("allowed/new/../../secret/new.txt")
+        ));
+        assert!(!permissions.allows(
+            Permission::Read,
+            &sandbox.path("allowed/new/../../../new.txt")
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_leading_out_of_allowed_directories() {
+        let sandbox = Sandbox::new("links");
+        std::os::unix::fs::symlink(sandbox.0.join("secret"), sandbox.0.join("allowed/link"))
+            .unwrap();
+        std::os::unix::fs::symlink(sandbox.0.join("allowed"), sandbox.0.join("inside")).unwrap();
+        let permissions = sandbox.read();
+        assert!(!permissions.allows(Permission::Read, &sandbox.path("allowed/link")));
+        assert!(!permissions.allows(Permission::Read, &sandbox.path("allowed/link/file.txt")));
+        assert!(!permissions.allows(Permission::Read, &sandbox.path("allowed/link/new.txt")));
+        assert!(permissions.allows(Permission::Read, &sandbox.path("inside/file.txt")));
+        assert!(permissions.allows(Permission::Read, &sandbox.path("inside/new.txt")));
+    }
+
+    #[test]
+    fn resolve_keeps_missing_components() {
+        let sandbox = Sandbox::new("resolve");
+        let allowed = Path::new(&sandbox.path("allowed")).canonicalize().unwrap();
+        assert_eq!(
+            resolve(&sandbox.path("allowed/a/b/../c")),
+            allowed.join("a/c")
+        );
+        assert_eq!(resolve(&sandbox.path("allowed/a/./b")), allowed.join("a/b"));
+    }
+}
@@ -1,9 +1,12 @@
 use std::fmt::Display;
+use std::io;
+use std::time::Duration;
 
 use colored::Colorize;
 use lumi_lxr::span::Span;
 
-use crate::stack_trace::StackTrace;
+use crate::permissions::Permission;
+use crate::stack_trace::{lines, omitted, repeated, StackTrace, TraceLine};
 
 pub enum RuntimeError {
     Custom {
@@ -35,10 +38,56 @@ pub enum RuntimeError {
         span: Span,
         stack_trace: StackTrace,
     },
+    UnwrapNil {
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    Thrown {
+        message: String,
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    StackOverflow {
+        max_call_depth: usize,
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    StepLimit {
+        max_steps: usize,
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    Timeout {
+        timeout: Duration,
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    HeapLimit {
+        max_heap: usize,
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    PermissionDenied {
+        permission: Permission,
+        target: String,
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    AssertionFailed {
+        reason: Option<String>,
+        span: Span,
+        stack_trace: StackTrace,
+    },
+    Io {
+        action: String,
+        error: io::Error,
+        span: Span,
+        stack_trace: StackTrace,
+    },
 }
 
 impl RuntimeError {
-    fn message(&self) -> String {
+    pub(crate) fn message(&self) -> String {
         match self {
             Self::Custom { message, .. } => message.clone(),
             Self::SymbolNotFound { symbol_name, .. } => {
@@ -52,17 +101,48 @@ impl RuntimeError {
                 "cannot read property \"{}\" of \"{}\"",
                 property_name, class_name
             ),
-            Self::InvalidBinaryOperands { .. } => format!("invalid operands to binary expression",),
+            Self::InvalidBinaryOperands { .. } => {
+                "invalid operands to binary expression".to_owned()
+            }
             Self::SymbolNotCallable { symbol_name, .. } => {
                 format!("symbol \"{}\" is not a function", symbol_name)
             }
-            Self::InvalidInstantiation { .. } => {
-                format!("only classes can be instantiated")
+            Self::InvalidInstantiation { .. } => "only classes can be instantiated".to_owned(),
+            Self::UnwrapNil { .. } => "unwrapped a nil value".to_owned(),
+            Self::Thrown { message, .. } => format!("uncaught error: {}", message),
+            Self::StackOverflow { max_call_depth, .. } => format!(
+                "stack overflow, the maximum call depth of {} was exceeded",
+                max_call_depth
+            ),
+            Self::StepLimit { max_steps, .. } => {
+                format!(
+                    "the limit of {} executed instructions was exceeded",
+                    max_steps
+                )
             }
+            Self::Timeout { timeout, .. } => format!(
+                "the time limit of {} milliseconds was exceeded",
+                timeout.as_millis()
+            ),
+            Self::HeapLimit { max_heap, .. } => {
+                format!("the limit of {} objects in memory was exceeded", max_heap)
+            }
+            Self::PermissionDenied {
+                permission, target, ..
+            } => format!(
+                "{} access to \"{}\" was denied, run again with --allow-{}",
+                permission, target, permission
+            ),
+            Self::AssertionFailed { reason: None, .. } => "assertion failed".to_owned(),
+            Self::AssertionFailed {
+                reason: Some(reason),
+                ..
+            } => format!("assertion failed, {}", reason),
+            Self::Io { action, error, .. } => format!("cannot {}: {}", action, error),
         }
     }
 
-    fn span(&self) -> Span {
+    pub(crate) fn span(&self) -> Span {
         match self {
             Self::Custom { span, .. } => span.clone(),
             Self::SymbolNotFound { span, .. } => span.clone(),
@@ -70,10 +150,19 @@ impl RuntimeError {
             Self::InvalidBinaryOperands { span, .. } => span.clone(),
             Self::SymbolNotCallable { span, .. } => span.clone(),
             Self::InvalidInstantiation { span, .. } => span.clone(),
+            Self::UnwrapNil { span, .. } => span.clone(),
+            Self::Thrown { span, .. } => span.clone(),
+            Self::StackOverflow { span, .. } => span.clone(),
+            Self::StepLimit { span, .. } => span.clone(),
+            Self::Timeout { span, .. } => span.clone(),
+            Self::HeapLimit { span, .. } => span.clone(),
+            Self::PermissionDenied { span, .. } => span.clone(),
+            Self::AssertionFailed { span, .. } => span.clone(),
+            Self::Io { span, .. } => span.clone(),
         }
     }
 
-    fn stack_trace(&self) -> &StackTrace {
+    pub(crate) fn stack_trace(&self) -> &StackTrace {
         match self {
             Self::Custom { stack_trace, .. } => stack_trace,
             Self::SymbolNotFound { stack_trace, .. } => stack_trace,
@@ -81,6 +170,15 @@ impl RuntimeError {
             Self::InvalidBinaryOperands { stack_trace, .. } => stack_trace,
             Self::SymbolNotCallable { stack_trace, .. } => stack_trace,
             Self::InvalidInstantiation { stack_trace, .. } => stack_trace,
+            Self::UnwrapNil { stack_trace, .. } => stack_trace,
+            Self::Thrown { stack_trace, .. } => stack_trace,
+            Self::StackOverflow { stack_trace, .. } => stack_trace,
+            Self::StepLimit { stack_trace, .. } => stack_trace,
+            Self::Timeout { stack_trace, .. } => stack_trace,
+            Self::HeapLimit { stack_trace, .. } => stack_trace,
+            Self::PermissionDenied { stack_trace, .. } => stack_trace,
+            Self::AssertionFailed { stack_trace, .. } => stack_trace,
+            Self::Io { stack_trace, .. } => stack_trace,
         }
     }
 }
@@ -112,51 +210,43 @@ impl Display for RuntimeError {
             " ",
             "|".blue().bold(),
             " ".repeat(column),
-            "^".repeat(self.span().end().column() - self.span().start().column())
-                .red()
-                .bold(),
+            "^".repeat(
+                self.span()
+                    .end()
+                    .column()
+                    .saturating_sub(self.span().start().column())
+            )
+            .red()
+            .bold(),
         );
-        let mut prev_span = self.span().clone();
-        for trace in self.stack_trace().iter().rev() {
-            let line = prev_span.start().line();
-            let column = prev_span.start().column();
-            let function_and_file = format!(
-                "{}:{}:{}",
-                prev_span.source_code().file_path(),
-                line,
-                column
-            );
-            if let Some(function) = trace.function() {
-                if let Some(class_name) = function.class_name() {
-                    output.push_str(&format!(
+        for line in lines(&self.span(), self.stack_trace()) {
+            match line {
+                TraceLine::Call(Some(function), location) => match function.class_name() {
+                    Some(class_name) => output.push_str(&format!(
                         "\n{} {}.{} {}",
                         "at".black(),
                         class_name,
-                        function.name().underline(),
-                        function_and_file.black()
-                    ))
-                } else {
-                    output.push_str(&format!(
+                        function.name().as_str().underline(),
+                        location.black()
+                    )),
+                    None => output.push_str(&format!(
                         "\n{} {} {}",
                         "at".black(),
-                        function.name().underline(),
-                        function_and_file.black()
-                    ));
+                        function.name().as_str().underline(),
+                        location.black()
+                    )),
+                },
+                TraceLine::Call(None, location) => {
+                    output.push_str(&format!("\n{} {}", "at".black(), location.black()))
+                }
+                TraceLine::Repeated(count) => {
+                    output.push_str(&format!("\n{}", repeated(count).black()))
+                }
+                TraceLine::Omitted(count) => {
+                    output.push_str(&format!("\n{}", omitted(count).black()))
                 }
-            } else {
-                output.push_str(&format!("\n{} {}", "at".black(), function_and_file.black()));
             }
-            prev_span = trace.span();
         }
-        let line = prev_span.start().line();
-        let column = prev_span.start().column();
-        let function_and_file = format!(
-            "{}:{}:{}",
-            prev_span.source_code().file_path(),
-            line,
-            column
-        );
-        output.push_str(&format!("\n{} {}", "at".black(), function_and_file.black()));
         write!(f, "{}", output)
     }
 }
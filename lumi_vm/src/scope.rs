@@ -1,88 +1,184 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-#[derive(Debug)]
+use lumi_lxr::symbol::Symbol;
+
+static NEXT_SCOPE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Bumped whenever a method is declared in any scope, which makes the
+/// method table ids cached by the scopes stale.
+static METHODS_VERSION: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone)]
 struct InnerScope {
-    symbols: HashMap<String, usize>,
-    methods: HashMap<(usize, String), usize>,
+    symbols: HashMap<Symbol, usize>,
+    methods: HashMap<(usize, Symbol), usize>,
+    statics: HashMap<(usize, Symbol), usize>,
 }
 
+/// The symbols, methods and statics declared in a scope at some point.
+#[derive(Debug, Clone)]
+pub(crate) struct Bindings(InnerScope);
+
 #[derive(Debug, Clone)]
 pub(crate) struct Scope {
+    id: usize,
     pub(crate) parent: Option<Rc<Scope>>,
     inner: Rc<RefCell<InnerScope>>,
+    /// The method table id along with the methods version it was found
+    /// with, so that dispatching doesn't walk the parents every time.
+    method_table: Cell<Option<(usize, usize)>>,
 }
 
 impl Scope {
     pub(crate) fn root() -> Self {
         Self {
+            id: NEXT_SCOPE_ID.fetch_add(1, Ordering::Relaxed),
             parent: None,
             inner: Rc::new(RefCell::new(InnerScope {
                 symbols: HashMap::new(),
                 methods: HashMap::new(),
+                statics: HashMap::new(),
             })),
+            method_table: Cell::new(None),
         }
     }
 
     pub(crate) fn new(parent: Rc<Scope>) -> Self {
         Self {
+            id: NEXT_SCOPE_ID.fetch_add(1, Ordering::Relaxed),
             parent: Some(parent),
             inner: Rc::new(RefCell::new(InnerScope {
                 symbols: HashMap::new(),
                 methods: HashMap::new(),
+                statics: HashMap::new(),
             })),
+            method_table: Cell::new(None),
         }
     }
 
-    pub(crate) fn set_symbol(&self, ident: &str, object: usize) {
-        self.inner
-            .borrow_mut()
-            .symbols
-            .insert(ident.to_owned(), object);
+    pub(crate) fn set_symbol(&self, ident: Symbol, object: usize) {
+        self.inner.borrow_mut().symbols.insert(ident, object);
     }
 
-    pub(crate) fn assign_symbol(&self, ident: &str, object: usize) -> Option<()> {
-        if let Some(symbol) = self.inner.borrow_mut().symbols.get_mut(ident) {
+    pub(crate) fn assign_symbol(&self, ident: Symbol, object: usize) -> Option<()> {
+        if let Some(symbol) = self.inner.borrow_mut().symbols.get_mut(&ident) {
             *symbol = object;
             Some(())
+        } else if let Some(parent) = &self.parent {
+            parent.assign_symbol(ident, object)
         } else {
-            if let Some(parent) = &self.parent {
-                parent.assign_symbol(ident, object)
-            } else {
-                None
-            }
+            None
         }
     }
 
-    pub(crate) fn symbol(&self, ident: &str) -> Option<usize> {
-        if let Some(value) = self.inner.borrow().symbols.get(ident) {
+    pub(crate) fn symbol(&self, ident: Symbol) -> Option<usize> {
+        if let Some(value) = self.inner.borrow().symbols.get(&ident) {
             Some(*value)
+        } else if let Some(parent) = &self.parent {
+            parent.symbol(ident)
         } else {
-            if let Some(parent) = &self.parent {
-                parent.symbol(ident)
-            } else {
-                None
-            }
+            None
         }
     }
 
-    pub(crate) fn set_method(&self, class: usize, ident: &str, method: usize) {
+    pub(crate) fn set_method(&self, class: usize, ident: Symbol, method: usize) {
+        METHODS_VERSION.fetch_add(1, Ordering::Relaxed);
         self.inner
             .borrow_mut()
             .methods
-            .insert((class, ident.to_owned()), method);
+            .insert((class, ident), method);
     }
 
-    pub(crate) fn method(&self, class: usize, ident: &str) -> Option<usize> {
-        if let Some(value) = self.inner.borrow().methods.get(&(class, ident.to_owned())) {
-            Some(value.clone())
+    pub(crate) fn method(&self, class: usize, ident: Symbol) -> Option<usize> {
+        if let Some(value) = self.inner.borrow().methods.get(&(class, ident)) {
+            Some(*value)
+        } else if let Some(parent) = &self.parent {
+            parent.method(class, ident)
         } else {
-            if let Some(parent) = &self.parent {
-                parent.method(class, ident)
-            } else {
-                None
+            None
+        }
+    }
+
+    pub(crate) fn set_static(&self, class: usize, ident: Symbol, method: usize) {
+        self.inner
+            .borrow_mut()
+            .statics
+            .insert((class, ident), method);
+    }
+
+    pub(crate) fn static_method(&self, class: usize, ident: Symbol) -> Option<usize> {
+        if let Some(value) = self.inner.borrow().statics.get(&(class, ident)) {
+            Some(*value)
+        } else if let Some(parent) = &self.parent {
+            parent.static_method(class, ident)
+        } else {
+            None
+        }
+    }
+
+    /// Copies what is declared in this scope, without its parents.
+    pub(crate) fn bindings(&self) -> Bindings {
+        Bindings(self.inner.borrow().clone())
+    }
+
+    /// Goes back to what was declared in this scope when `bindings` were
+    /// copied.
+    pub(crate) fn restore(&self, bindings: &Bindings) {
+        METHODS_VERSION.fetch_add(1, Ordering::Relaxed);
+        *self.inner.borrow_mut() = bindings.0.clone();
+    }
+
+    /// Gets the id of the nearest scope, starting from this one, that
+    /// declares methods.
+    ///
+    /// Two scopes with the same method table id see exactly the same
+    /// methods, which makes this id a valid inline cache key even with
+    /// scoped `impl` blocks.
+    pub(crate) fn method_table_id(&self) -> usize {
+        let version = METHODS_VERSION.load(Ordering::Relaxed);
+        if let Some((cached_version, id)) = self.method_table.get() {
+            if cached_version == version {
+                return id;
             }
         }
+        let id = if !self.inner.borrow().methods.is_empty() {
+            self.id
+        } else if let Some(parent) = &self.parent {
+            parent.method_table_id()
+        } else {
+            self.id
+        };
+        self.method_table.set(Some((version, id)));
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lumi_lxr::symbol::sym;
+
+    use super::*;
+
+    #[test]
+    fn method_table_id_is_the_nearest_scope_with_methods() {
+        let root = Rc::new(Scope::root());
+        root.set_method(1, sym::add, 2);
+        let middle = Rc::new(Scope::new(Rc::clone(&root)));
+        let child = Scope::new(Rc::clone(&middle));
+        assert_eq!(child.method_table_id(), root.id);
+        assert_eq!(middle.method_table_id(), root.id);
+    }
+
+    #[test]
+    fn method_table_id_follows_methods_declared_later() {
+        let root = Rc::new(Scope::root());
+        let middle = Rc::new(Scope::new(Rc::clone(&root)));
+        let child = Scope::new(Rc::clone(&middle));
+        assert_eq!(child.method_table_id(), root.id);
+        middle.set_method(1, sym::add, 2);
+        assert_eq!(child.method_table_id(), middle.id);
     }
 }
@@ -1,25 +1,23 @@
 use lumi_lxr::span::Span;
+use lumi_lxr::symbol::Symbol;
 
 #[derive(Debug, Clone)]
 pub(crate) struct TraceFunction {
-    name: String,
-    class_name: Option<String>,
+    name: Symbol,
+    class_name: Option<Symbol>,
 }
 
 impl TraceFunction {
-    pub(crate) fn new(name: &str, class_name: Option<&str>) -> Self {
-        Self {
-            name: name.to_owned(),
-            class_name: class_name.map(|name| name.to_owned()),
-        }
+    pub(crate) fn new(name: Symbol, class_name: Option<Symbol>) -> Self {
+        Self { name, class_name }
     }
 
-    pub(crate) fn name(&self) -> String {
-        self.name.clone()
+    pub(crate) fn name(&self) -> Symbol {
+        self.name
     }
 
-    pub(crate) fn class_name(&self) -> Option<String> {
-        self.class_name.clone()
+    pub(crate) fn class_name(&self) -> Option<Symbol> {
+        self.class_name
     }
 }
 
@@ -44,3 +42,175 @@ impl Trace {
 }
 
 pub type StackTrace = Vec<Trace>;
+
+/// The number of distinct calls shown at each end of a long stack trace.
+const TRACE_EDGE: usize = 10;
+
+/// A line of the report of a stack trace.
+pub(crate) enum TraceLine<'trace> {
+    /// A call along with the location it was executing.
+    Call(Option<&'trace TraceFunction>, String),
+    /// The number of times the previous call repeats right after itself, as
+    /// in a recursion.
+    Repeated(usize),
+    /// The number of calls left out in the middle of a long stack trace.
+    Omitted(usize),
+}
+
+/// Lists the calls of a stack trace, innermost first, with the location
+/// each one was executing when an error was raised at `span`. The last
+/// location is the top-level one, which isn't inside any function.
+///
+/// Consecutive identical calls are collapsed, and only the first and last
+/// [`TRACE_EDGE`] calls are kept, so that a stack overflow stays readable.
+pub(crate) fn lines<'trace>(
+    span: &Span,
+    stack_trace: &'trace StackTrace,
+) -> Vec<TraceLine<'trace>> {
+    let mut calls: Vec<(Option<&TraceFunction>, String, usize)> = vec![];
+    let mut prev_span = span.clone();
+    let functions = stack_trace
+        .iter()
+        .rev()
+        .map(|trace| {
+            let call = (trace.function(), location(&prev_span));
+            prev_span = trace.span();
+            call
+        })
+        .collect::<Vec<_>>();
+    let top_level = (None, location(&prev_span));
+    for (function, location) in functions.into_iter().chain([top_level]) {
+        match calls.last_mut() {
+            Some((prev_function, prev_location, count))
+                if same_function(*prev_function, function) && *prev_location == location =>
+            {
+                *count += 1;
+            }
+            _ => calls.push((function, location, 1)),
+        }
+    }
+    let omitted = calls.len().saturating_sub(2 * TRACE_EDGE);
+    let mut lines = vec![];
+    for (index, (function, location, count)) in calls.into_iter().enumerate() {
+        if omitted > 0 && (TRACE_EDGE..TRACE_EDGE + omitted).contains(&index) {
+            match lines.last_mut() {
+                Some(TraceLine::Omitted(calls)) => *calls += count,
+                _ => lines.push(TraceLine::Omitted(count)),
+            }
+            continue;
+        }
+        lines.push(TraceLine::Call(function, location));
+        if count > 1 {
+            lines.push(TraceLine::Repeated(count - 1));
+        }
+    }
+    lines
+}
+
+/// Renders a stack trace as plain text, one `at` line per call, to store it
+/// in the `trace` field of an `Error`.
+pub(crate) fn render(span: &Span, stack_trace: &StackTrace) -> String {
+    lines(span, stack_trace)
+        .into_iter()
+        .map(|line| match line {
+            TraceLine::Call(Some(function), location) => match function.class_name() {
+                Some(class_name) => format!("at {}.{} {}", class_name, function.name(), location),
+                None => format!("at {} {}", function.name(), location),
+            },
+            TraceLine::Call(None, location) => format!("at {}", location),
+            TraceLine::Repeated(count) => repeated(count),
+            TraceLine::Omitted(count) => omitted(count),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(crate) fn repeated(count: usize) -> String {
+    match count {
+        1 => "... the call above is repeated 1 more time".to_owned(),
+        _ => format!("... the call above is repeated {} more times", count),
+    }
+}
+
+pub(crate) fn omitted(count: usize) -> String {
+    match count {
+        1 => "... 1 call omitted".to_owned(),
+        _ => format!("... {} calls omitted", count),
+    }
+}
+
+fn same_function(a: Option<&TraceFunction>, b: Option<&TraceFunction>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.name() == b.name() && a.class_name() == b.class_name(),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn location(span: &Span) -> String {
+    format!(
+        "{}:{}:{}",
+        span.source_code().file_path(),
+        span.start().line(),
+        span.start().column()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::vm;
+
+    use super::*;
+
+    /// Runs `code` with at most `max_call_depth` nested calls, returning the
+    /// lines of the trace of the error it raises.
+    fn trace(code: &str, max_call_depth: usize) -> Vec<String> {
+        let mut vm = vm(code).with_max_call_depth(max_call_depth);
+        let error = vm.run().expect_err("the script should fail");
+        render(&error.span(), error.stack_trace())
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn recursion_is_collapsed() {
+        let code = "fun f() {\n    f();\n}\nf();";
+        assert_eq!(
+            trace(code, 50),
+            [
+                "at f test.ls:2:5",
+                "... the call above is repeated 49 more times",
+                "at test.ls:4:1",
+            ]
+        );
+    }
+
+    #[test]
+    fn calls_are_listed_innermost_first() {
+        let code = "fun f() {\n    g();\n}\nfun g() {\n    assert(false);\n}\nf();";
+        assert_eq!(
+            trace(code, 10),
+            ["at g test.ls:5:5", "at f test.ls:2:5", "at test.ls:7:1",]
+        );
+    }
+
+    #[test]
+    fn middle_calls_are_omitted() {
+        let code = "fun f() {\n    g();\n}\nfun g() {\n    f();\n}\nf();";
+        let lines = trace(code, 50);
+        assert_eq!(lines.len(), 2 * TRACE_EDGE + 1);
+        assert_eq!(lines[0], "at g test.ls:5:5");
+        assert_eq!(lines[1], "at f test.ls:2:5");
+        assert_eq!(lines[TRACE_EDGE], omitted(50 + 1 - 2 * TRACE_EDGE));
+        assert_eq!(lines[2 * TRACE_EDGE], "at test.ls:7:1");
+    }
+
+    #[test]
+    fn counts_are_pluralized() {
+        assert_eq!(repeated(1), "... the call above is repeated 1 more time");
+        assert_eq!(repeated(2), "... the call above is repeated 2 more times");
+        assert_eq!(omitted(1), "... 1 call omitted");
+        assert_eq!(omitted(3), "... 3 calls omitted");
+    }
+}
@@ -0,0 +1,56 @@
+//! Helpers shared by the tests of the VM.
+
+use lumi_lxr::lexer::Lexer;
+use lumi_lxr::source_code::SourceCode;
+use lumi_lxr::symbol::Symbol;
+use lumi_psr::ast::Ast;
+use lumi_psr::parser::ParseStream;
+
+use crate::chunk::Chunk;
+use crate::emitter::BytecodeEmitter;
+use crate::runtime_error::RuntimeError;
+use crate::vm::Vm;
+
+const PRELUDE: [(&str, &str); 2] = [
+    ("core.ls", include_str!("../../core.ls")),
+    ("std.ls", include_str!("../../std.ls")),
+];
+
+/// Compiles the prelude followed by `code` into a new VM.
+pub fn vm(code: &str) -> Vm {
+    let mut chunk = Chunk::new();
+    for (path, code) in PRELUDE.into_iter().chain([("test.ls", code)]) {
+        let tokens = Lexer::new(SourceCode::new(path, code)).tokens().unwrap();
+        let ast = ParseStream::new(tokens)
+            .parse::<Ast>()
+            .unwrap_or_else(|error| panic!("{}", error.message()));
+        if let Err(errors) = BytecodeEmitter::emit(&ast, &mut chunk) {
+            panic!("{}", errors[0].message());
+        }
+    }
+    Vm::new(chunk)
+}
+
+/// Compiles and runs `code` after the prelude.
+pub fn run(code: &str) -> (Vm, Result<(), RuntimeError>) {
+    let mut vm = vm(code);
+    let result = vm.run();
+    (vm, result)
+}
+
+/// Compiles and runs `code` after the prelude, failing the test when the
+/// script raises an error.
+pub fn run_ok(code: &str) -> Vm {
+    let (vm, result) = run(code);
+    if let Err(error) = result {
+        panic!("{}", error.message());
+    }
+    vm
+}
+
+/// Gets the text `println` writes for the variable `name` of the top level.
+pub fn global(vm: &Vm, name: &str) -> String {
+    vm.global(Symbol::intern(name))
+        .map(|object_id| vm.display(object_id))
+        .unwrap_or_else(|| panic!("variable {} was not found", name))
+}
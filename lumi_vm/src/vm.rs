@@ -1,15 +1,30 @@
 use std::{collections::HashMap, rc::Rc, time::Instant};
 
+use lumi_lxr::{
+    span::Span,
+    symbol::{sym, Symbol},
+};
+
 use crate::{
     call_frame::{CallFrame, CallStack},
     chunk::{Bytecode, Chunk, Constant},
+    inline_cache::InlineCache,
+    limits::VmLimits,
     memory::Memory,
-    object::{Class, Function, InnerFunction, Instance, NativeFunction, Object, Primitive},
+    native::Natives,
+    object::{Class, Function, InnerFunction, Instance, Object, Primitive, Trait, STR_CLASS},
+    permissions::{Permission, Permissions},
     runtime_error::RuntimeError,
-    scope::Scope,
-    stack_trace::{StackTrace, Trace, TraceFunction},
+    scope::{Bindings, Scope},
+    stack_trace::{render, StackTrace, Trace, TraceFunction},
 };
 
+/// The default maximum number of nested calls.
+pub const MAX_CALL_DEPTH: usize = 1024;
+
+/// The number of instructions executed between two checks of the timeout.
+const TIMEOUT_INTERVAL: usize = 1024;
+
 pub struct Vm {
     chunk: Chunk,
     memory: Memory,
@@ -18,13 +33,137 @@ pub struct Vm {
     constant_stack: Vec<Constant>,
     stack_trace: StackTrace,
     scope: Rc<Scope>,
+    inline_cache: InlineCache,
+    natives: Natives,
+    handlers: Vec<Handler>,
+    max_call_depth: usize,
+    limits: VmLimits,
+    steps: usize,
+    permissions: Permissions,
+    args: Vec<String>,
+    exit_code: Option<i32>,
+}
+
+/// The memory and the top-level variables of a [`Vm`] at some point, which
+/// [`Vm::restore`] goes back to.
+pub struct Snapshot {
+    memory: Memory,
+    scope: Rc<Scope>,
+    bindings: Bindings,
+}
+
+/// A `try` block being executed, with the state to restore when an error
+/// raised inside it is caught.
+struct Handler {
+    catch_ptr: usize,
+    depth: usize,
+    scope: Rc<Scope>,
+    object_stack_len: usize,
+    constant_stack_len: usize,
 }
 
 impl Vm {
-    fn get_object(&self, object_id: usize) -> &Object {
+    pub(crate) fn get_object(&self, object_id: usize) -> &Object {
         self.memory.get(object_id)
     }
 
+    /// Gets the text `println` writes for an object.
+    pub(crate) fn display(&self, object_id: usize) -> String {
+        match self.get_object(object_id) {
+            Object::Class(class) => format!("<class {}>", class.name()),
+            Object::Primitive(primitive) if primitive.class() == 0 => "nil".to_owned(),
+            Object::Primitive(primitive) => format!("{:?}", primitive.value()),
+            Object::Function(_) => "<function>".to_owned(),
+            Object::Str(value) => value.clone(),
+            Object::Trait(r#trait) => format!("<trait {}>", r#trait.name()),
+            Object::Instance(instance) => match self.get_object(instance.class_id()) {
+                Object::Class(class) => format!("<instance {}>", class.name()),
+                _ => "<instance>".to_owned(),
+            },
+        }
+    }
+
+    pub(crate) fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Stops the script once the current instruction is done, without
+    /// running the `finally` blocks left.
+    pub(crate) fn exit(&mut self, code: i32) {
+        self.exit_code = Some(code);
+    }
+
+    /// Creates a `List` of the given objects, for the natives that return
+    /// several values.
+    pub(crate) fn list(&mut self, objects: Vec<Object>) -> Result<Object, RuntimeError> {
+        let (list, node) = (self.class(sym::List)?, self.class(sym::ListNode)?);
+        let length = objects.len();
+        let mut next = self.memory.alloc(Object::Primitive(Primitive::new(0, 0.0)));
+        for object in objects.into_iter().rev() {
+            let value = self.memory.alloc(object);
+            let fields = HashMap::from([(sym::value, value), (sym::next, next)]);
+            next = self
+                .memory
+                .alloc(Object::Instance(Instance::new(node, fields)));
+        }
+        let length = self
+            .memory
+            .alloc(Object::Primitive(Primitive::new(2, length as f64)));
+        let fields = HashMap::from([(sym::head, next), (sym::length, length)]);
+        Ok(Object::Instance(Instance::new(list, fields)))
+    }
+
+    /// Finds a class declared by `core.ls`.
+    fn class(&self, name: Symbol) -> Result<usize, RuntimeError> {
+        self.scope
+            .symbol(name)
+            .ok_or_else(|| RuntimeError::SymbolNotFound {
+                symbol_name: name.to_string(),
+                span: self.span().clone(),
+                stack_trace: self.stack_trace.clone(),
+            })
+    }
+
+    /// Stores a new object in a field of an instance, for the natives that
+    /// keep their state in `this`.
+    pub(crate) fn set_field(&mut self, instance_id: usize, key: Symbol, object: Object) {
+        let object_id = self.memory.alloc(object);
+        if let Object::Instance(instance) = self.memory.get_mut(instance_id) {
+            instance.set_field(key, object_id);
+        }
+    }
+
+    /// Checks that natives are granted a permission before they act on
+    /// `target`, failing at the call being executed otherwise.
+    pub(crate) fn permit(&self, permission: Permission, target: &str) -> Result<(), RuntimeError> {
+        if self.permissions.allows(permission, target) {
+            Ok(())
+        } else {
+            Err(RuntimeError::PermissionDenied {
+                permission,
+                target: target.to_owned(),
+                span: self.span().clone(),
+                stack_trace: self.stack_trace.clone(),
+            })
+        }
+    }
+
+    /// Gets the span of the current instruction.
+    pub(crate) fn span(&self) -> &Span {
+        self.chunk().span(self.frame().instructions_ptr)
+    }
+
+    /// Finds a variable of the current scope, for the tests to check the
+    /// state a script left.
+    #[cfg(test)]
+    pub(crate) fn global(&self, name: Symbol) -> Option<usize> {
+        self.scope.symbol(name)
+    }
+
+    pub(crate) fn stack_trace(&self) -> &StackTrace {
+        &self.stack_trace
+    }
+
     fn scope(&self) -> &Rc<Scope> {
         &self.scope
     }
@@ -58,7 +197,8 @@ impl Vm {
 impl Vm {
     pub fn new(chunk: Chunk) -> Self {
         let root_call_frame = CallFrame::new(None, HashMap::new());
-        Self {
+        let inline_cache = InlineCache::new(chunk.len());
+        let mut vm = Self {
             chunk,
             memory: Memory::new(),
             call_stack: CallStack::new(root_call_frame),
@@ -66,73 +206,212 @@ impl Vm {
             constant_stack: vec![],
             stack_trace: StackTrace::new(),
             scope: Rc::new(Scope::root()),
+            inline_cache,
+            natives: Natives::new(),
+            handlers: vec![],
+            max_call_depth: MAX_CALL_DEPTH,
+            limits: VmLimits::default(),
+            steps: 0,
+            permissions: Permissions::default(),
+            args: vec![],
+            exit_code: None,
+        };
+        register(&mut vm);
+        vm
+    }
+
+    /// Sets the accesses to the system granted to the script, see
+    /// [`Permissions`].
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Sets the arguments the script was started with, read by `args()`.
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Gets the code the script asked to exit with by calling `exit()`, if
+    /// it did.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Sets the resources the script can use, see [`VmLimits`].
+    pub fn with_limits(mut self, limits: VmLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets how many calls can be nested before a stack overflow is
+    /// reported, [`MAX_CALL_DEPTH`] by default.
+    pub fn with_max_call_depth(mut self, max_call_depth: usize) -> Self {
+        self.max_call_depth = max_call_depth;
+        self
+    }
+
+    /// Saves the memory and the variables of the top level, for instance
+    /// once it ran, so that every test can start from there.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            memory: self.memory.clone(),
+            scope: Rc::clone(&self.scope),
+            bindings: self.scope.bindings(),
         }
     }
 
+    /// Undoes what was changed since the snapshot was taken, including the
+    /// objects created and the variables assigned.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.memory = snapshot.memory.clone();
+        self.scope = Rc::clone(&snapshot.scope);
+        self.scope.restore(&snapshot.bindings);
+        self.inline_cache.invalidate();
+    }
+
+    /// Gets the chunk being run, to append code to it that the next call to
+    /// [`Vm::run`] executes in the state the previous ones left.
+    pub fn chunk_mut(&mut self) -> &mut Chunk {
+        &mut self.chunk
+    }
+
+    /// Runs the chunk from where the previous run stopped. When the script
+    /// fails or exits, the VM goes back to the top level, at the end of the
+    /// chunk, so that it can run the code appended afterwards.
     pub fn run(&mut self) -> Result<(), RuntimeError> {
-        register(self);
+        self.inline_cache.grow(self.chunk.len());
+        self.exit_code = None;
+        self.steps = 0;
+        let scope = Rc::clone(&self.scope);
+        let result = self.execute();
+        if result.is_err() || self.exit_code.is_some() {
+            self.unwind(scope);
+        }
+        result
+    }
+
+    /// Drops the calls, values and `try` blocks left by a script that
+    /// stopped early, restoring the top-level `scope`.
+    fn unwind(&mut self, scope: Rc<Scope>) {
+        self.call_stack.truncate(0);
+        self.stack_trace.clear();
+        self.object_stack.clear();
+        self.constant_stack.clear();
+        self.handlers.clear();
+        self.scope = scope;
+        self.frame_mut().instructions_ptr = self.chunk.len();
+    }
+
+    fn execute(&mut self) -> Result<(), RuntimeError> {
+        let start = Instant::now();
         while let Some(instruction) = self.current_instruction() {
-            match instruction {
-                Bytecode::LoadConstant => op_load_constant(self)?,
-                Bytecode::ConvertConstant => op_convert_constant(self)?,
-                Bytecode::DeclareClass => op_declare_class(self)?,
-                Bytecode::Instantiate => op_instantiate(self)?,
-                Bytecode::PrintLn => op_println(self)?,
-                Bytecode::GetSymbol => op_get_symbol(self)?,
-                Bytecode::DeclareVariable => op_declare_var(self)?,
-                Bytecode::SetVariable => op_set_var(self)?,
-                Bytecode::Return => op_return(self)?,
-                Bytecode::BeginScope => op_begin_scope(self)?,
-                Bytecode::EndScope => op_end_scope(self)?,
-                Bytecode::SetProperty => op_set_property(self)?,
-                Bytecode::GetProperty => op_get_property(self)?,
-                Bytecode::DeclareFunction => op_declare_function(self)?,
-                Bytecode::CallFunction => op_call_function(self)?,
-                Bytecode::DeclareMethod => op_declare_method(self)?,
-                Bytecode::Add => op_add(self)?,
-                Bytecode::Subtract => op_sub(self)?,
-                Bytecode::Equals => op_eq(self)?,
-                Bytecode::Not => op_not(self)?,
-                Bytecode::JumpIfFalse => op_jump_if_false(self)?,
-                Bytecode::Pop => op_pop(self)?,
+            self.check_limits(start)?;
+            let result = match instruction {
+                Bytecode::LoadConstant => op_load_constant(self),
+                Bytecode::ConvertConstant => op_convert_constant(self),
+                Bytecode::DeclareClass => op_declare_class(self),
+                Bytecode::Instantiate => op_instantiate(self),
+                Bytecode::PrintLn => op_println(self),
+                Bytecode::GetSymbol => op_get_symbol(self),
+                Bytecode::DeclareVariable => op_declare_var(self),
+                Bytecode::SetVariable => op_set_var(self),
+                Bytecode::Return => op_return(self),
+                Bytecode::BeginScope => op_begin_scope(self),
+                Bytecode::EndScope => op_end_scope(self),
+                Bytecode::SetProperty => op_set_property(self),
+                Bytecode::GetProperty => op_get_property(self),
+                Bytecode::DeclareFunction => op_declare_function(self),
+                Bytecode::CallFunction => op_call_function(self),
+                Bytecode::DeclareMethod => op_declare_method(self),
+                Bytecode::DeclareStaticMethod => op_declare_static_method(self),
+                Bytecode::DeclareExternFunction => op_declare_extern_function(self),
+                Bytecode::DeclareExternMethod => op_declare_extern_method(self),
+                Bytecode::GetStatic => op_get_static(self),
+                Bytecode::DeclareTrait => op_declare_trait(self),
+                Bytecode::DeclareTraitMethod => op_declare_trait_method(self),
+                Bytecode::ImplementTrait => op_implement_trait(self),
+                Bytecode::Add => op_add(self),
+                Bytecode::Subtract => op_sub(self),
+                Bytecode::Multiply => op_mul(self),
+                Bytecode::Divide => op_div(self),
+                Bytecode::Remainder => op_rem(self),
+                Bytecode::Equals => op_eq(self),
+                Bytecode::Not => op_not(self),
+                Bytecode::JumpIfFalse => op_jump_if_false(self),
+                Bytecode::JumpIfFalseOrPop => op_jump_if_false_or_pop(self),
+                Bytecode::JumpIfTrueOrPop => op_jump_if_true_or_pop(self),
+                Bytecode::ToBool => op_to_bool(self),
+                Bytecode::JumpIfNil => op_jump_if_nil(self),
+                Bytecode::JumpIfNotNil => op_jump_if_not_nil(self),
+                Bytecode::Unwrap => op_unwrap(self),
+                Bytecode::Propagate => op_propagate(self),
+                Bytecode::Pop => op_pop(self),
+                Bytecode::Jump => op_jump(self),
+                Bytecode::PushHandler => op_push_handler(self),
+                Bytecode::PopHandler => op_pop_handler(self),
+                Bytecode::Throw => op_throw(self),
                 _ => panic!("Bytecode {:?} not implemented", instruction),
             };
-        }
-        if !self.object_stack.is_empty() {
-            println!("Stack not empty, {:?}", self.object_stack);
+            if let Err(error) = result {
+                catch(self, error)?;
+            }
+            if self.exit_code.is_some() {
+                return Ok(());
+            }
         }
         Ok(())
     }
 
-    fn register_native_function(
-        &mut self,
-        function_name: &str,
-        params: &[String],
-        function: NativeFunction,
-    ) {
-        let function_id = self.memory.alloc(Object::Function(Function::new(
-            function_name,
-            params,
-            None,
-            InnerFunction::Native { fun: function },
-        )));
-        self.scope.set_symbol(function_name, function_id);
+    /// Stops the script when it exceeds one of its limits, before the
+    /// current instruction is executed. These errors skip the `try` blocks,
+    /// so that scripts can't keep running past them.
+    fn check_limits(&mut self, start: Instant) -> Result<(), RuntimeError> {
+        self.steps += 1;
+        if let Some(max_steps) = self.limits.max_steps {
+            if self.steps > max_steps {
+                return Err(RuntimeError::StepLimit {
+                    max_steps,
+                    span: self.span().clone(),
+                    stack_trace: self.stack_trace.clone(),
+                });
+            }
+        }
+        // Reading the clock is slow compared to most instructions.
+        if let Some(timeout) = self.limits.timeout {
+            if self.steps.is_multiple_of(TIMEOUT_INTERVAL) && start.elapsed() > timeout {
+                return Err(RuntimeError::Timeout {
+                    timeout,
+                    span: self.span().clone(),
+                    stack_trace: self.stack_trace.clone(),
+                });
+            }
+        }
+        if let Some(max_heap) = self.limits.max_heap {
+            if self.memory.len() > max_heap {
+                return Err(RuntimeError::HeapLimit {
+                    max_heap,
+                    span: self.span().clone(),
+                    stack_trace: self.stack_trace.clone(),
+                });
+            }
+        }
+        Ok(())
     }
 
-    fn register_native_method(
-        &mut self,
-        class_id: usize,
-        method_name: &str,
-        params: &[String],
-        function: NativeFunction,
-    ) {
-        let method_id = self.memory.alloc(Object::Function(Function::new(
-            method_name,
-            params,
-            Some(class_id),
-            InnerFunction::Native { fun: function },
-        )));
-        self.scope.set_method(class_id, method_name, method_id);
+    /// Resolves the method `name` of the class `class_id` visible from the
+    /// current scope, using the inline cache of the current instruction.
+    fn method(&mut self, class_id: usize, name: Symbol) -> Option<usize> {
+        let index = self.frame().instructions_ptr;
+        let method_table_id = self.scope.method_table_id();
+        if let Some(method) = self.inline_cache.get(index, class_id, method_table_id) {
+            return Some(method);
+        }
+        let method = self.scope.method(class_id, name)?;
+        self.inline_cache
+            .set(index, class_id, method_table_id, method);
+        Some(method)
     }
 
     fn frame(&self) -> &CallFrame {
@@ -153,119 +432,14 @@ impl Vm {
 }
 
 fn register(vm: &mut Vm) {
-    let nil_ptr = vm.memory.alloc(Object::Class(Class::new("Nil")));
-    let bool_ptr = vm.memory.alloc(Object::Class(Class::new("Bool")));
-    let num_ptr = vm.memory.alloc(Object::Class(Class::new("Number")));
-    vm.scope.set_symbol("Nil", nil_ptr);
-    vm.scope.set_symbol("Bool", bool_ptr);
-    vm.scope.set_symbol("Num", num_ptr);
-    let start = Instant::now();
-    vm.register_native_function(
-        "clock",
-        &vec![],
-        Box::new(move |_, _| {
-            let end = Instant::now();
-            let diff = (end - start).as_millis();
-            Ok(Object::Primitive(Primitive::new(2, diff as f64)))
-        }),
-    );
-
-    vm.register_native_method(
-        2,
-        "add",
-        &vec!["other".to_owned()],
-        Box::new(|vm, params| {
-            let this = *params.get("this").unwrap();
-            let other = *params.get("other").unwrap();
-            let this = vm.memory.get(this);
-            let other = vm.memory.get(other);
-            if let (Object::Primitive(operand1), Object::Primitive(operand2)) = (this, other) {
-                Ok(Object::Primitive(Primitive::new(
-                    2,
-                    operand1.value() + operand2.value(),
-                )))
-            } else {
-                let index = vm.frame().instructions_ptr;
-                let span = vm.chunk().span(index);
-                Err(RuntimeError::InvalidBinaryOperands {
-                    span: span.clone(),
-                    stack_trace: vm.stack_trace.clone(),
-                })
-            }
-        }),
-    );
-
-    vm.register_native_method(
-        2,
-        "sub",
-        &vec!["other".to_owned()],
-        Box::new(|vm, params| {
-            let this = *params.get("this").unwrap();
-            let other = *params.get("other").unwrap();
-            let this = vm.memory.get(this);
-            let other = vm.memory.get(other);
-            if let (Object::Primitive(operand1), Object::Primitive(operand2)) = (this, other) {
-                Ok(Object::Primitive(Primitive::new(
-                    2,
-                    operand1.value() - operand2.value(),
-                )))
-            } else {
-                let index = vm.frame().instructions_ptr;
-                let span = vm.chunk().span(index);
-                Err(RuntimeError::InvalidBinaryOperands {
-                    span: span.clone(),
-                    stack_trace: vm.stack_trace.clone(),
-                })
-            }
-        }),
-    );
-
-    vm.register_native_method(
-        2,
-        "eq",
-        &["other".to_owned()],
-        Box::new(|vm, params| {
-            let this = *params.get("this").unwrap();
-            let other = *params.get("other").unwrap();
-            let this = vm.memory.get(this);
-            let other = vm.memory.get(other);
-            if let (Object::Primitive(operand1), Object::Primitive(operand2)) = (this, other) {
-                Ok(Object::Primitive(Primitive::new(
-                    1,
-                    if operand1.value() == operand2.value() {
-                        1.0
-                    } else {
-                        0.0
-                    },
-                )))
-            } else {
-                let index = vm.frame().instructions_ptr;
-                let span = vm.chunk().span(index);
-                Err(RuntimeError::InvalidBinaryOperands {
-                    span: span.clone(),
-                    stack_trace: vm.stack_trace.clone(),
-                })
-            }
-        }),
-    );
-
-    vm.register_native_method(
-        1,
-        "not",
-        &[],
-        Box::new(|vm, params| {
-            let this = *params.get("this").unwrap();
-            let this = vm.memory.get(this);
-            if let Object::Primitive(primitive) = this {
-                Ok(Object::Primitive(Primitive::new(
-                    1,
-                    if primitive.value() == 0.0 { 1.0 } else { 0.0 },
-                )))
-            } else {
-                unreachable!()
-            }
-        }),
-    );
+    let nil_ptr = vm.memory.alloc(Object::Class(Class::new(sym::Nil)));
+    let bool_ptr = vm.memory.alloc(Object::Class(Class::new(sym::Bool)));
+    let num_ptr = vm.memory.alloc(Object::Class(Class::new(sym::Num)));
+    let str_ptr = vm.memory.alloc(Object::Class(Class::new(sym::Str)));
+    vm.scope.set_symbol(sym::Nil, nil_ptr);
+    vm.scope.set_symbol(sym::Bool, bool_ptr);
+    vm.scope.set_symbol(sym::Num, num_ptr);
+    vm.scope.set_symbol(sym::Str, str_ptr);
 }
 
 fn op_begin_scope(vm: &mut Vm) -> Result<(), RuntimeError> {
@@ -273,7 +447,7 @@ fn op_begin_scope(vm: &mut Vm) -> Result<(), RuntimeError> {
     // REVIEW: Maybe we should created a method/function for that.
     vm.scope = Rc::new(Scope::new(Rc::clone(&vm.scope)));
     for (key, object) in &slots {
-        vm.scope.set_symbol(key, object.clone());
+        vm.scope.set_symbol(*key, *object);
     }
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
@@ -281,7 +455,7 @@ fn op_begin_scope(vm: &mut Vm) -> Result<(), RuntimeError> {
 
 fn op_end_scope(vm: &mut Vm) -> Result<(), RuntimeError> {
     if let Some(parent) = &vm.scope.parent {
-        vm.scope = Rc::clone(&parent);
+        vm.scope = Rc::clone(parent);
     }
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
@@ -309,6 +483,9 @@ fn op_convert_constant(vm: &mut Vm) -> Result<(), RuntimeError> {
         Constant::Number(value) => {
             vm.create_object(Object::Primitive(Primitive::new(2, value)));
         }
+        Constant::Str(value) => {
+            vm.create_object(Object::Str(value));
+        }
         _ => panic!("Cannot convert to a value"),
     }
     vm.frame_mut().instructions_ptr += 1;
@@ -317,17 +494,17 @@ fn op_convert_constant(vm: &mut Vm) -> Result<(), RuntimeError> {
 
 fn op_declare_var(vm: &mut Vm) -> Result<(), RuntimeError> {
     let (object_id, _) = vm.pop_object();
-    let variable_name = vm.pop_constant().as_string();
-    vm.scope().set_symbol(&variable_name, object_id);
+    let variable_name = vm.pop_constant().as_symbol();
+    vm.scope().set_symbol(variable_name, object_id);
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
 }
 
 fn op_declare_class(vm: &mut Vm) -> Result<(), RuntimeError> {
-    let class_name = vm.pop_constant().as_string();
-    let class = Object::Class(Class::new(&class_name));
+    let class_name = vm.pop_constant().as_symbol();
+    let class = Object::Class(Class::new(class_name));
     let class_id = vm.memory.alloc(class);
-    vm.scope.set_symbol(&class_name, class_id);
+    vm.scope.set_symbol(class_name, class_id);
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
 }
@@ -337,7 +514,7 @@ fn op_instantiate(vm: &mut Vm) -> Result<(), RuntimeError> {
     let mut fields = HashMap::new();
     let mut fields_count = fields_count as i16;
     while fields_count > 0 {
-        let field_name = vm.pop_constant().as_string();
+        let field_name = vm.pop_constant().as_symbol();
         let (field_value_id, _) = vm.pop_object();
         fields.insert(field_name, field_value_id);
         fields_count -= 1;
@@ -367,36 +544,20 @@ fn op_instantiate(vm: &mut Vm) -> Result<(), RuntimeError> {
 
 fn op_println(vm: &mut Vm) -> Result<(), RuntimeError> {
     let (object_id, _) = vm.pop_object();
-    match vm.get_object(object_id) {
-        Object::Class(class) => {
-            println!("<class {}>", class.name());
-        }
-        Object::Primitive(primitive) => {
-            println!("{:?}", primitive.value());
-        }
-        Object::Function(_) => {
-            println!("<function>");
-        }
-        Object::Instance(instance) => {
-            let class = vm.get_object(instance.class_id());
-            if let Object::Class(class) = class {
-                println!("<instance {}>", class.name());
-            }
-        }
-    }
+    println!("{}", vm.display(object_id));
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
 }
 
 fn op_get_symbol(vm: &mut Vm) -> Result<(), RuntimeError> {
-    let symbol_name = vm.pop_constant().as_string();
-    if let Some(object) = vm.scope.symbol(&symbol_name) {
+    let symbol_name = vm.pop_constant().as_symbol();
+    if let Some(object) = vm.scope.symbol(symbol_name) {
         vm.push_object(object);
     } else {
         let index = vm.frame().instructions_ptr;
         let span = vm.chunk().span(index);
         return Err(RuntimeError::SymbolNotFound {
-            symbol_name,
+            symbol_name: symbol_name.to_string(),
             span: span.clone(),
             stack_trace: vm.stack_trace.clone(),
         });
@@ -407,8 +568,8 @@ fn op_get_symbol(vm: &mut Vm) -> Result<(), RuntimeError> {
 
 fn op_set_var(vm: &mut Vm) -> Result<(), RuntimeError> {
     let (object_id, _) = vm.pop_object();
-    let var_name = vm.pop_constant().as_string();
-    vm.scope.assign_symbol(&var_name, object_id);
+    let var_name = vm.pop_constant().as_symbol();
+    vm.scope.assign_symbol(var_name, object_id);
     vm.push_object(object_id);
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
@@ -416,10 +577,12 @@ fn op_set_var(vm: &mut Vm) -> Result<(), RuntimeError> {
 
 fn op_return(vm: &mut Vm) -> Result<(), RuntimeError> {
     if let Some(return_scope) = vm.frame().return_scope() {
-        vm.scope = Rc::clone(&return_scope);
+        vm.scope = return_scope;
     }
     vm.call_stack.pop();
     vm.stack_trace.pop();
+    let depth = vm.call_stack.depth();
+    vm.handlers.retain(|handler| handler.depth <= depth);
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
 }
@@ -427,82 +590,79 @@ fn op_return(vm: &mut Vm) -> Result<(), RuntimeError> {
 fn op_set_property(vm: &mut Vm) -> Result<(), RuntimeError> {
     let (lhs_id, _) = vm.pop_object();
     let (rhs_id, _) = vm.pop_object();
-    let prop_name = vm.pop_constant().as_string();
+    let prop_name = vm.pop_constant().as_symbol();
     if let Object::Instance(instance) = vm.memory.get_mut(lhs_id) {
-        instance.set_field(&prop_name, rhs_id);
+        instance.set_field(prop_name, rhs_id);
     }
+    vm.push_object(rhs_id);
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
 }
 
 fn op_get_property(vm: &mut Vm) -> Result<(), RuntimeError> {
-    let prop_name = vm.pop_constant().as_string();
+    let prop_name = vm.pop_constant().as_symbol();
     let instance_id = vm.object_stack.pop().unwrap();
     let instance = vm.memory.get(instance_id);
-    match instance {
+    let class_id = match instance {
         Object::Instance(instance) => {
-            if let Some(prop) = instance.field(&prop_name) {
+            if let Some(prop) = instance.field(prop_name) {
                 vm.object_stack.push(prop);
-            } else if let Some(method) = vm.scope.method(instance.class_id(), &prop_name) {
-                vm.object_stack.push(instance_id);
-                vm.object_stack.push(method);
-            } else {
-                let index = vm.frame().instructions_ptr;
-                let span = vm.chunk().span(index);
-                let class = vm.memory.get(instance.class_id());
-                if let Object::Class(class) = class {
-                    return Err(RuntimeError::CannotReadProperty {
-                        property_name: span.source_text(),
-                        class_name: class.name(),
-                        span: span.clone(),
-                        stack_trace: vm.stack_trace.clone(),
-                    });
-                }
-            }
-            vm.frame_mut().instructions_ptr += 1;
-            Ok(())
-        }
-        Object::Primitive(instance) => {
-            if let Some(method) = vm.scope.method(instance.class(), &prop_name) {
-                vm.object_stack.push(instance_id);
-                vm.object_stack.push(method);
-            } else {
-                let index = vm.frame().instructions_ptr;
-                let span = vm.chunk().span(index);
-                let class = vm.memory.get(instance.class());
-                if let Object::Class(class) = class {
-                    return Err(RuntimeError::CannotReadProperty {
-                        property_name: span.source_text(),
-                        class_name: class.name(),
-                        span: span.clone(),
-                        stack_trace: vm.stack_trace.clone(),
-                    });
-                }
+                vm.frame_mut().instructions_ptr += 1;
+                return Ok(());
             }
-            vm.frame_mut().instructions_ptr += 1;
-            Ok(())
+            instance.class_id()
         }
+        Object::Primitive(instance) => instance.class(),
+        Object::Str(_) => STR_CLASS,
         Object::Class(class) => {
             let index = vm.frame().instructions_ptr;
             let span = vm.chunk().span(index);
-            Err(RuntimeError::CannotReadProperty {
+            return Err(RuntimeError::CannotReadProperty {
                 property_name: span.source_text(),
-                class_name: class.name(),
+                class_name: class.name().to_string(),
                 span: span.clone(),
                 stack_trace: vm.stack_trace.clone(),
-            })
+            });
         }
         Object::Function(_) => {
             let index = vm.frame().instructions_ptr;
             let span = vm.chunk().span(index);
-            Err(RuntimeError::CannotReadProperty {
+            return Err(RuntimeError::CannotReadProperty {
                 property_name: span.source_text(),
                 class_name: "Function".to_owned(),
                 span: span.clone(),
                 stack_trace: vm.stack_trace.clone(),
-            })
+            });
+        }
+        Object::Trait(r#trait) => {
+            let index = vm.frame().instructions_ptr;
+            let span = vm.chunk().span(index);
+            return Err(RuntimeError::CannotReadProperty {
+                property_name: span.source_text(),
+                class_name: r#trait.name().to_string(),
+                span: span.clone(),
+                stack_trace: vm.stack_trace.clone(),
+            });
+        }
+    };
+    if let Some(method) = vm.method(class_id, prop_name) {
+        vm.object_stack.push(instance_id);
+        vm.object_stack.push(method);
+    } else {
+        let index = vm.frame().instructions_ptr;
+        let span = vm.chunk().span(index);
+        let class = vm.memory.get(class_id);
+        if let Object::Class(class) = class {
+            return Err(RuntimeError::CannotReadProperty {
+                property_name: span.source_text(),
+                class_name: class.name().to_string(),
+                span: span.clone(),
+                stack_trace: vm.stack_trace.clone(),
+            });
         }
     }
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
 }
 
 fn op_declare_function(vm: &mut Vm) -> Result<(), RuntimeError> {
@@ -512,25 +672,25 @@ fn op_declare_function(vm: &mut Vm) -> Result<(), RuntimeError> {
     if let Constant::Size(params_count) = params_count {
         for _ in 0..params_count {
             let param_name = vm.constant_stack.pop().unwrap();
-            if let Constant::String(param_name) = param_name {
+            if let Constant::Symbol(param_name) = param_name {
                 params.push(param_name);
             }
         }
     }
     let start = vm.constant_stack.pop().unwrap();
     let end = vm.constant_stack.pop().unwrap();
-    if let (Constant::String(function_name), Constant::Size(start), Constant::Size(end)) =
+    if let (Constant::Symbol(function_name), Constant::Size(start), Constant::Size(end)) =
         (function_name, start, end)
     {
         let object = Object::Function(Function::new(
-            &function_name,
+            function_name,
             &params,
             None,
             InnerFunction::frame(Rc::clone(&vm.scope), start..end),
         ));
         let object_id = vm.memory.alloc(object);
         vm.object_stack.push(object_id);
-        vm.scope.set_symbol(&function_name, object_id);
+        vm.scope.set_symbol(function_name, object_id);
         vm.frame_mut().instructions_ptr = end;
     }
     Ok(())
@@ -543,7 +703,7 @@ fn op_declare_method(vm: &mut Vm) -> Result<(), RuntimeError> {
     if let Constant::Size(params_count) = params_count {
         for _ in 0..params_count {
             let param_name = vm.constant_stack.pop().unwrap();
-            if let Constant::String(param_name) = param_name {
+            if let Constant::Symbol(param_name) = param_name {
                 params.push(param_name);
             }
         }
@@ -552,15 +712,15 @@ fn op_declare_method(vm: &mut Vm) -> Result<(), RuntimeError> {
     let end = vm.constant_stack.pop().unwrap();
     let class_name = vm.constant_stack.pop().unwrap();
     if let (
-        Constant::String(class_name),
-        Constant::String(method_name),
+        Constant::Symbol(class_name),
+        Constant::Symbol(method_name),
         Constant::Size(start),
         Constant::Size(end),
     ) = (class_name, method_name, start, end)
     {
-        if let Some(class) = vm.scope.symbol(&class_name) {
+        if let Some(class) = vm.scope.symbol(class_name) {
             let method_id = vm.memory.alloc(Object::Function(Function::new(
-                &method_name,
+                method_name,
                 &params,
                 Some(class),
                 InnerFunction::Frame {
@@ -568,7 +728,8 @@ fn op_declare_method(vm: &mut Vm) -> Result<(), RuntimeError> {
                     range: start..end,
                 },
             )));
-            vm.scope.set_method(class, &method_name, method_id);
+            vm.scope.set_method(class, method_name, method_id);
+            vm.inline_cache.invalidate();
             vm.frame_mut().instructions_ptr = end;
         } else {
             let index = vm.frame().instructions_ptr;
@@ -585,6 +746,216 @@ fn op_declare_method(vm: &mut Vm) -> Result<(), RuntimeError> {
     Ok(())
 }
 
+fn op_declare_static_method(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let method_name = vm.pop_constant().as_symbol();
+    let params_count = vm.pop_constant().as_size();
+    let params = (0..params_count)
+        .map(|_| vm.pop_constant().as_symbol())
+        .collect::<Vec<_>>();
+    let start = vm.pop_constant().as_size();
+    let end = vm.pop_constant().as_size();
+    let class_name = vm.pop_constant().as_symbol();
+    let Some(class) = vm.scope.symbol(class_name) else {
+        let index = vm.frame().instructions_ptr;
+        let span = vm.chunk().span(index);
+        return Err(RuntimeError::SymbolNotFound {
+            symbol_name: span.source_text(),
+            span: span.clone(),
+            stack_trace: vm.stack_trace.clone(),
+        });
+    };
+    let method_id = vm.memory.alloc(Object::Function(Function::new_static(
+        method_name,
+        &params,
+        class,
+        InnerFunction::frame(Rc::clone(&vm.scope), start..end),
+    )));
+    vm.scope.set_static(class, method_name, method_id);
+    vm.frame_mut().instructions_ptr = end;
+    Ok(())
+}
+
+fn op_declare_extern_function(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let function_name = vm.pop_constant().as_symbol();
+    let params = (0..vm.pop_constant().as_size())
+        .map(|_| vm.pop_constant().as_symbol())
+        .collect::<Vec<_>>();
+    let inner = native(vm, function_name.as_str());
+    let function_id = vm.memory.alloc(Object::Function(Function::new(
+        function_name,
+        &params,
+        None,
+        inner,
+    )));
+    vm.scope.set_symbol(function_name, function_id);
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
+}
+
+fn op_declare_extern_method(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let method_name = vm.pop_constant().as_symbol();
+    let params = (0..vm.pop_constant().as_size())
+        .map(|_| vm.pop_constant().as_symbol())
+        .collect::<Vec<_>>();
+    let Constant::Bool(is_static) = vm.pop_constant() else {
+        panic!("Cannot convert constants");
+    };
+    let class_name = vm.pop_constant().as_symbol();
+    let Some(class) = vm.scope.symbol(class_name) else {
+        return Err(RuntimeError::SymbolNotFound {
+            symbol_name: class_name.to_string(),
+            span: vm.span().clone(),
+            stack_trace: vm.stack_trace.clone(),
+        });
+    };
+    let name = format!("{}.{}", class_name, method_name);
+    let inner = native(vm, &name);
+    if is_static {
+        let method_id = vm.memory.alloc(Object::Function(Function::new_static(
+            method_name,
+            &params,
+            class,
+            inner,
+        )));
+        vm.scope.set_static(class, method_name, method_id);
+    } else {
+        let method_id = vm.memory.alloc(Object::Function(Function::new(
+            method_name,
+            &params,
+            Some(class),
+            inner,
+        )));
+        vm.scope.set_method(class, method_name, method_id);
+        vm.inline_cache.invalidate();
+    }
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
+}
+
+/// Binds an extern declaration to its native, which the emitter already
+/// checked exists and takes as many parameters.
+fn native(vm: &Vm, name: &str) -> InnerFunction {
+    let native = vm.natives.get(name).expect("extern without a native");
+    InnerFunction::Native {
+        fun: Rc::clone(native.fun()),
+    }
+}
+
+fn op_get_static(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let method_name = vm.pop_constant().as_symbol();
+    let (class_id, class) = vm.pop_object();
+    let class_name = match class {
+        Object::Class(class) => class.name().to_string(),
+        Object::Trait(r#trait) => r#trait.name().to_string(),
+        Object::Function(_) => "Function".to_owned(),
+        Object::Instance(_) | Object::Primitive(_) | Object::Str(_) => "instance".to_owned(),
+    };
+    if let Some(method) = vm.scope.static_method(class_id, method_name) {
+        vm.object_stack.push(method);
+        vm.frame_mut().instructions_ptr += 1;
+        Ok(())
+    } else {
+        let index = vm.frame().instructions_ptr;
+        let span = vm.chunk().span(index);
+        Err(RuntimeError::CannotReadProperty {
+            property_name: span.source_text(),
+            class_name,
+            span: span.clone(),
+            stack_trace: vm.stack_trace.clone(),
+        })
+    }
+}
+
+fn op_declare_trait(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let trait_name = vm.pop_constant().as_symbol();
+    let trait_id = vm.memory.alloc(Object::Trait(Trait::new(trait_name)));
+    vm.scope.set_symbol(trait_name, trait_id);
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
+}
+
+fn op_declare_trait_method(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let method_name = vm.pop_constant().as_symbol();
+    let params_count = vm.pop_constant().as_size();
+    let params = (0..params_count)
+        .map(|_| vm.pop_constant().as_symbol())
+        .collect::<Vec<_>>();
+    let start = vm.pop_constant().as_size();
+    let end = vm.pop_constant().as_size();
+    let trait_name = vm.pop_constant().as_symbol();
+    let method_id = vm.memory.alloc(Object::Function(Function::new(
+        method_name,
+        &params,
+        None,
+        InnerFunction::frame(Rc::clone(&vm.scope), start..end),
+    )));
+    if let Some(Object::Trait(r#trait)) = vm
+        .scope
+        .symbol(trait_name)
+        .map(|trait_id| vm.memory.get_mut(trait_id))
+    {
+        r#trait.set_method(method_name, method_id);
+    }
+    vm.frame_mut().instructions_ptr = end;
+    Ok(())
+}
+
+/// Copies the default methods of a trait that an impl block doesn't
+/// override to the implementing class, bound to it like the methods
+/// declared by the impl block itself.
+fn op_implement_trait(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let methods_count = vm.pop_constant().as_size();
+    let overridden = (0..methods_count)
+        .map(|_| vm.pop_constant().as_symbol())
+        .collect::<Vec<_>>();
+    let trait_name = vm.pop_constant().as_symbol();
+    let class_name = vm.pop_constant().as_symbol();
+    let index = vm.frame().instructions_ptr;
+    let span = vm.chunk().span(index).clone();
+    let (Some(class), Some(trait_id)) = (vm.scope.symbol(class_name), vm.scope.symbol(trait_name))
+    else {
+        return Err(RuntimeError::SymbolNotFound {
+            symbol_name: span.source_text(),
+            span,
+            stack_trace: vm.stack_trace.clone(),
+        });
+    };
+    let Object::Trait(r#trait) = vm.memory.get(trait_id) else {
+        return Err(RuntimeError::Custom {
+            message: format!("\"{}\" is not a trait", trait_name),
+            span,
+            stack_trace: vm.stack_trace.clone(),
+        });
+    };
+    let defaults = r#trait
+        .methods()
+        .iter()
+        .filter(|(name, _)| !overridden.contains(name))
+        .filter_map(|(name, method)| match vm.memory.get(*method) {
+            Object::Function(function) => match function.inner() {
+                InnerFunction::Frame { scope, range } => Some((
+                    *name,
+                    Function::new(
+                        *name,
+                        function.params(),
+                        Some(class),
+                        InnerFunction::frame(Rc::clone(scope), range.clone()),
+                    ),
+                )),
+                InnerFunction::Native { .. } => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    for (name, method) in defaults {
+        let method_id = vm.memory.alloc(Object::Function(method));
+        vm.scope.set_method(class, name, method_id);
+    }
+    vm.inline_cache.invalidate();
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
+}
+
 fn op_call_function(vm: &mut Vm) -> Result<(), RuntimeError> {
     let mut args = vec![];
     let args_count = vm.constant_stack.pop().unwrap();
@@ -601,53 +972,41 @@ fn op_call_function(vm: &mut Vm) -> Result<(), RuntimeError> {
 }
 
 fn op_add(vm: &mut Vm) -> Result<(), RuntimeError> {
-    let operand2 = vm.object_stack.pop().unwrap();
-    let operand1 = *vm.object_stack.last().unwrap();
-    let object1 = vm.memory.get(operand1);
-    if let Some(class_id) = object1.class_id() {
-        if let Some(method) = vm.scope.method(class_id, "add") {
-            call_function(vm, &[operand2], method)
-        } else {
-            let index = vm.frame().instructions_ptr;
-            let span = vm.chunk().span(index);
-            Err(RuntimeError::Custom {
-                message: "trait \"Add\" not implemented".to_owned(),
-                span: span.clone(),
-                stack_trace: vm.stack_trace.clone(),
-            })
-        }
-    } else {
-        let index = vm.frame().instructions_ptr;
-        let span = vm.chunk().span(index);
-        Err(RuntimeError::Custom {
-            message: "trait \"Add\" not implemented".to_owned(),
-            span: span.clone(),
-            stack_trace: vm.stack_trace.clone(),
-        })
-    }
+    operator(vm, sym::add, "Add")
 }
 
 fn op_sub(vm: &mut Vm) -> Result<(), RuntimeError> {
+    operator(vm, sym::sub, "Sub")
+}
+
+fn op_mul(vm: &mut Vm) -> Result<(), RuntimeError> {
+    operator(vm, sym::mul, "Mul")
+}
+
+fn op_div(vm: &mut Vm) -> Result<(), RuntimeError> {
+    operator(vm, sym::div, "Div")
+}
+
+fn op_rem(vm: &mut Vm) -> Result<(), RuntimeError> {
+    operator(vm, sym::rem, "Rem")
+}
+
+/// Calls the method of the left operand that implements a binary operator,
+/// passing the right operand as its argument.
+fn operator(vm: &mut Vm, method: Symbol, trait_name: &str) -> Result<(), RuntimeError> {
     let operand2 = vm.object_stack.pop().unwrap();
     let operand1 = *vm.object_stack.last().unwrap();
     let object1 = vm.memory.get(operand1);
-    if let Some(class_id) = object1.class_id() {
-        if let Some(method) = vm.scope.method(class_id, "sub") {
-            call_function(vm, &[operand2], method)
-        } else {
-            let index = vm.frame().instructions_ptr;
-            let span = vm.chunk().span(index);
-            Err(RuntimeError::Custom {
-                message: "trait \"Sub\" not implemented".to_owned(),
-                span: span.clone(),
-                stack_trace: vm.stack_trace.clone(),
-            })
-        }
+    if let Some(method) = object1
+        .class_id()
+        .and_then(|class_id| vm.method(class_id, method))
+    {
+        call_function(vm, &[operand2], method)
     } else {
         let index = vm.frame().instructions_ptr;
         let span = vm.chunk().span(index);
         Err(RuntimeError::Custom {
-            message: "trait \"Sub\" not implemented".to_owned(),
+            message: format!("trait \"{}\" not implemented", trait_name),
             span: span.clone(),
             stack_trace: vm.stack_trace.clone(),
         })
@@ -657,9 +1016,19 @@ fn op_sub(vm: &mut Vm) -> Result<(), RuntimeError> {
 fn op_eq(vm: &mut Vm) -> Result<(), RuntimeError> {
     let operand2 = vm.object_stack.pop().unwrap();
     let operand1 = *vm.object_stack.last().unwrap();
+    if is_nil(vm, operand1) || is_nil(vm, operand2) {
+        let equal = is_nil(vm, operand1) && is_nil(vm, operand2);
+        vm.object_stack.pop();
+        vm.create_object(Object::Primitive(Primitive::new(
+            1,
+            if equal { 1.0 } else { 0.0 },
+        )));
+        vm.frame_mut().instructions_ptr += 1;
+        return Ok(());
+    }
     let object1 = vm.memory.get(operand1);
     if let Some(class_id) = object1.class_id() {
-        if let Some(method) = vm.scope.method(class_id, "eq") {
+        if let Some(method) = vm.method(class_id, sym::eq) {
             call_function(vm, &[operand2], method)
         } else {
             let index = vm.frame().instructions_ptr;
@@ -685,7 +1054,7 @@ fn op_not(vm: &mut Vm) -> Result<(), RuntimeError> {
     let object_id = *vm.object_stack.last().unwrap();
     let object = vm.memory.get(object_id);
     if let Some(class_id) = object.class_id() {
-        if let Some(method) = vm.scope.method(class_id, "not") {
+        if let Some(method) = vm.method(class_id, sym::not) {
             call_function(vm, &[], method)
         } else {
             let index = vm.frame().instructions_ptr;
@@ -708,34 +1077,295 @@ fn op_not(vm: &mut Vm) -> Result<(), RuntimeError> {
 }
 
 fn op_jump_if_false(vm: &mut Vm) -> Result<(), RuntimeError> {
-    let offset = vm.constant_stack.pop().unwrap();
+    let offset = vm.pop_constant().as_size();
     let object_id = vm.object_stack.pop().unwrap();
-    if let Object::Primitive(primitive) = vm.memory.get(object_id) {
-        if primitive.value() == 0.0 {
-            if let Constant::Size(offset) = offset {
-                vm.frame_mut().instructions_ptr += offset + 1;
+    let value = bool_value(vm, object_id)?;
+    jump_if(vm, !value, offset);
+    Ok(())
+}
+
+/// Skips the right operand of `&&` when the left one is false, which is
+/// kept on the stack as its result.
+fn op_jump_if_false_or_pop(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let offset = vm.pop_constant().as_size();
+    let object_id = *vm.object_stack.last().unwrap();
+    let value = bool_value(vm, object_id)?;
+    if value {
+        vm.object_stack.pop();
+    }
+    jump_if(vm, !value, offset);
+    Ok(())
+}
+
+/// Skips the right operand of `||` when the left one is true, which is
+/// kept on the stack as its result.
+fn op_jump_if_true_or_pop(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let offset = vm.pop_constant().as_size();
+    let object_id = *vm.object_stack.last().unwrap();
+    let value = bool_value(vm, object_id)?;
+    if !value {
+        vm.object_stack.pop();
+    }
+    jump_if(vm, value, offset);
+    Ok(())
+}
+
+/// Converts the condition on top of the stack to a `Bool` through the
+/// `toBool` method of its class. Booleans are left as they are.
+fn op_to_bool(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let object_id = *vm.object_stack.last().unwrap();
+    let object = vm.get_object(object_id);
+    if matches!(object, Object::Primitive(primitive) if primitive.class() == 1) {
+        vm.frame_mut().instructions_ptr += 1;
+        return Ok(());
+    }
+    let class_id = object.class_id();
+    match class_id.and_then(|class_id| vm.method(class_id, sym::toBool)) {
+        Some(method) => call_function(vm, &[], method),
+        None => {
+            let class_name = match class_id.map(|class_id| vm.get_object(class_id)) {
+                Some(Object::Class(class)) => class.name().to_string(),
+                _ => "unknown".to_owned(),
+            };
+            let index = vm.frame().instructions_ptr;
+            let span = vm.chunk().span(index);
+            Err(RuntimeError::Custom {
+                message: format!(
+                    "cannot use \"{}\" as a condition, trait \"ToBool\" is not implemented",
+                    class_name
+                ),
+                span: span.clone(),
+                stack_trace: vm.stack_trace.clone(),
+            })
+        }
+    }
+}
+
+/// Reads a condition already converted by [`op_to_bool`].
+fn bool_value(vm: &Vm, object_id: usize) -> Result<bool, RuntimeError> {
+    match vm.get_object(object_id) {
+        Object::Primitive(primitive) if primitive.class() == 1 => Ok(primitive.value() != 0.0),
+        _ => {
+            let index = vm.frame().instructions_ptr;
+            let span = vm.chunk().span(index);
+            Err(RuntimeError::Custom {
+                message: "condition must be a \"Bool\"".to_owned(),
+                span: span.clone(),
+                stack_trace: vm.stack_trace.clone(),
+            })
+        }
+    }
+}
+
+/// Skips the rest of a `?.` access when the object is nil, which is kept on
+/// the stack as its result.
+fn op_jump_if_nil(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let offset = vm.pop_constant().as_size();
+    let object_id = *vm.object_stack.last().unwrap();
+    let nil = is_nil(vm, object_id);
+    jump_if(vm, nil, offset);
+    Ok(())
+}
+
+/// Skips the right operand of `??` when the left one isn't nil, which is
+/// kept on the stack as its result.
+fn op_jump_if_not_nil(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let offset = vm.pop_constant().as_size();
+    let object_id = *vm.object_stack.last().unwrap();
+    let nil = is_nil(vm, object_id);
+    jump_if(vm, !nil, offset);
+    Ok(())
+}
+
+fn op_unwrap(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let object_id = *vm.object_stack.last().unwrap();
+    if is_nil(vm, object_id) {
+        let index = vm.frame().instructions_ptr;
+        let span = vm.chunk().span(index);
+        return Err(RuntimeError::UnwrapNil {
+            span: span.clone(),
+            stack_trace: vm.stack_trace.clone(),
+        });
+    }
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
+}
+
+/// Replaces the `Result` or `Option` on the stack with its value, skipping
+/// the early return emitted after `?`, or keeps it on the stack for that
+/// return when it holds an error or no value.
+fn op_propagate(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let offset = vm.pop_constant().as_size();
+    let object_id = *vm.object_stack.last().unwrap();
+    let outcome = match vm.get_object(object_id) {
+        Object::Instance(instance) => {
+            let class_name = match vm.get_object(instance.class_id()) {
+                Object::Class(class) => Some(class.name()),
+                _ => None,
+            };
+            // The tag tells whether there is a value, which may be nil.
+            let tag = match class_name {
+                Some(name) if name == sym::Result => instance.field(sym::ok),
+                Some(name) if name == sym::Option => instance.field(sym::some),
+                _ => None,
+            };
+            match (tag, instance.field(sym::value)) {
+                (Some(tag), Some(value)) => Some((value, !bool_value(vm, tag)?)),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    match outcome {
+        Some((_, true)) => {
+            jump_if(vm, false, offset);
+            Ok(())
+        }
+        Some((value, false)) => {
+            vm.pop_object();
+            vm.push_object(value);
+            jump_if(vm, true, offset);
+            Ok(())
+        }
+        None => Err(RuntimeError::Custom {
+            message: "the \"?\" operator can only be applied to a \"Result\" or an \"Option\""
+                .to_owned(),
+            span: vm.span().clone(),
+            stack_trace: vm.stack_trace.clone(),
+        }),
+    }
+}
+
+fn op_jump(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let offset = vm.pop_constant().as_size();
+    jump_if(vm, true, offset);
+    Ok(())
+}
+
+/// Enters a `try` block, whose errors are caught by the instructions at the
+/// popped offset.
+fn op_push_handler(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let offset = vm.pop_constant().as_size();
+    let handler = Handler {
+        catch_ptr: vm.frame().instructions_ptr + offset + 1,
+        depth: vm.call_stack.depth(),
+        scope: Rc::clone(&vm.scope),
+        object_stack_len: vm.object_stack.len(),
+        constant_stack_len: vm.constant_stack.len(),
+    };
+    vm.handlers.push(handler);
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
+}
+
+fn op_pop_handler(vm: &mut Vm) -> Result<(), RuntimeError> {
+    vm.handlers.pop();
+    vm.frame_mut().instructions_ptr += 1;
+    Ok(())
+}
+
+fn op_throw(vm: &mut Vm) -> Result<(), RuntimeError> {
+    let (object_id, _) = vm.pop_object();
+    if let Object::Instance(instance) = vm.get_object(object_id) {
+        let untraced = instance
+            .field(sym::trace)
+            .is_some_and(|trace| is_nil(vm, trace));
+        if untraced {
+            let trace = render(vm.span(), &vm.stack_trace);
+            let trace_id = vm.memory.alloc(Object::Str(trace));
+            if let Object::Instance(instance) = vm.memory.get_mut(object_id) {
+                instance.set_field(sym::trace, trace_id);
             }
-        } else {
-            vm.frame_mut().instructions_ptr += 1;
         }
     }
+    throw(vm, object_id)
+}
+
+/// Catches a runtime error raised by an instruction inside a `try` block as
+/// an `Error`, whose trace is the one of the runtime error. Outside of any
+/// `try` block, or without an `Error` class, the runtime error is returned.
+fn catch(vm: &mut Vm, error: RuntimeError) -> Result<(), RuntimeError> {
+    let Some(class_id) = vm.scope.symbol(sym::Error) else {
+        return Err(error);
+    };
+    if vm.handlers.is_empty() {
+        return Err(error);
+    }
+    let message = vm.memory.alloc(Object::Str(error.message()));
+    let trace = render(&error.span(), error.stack_trace());
+    let trace = vm.memory.alloc(Object::Str(trace));
+    let fields = HashMap::from([(sym::message, message), (sym::trace, trace)]);
+    let object_id = vm
+        .memory
+        .alloc(Object::Instance(Instance::new(class_id, fields)));
+    throw(vm, object_id)
+}
+
+/// Unwinds the stacks to the innermost `try` block and jumps to its `catch`
+/// or `finally` block, with the thrown object on the stack.
+fn throw(vm: &mut Vm, object_id: usize) -> Result<(), RuntimeError> {
+    let Some(handler) = vm.handlers.pop() else {
+        let message = match vm.get_object(object_id) {
+            Object::Instance(instance) => instance
+                .field(sym::message)
+                .map(|message| vm.get_object(message)),
+            _ => None,
+        };
+        let message = match message {
+            Some(Object::Str(message)) => message.clone(),
+            _ => "thrown value is not an \"Error\"".to_owned(),
+        };
+        return Err(RuntimeError::Thrown {
+            message,
+            span: vm.span().clone(),
+            stack_trace: vm.stack_trace.clone(),
+        });
+    };
+    vm.call_stack.truncate(handler.depth);
+    vm.stack_trace.truncate(handler.depth);
+    vm.scope = handler.scope;
+    vm.object_stack.truncate(handler.object_stack_len);
+    vm.constant_stack.truncate(handler.constant_stack_len);
+    vm.push_object(object_id);
+    vm.frame_mut().instructions_ptr = handler.catch_ptr;
     Ok(())
 }
 
+fn jump_if(vm: &mut Vm, cond: bool, offset: usize) {
+    if cond {
+        vm.frame_mut().instructions_ptr += offset + 1;
+    } else {
+        vm.frame_mut().instructions_ptr += 1;
+    }
+}
+
+fn is_nil(vm: &Vm, object_id: usize) -> bool {
+    matches!(vm.get_object(object_id), Object::Primitive(primitive) if primitive.class() == 0)
+}
+
 fn call_function(vm: &mut Vm, args: &[usize], callee_id: usize) -> Result<(), RuntimeError> {
     if let Object::Function(function) = vm.memory.get(callee_id) {
         let mut symbols = HashMap::new();
         for (index, arg) in args[..function.params().len()].iter().enumerate() {
-            symbols.insert(function.params()[index].clone(), arg.clone());
+            symbols.insert(function.params()[index], *arg);
         }
         if let Some(class_id) = function.class() {
-            let instance = vm.object_stack.pop().unwrap();
-            symbols.insert("this".to_owned(), instance);
-            symbols.insert("This".to_owned(), class_id);
+            if !function.is_static() {
+                let instance = vm.object_stack.pop().unwrap();
+                symbols.insert(sym::this, instance);
+            }
+            symbols.insert(sym::This, class_id);
         }
         let class = function.class().map(|class_id| vm.memory.get(class_id));
         match function.inner() {
             InnerFunction::Frame { range, scope } => {
+                if vm.call_stack.depth() >= vm.max_call_depth {
+                    return Err(RuntimeError::StackOverflow {
+                        max_call_depth: vm.max_call_depth,
+                        span: vm.span().clone(),
+                        stack_trace: vm.stack_trace.clone(),
+                    });
+                }
                 let frame = CallFrame::new(Some(Rc::clone(&vm.scope)), symbols);
                 let index = vm.frame().instructions_ptr;
                 let span = vm.chunk().span(index);
@@ -743,21 +1373,28 @@ fn call_function(vm: &mut Vm, args: &[usize], callee_id: usize) -> Result<(), Ru
                     if let Object::Class(class) = class {
                         vm.stack_trace.push(Trace::new(
                             span.clone(),
-                            Some(TraceFunction::new(&function.name(), Some(&class.name()))),
+                            Some(TraceFunction::new(function.name(), Some(class.name()))),
                         ));
                     }
                 } else {
                     vm.stack_trace.push(Trace::new(
                         span.clone(),
-                        Some(TraceFunction::new(&function.name(), None)),
+                        Some(TraceFunction::new(function.name(), None)),
                     ));
                 }
                 vm.call_stack.push(frame);
-                vm.scope = Rc::clone(&scope);
+                vm.scope = Rc::clone(scope);
                 vm.frame_mut().instructions_ptr = range.start;
             }
             InnerFunction::Native { fun } => {
-                let object = (fun)(vm, symbols)?;
+                let fun = Rc::clone(fun);
+                let args = symbols
+                    .get(&sym::this)
+                    .into_iter()
+                    .chain(&args[..function.params().len()])
+                    .copied()
+                    .collect::<Vec<_>>();
+                let object = fun(vm, &args)?;
                 let object_id = vm.memory.alloc(object);
                 vm.object_stack.push(object_id);
                 vm.frame_mut().instructions_ptr += 1;
@@ -780,3 +1417,392 @@ fn op_pop(vm: &mut Vm) -> Result<(), RuntimeError> {
     vm.frame_mut().instructions_ptr += 1;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::limits::VmLimits;
+    use crate::test_utils::{global, run, run_ok, vm};
+
+    /// Counts down `n` calls deep, executing a few instructions for each.
+    const COUNTDOWN: &str = r#"
+fun countdown(n: Num) -> Num {
+    if n == 0 {
+        return 0;
+    }
+    countdown(n - 1)
+}
+"#;
+
+    #[test]
+    fn redefined_methods_are_dispatched_after_a_cache_hit() {
+        let vm = run_ok(
+            r#"
+class Box {
+    value: Num,
+}
+
+impl Box {
+    fun get() -> Num {
+        1
+    }
+}
+
+fun call(box: Box) -> Num {
+    box.get()
+}
+
+let box = Box { value: 0 };
+let first = call(box);
+let cached = call(box);
+
+impl Box {
+    fun get() -> Num {
+        2
+    }
+}
+
+let second = call(box);
+"#,
+        );
+        assert_eq!(global(&vm, "first"), "1.0");
+        assert_eq!(global(&vm, "cached"), "1.0");
+        assert_eq!(global(&vm, "second"), "2.0");
+    }
+
+    #[test]
+    fn block_scoped_methods_are_dispatched_in_their_block() {
+        let vm = run_ok(
+            r#"
+class Box {
+    value: Num,
+}
+
+impl Box {
+    fun get() -> Num {
+        1
+    }
+}
+
+let box = Box { value: 0 };
+let outer = box.get();
+let inner = 0;
+{
+    impl Box {
+        fun get() -> Num {
+            2
+        }
+    }
+    inner = box.get();
+}
+let after = box.get();
+"#,
+        );
+        assert_eq!(global(&vm, "outer"), "1.0");
+        assert_eq!(global(&vm, "inner"), "2.0");
+        assert_eq!(global(&vm, "after"), "1.0");
+    }
+
+    #[test]
+    fn assignments_evaluate_their_value_once() {
+        let vm = run_ok(
+            r#"
+class Counter {
+    count: Num,
+}
+
+let calls = 0;
+fun next() -> Num {
+    calls = calls + 1;
+    calls
+}
+
+let counter = Counter { count: 0 };
+let value = 0;
+value = next();
+counter.count = next();
+let chained = counter.count = 5;
+"#,
+        );
+        assert_eq!(global(&vm, "calls"), "2.0");
+        assert_eq!(global(&vm, "value"), "1.0");
+        assert_eq!(global(&vm, "chained"), "5.0");
+    }
+
+    #[test]
+    fn catch_runs_before_finally() {
+        let vm = run_ok(
+            r#"
+let log = "";
+try {
+    log = log + "try ";
+    throw Error { message: "thrown", trace: "" };
+    log = log + "unreachable ";
+} catch (e) {
+    log = log + "catch " + e.message + " ";
+} finally {
+    log = log + "finally ";
+}
+log = log + "after";
+"#,
+        );
+        assert_eq!(global(&vm, "log"), "try catch thrown finally after");
+    }
+
+    #[test]
+    fn finally_runs_without_an_error() {
+        let vm = run_ok(
+            r#"
+let log = "";
+try {
+    log = log + "try ";
+} catch (e) {
+    log = log + "catch ";
+} finally {
+    log = log + "finally";
+}
+"#,
+        );
+        assert_eq!(global(&vm, "log"), "try finally");
+    }
+
+    #[test]
+    fn runtime_errors_are_caught() {
+        let vm = run_ok(
+            r#"
+let message = "";
+try {
+    assert(false);
+} catch (e) {
+    message = e.message;
+}
+"#,
+        );
+        assert_eq!(global(&vm, "message"), "assertion failed");
+    }
+
+    #[test]
+    fn rethrow_runs_the_inner_finally_then_the_outer_catch() {
+        let vm = run_ok(
+            r#"
+let log = "";
+try {
+    try {
+        throw Error { message: "inner", trace: "" };
+    } catch (e) {
+        log = log + "catch ";
+        throw e;
+    } finally {
+        log = log + "finally ";
+    }
+} catch (e) {
+    log = log + "outer " + e.message;
+}
+"#,
+        );
+        assert_eq!(global(&vm, "log"), "catch finally outer inner");
+    }
+
+    #[test]
+    fn uncaught_errors_run_finally_before_stopping() {
+        let (vm, result) = run(r#"
+let log = "";
+try {
+    throw Error { message: "uncaught", trace: "" };
+} finally {
+    log = log + "finally";
+}
+log = log + " after";
+"#);
+        assert_eq!(result.unwrap_err().message(), "uncaught error: uncaught");
+        assert_eq!(global(&vm, "log"), "finally");
+    }
+
+    #[test]
+    fn return_runs_the_finally_blocks_around_it() {
+        let vm = run_ok(
+            r#"
+let log = "";
+fun f() -> Num {
+    try {
+        try {
+            return 1;
+        } finally {
+            log = log + "inner ";
+        }
+    } finally {
+        log = log + "outer";
+    }
+}
+let value = f();
+"#,
+        );
+        assert_eq!(global(&vm, "value"), "1.0");
+        assert_eq!(global(&vm, "log"), "inner outer");
+    }
+
+    #[test]
+    fn deep_recursion_overflows_the_call_stack() {
+        let mut vm = vm("fun f() {\n    f();\n}\nf();").with_max_call_depth(64);
+        let error = vm.run().unwrap_err();
+        assert_eq!(
+            error.message(),
+            "stack overflow, the maximum call depth of 64 was exceeded"
+        );
+        assert_eq!(error.stack_trace().len(), 64);
+    }
+
+    #[test]
+    fn calls_within_the_maximum_depth_succeed() {
+        let mut vm = vm(r#"
+fun down(n: Num) -> Num {
+    if n == 0 {
+        return 0;
+    }
+    down(n - 1) + 1
+}
+let depth = down(63);
+"#)
+        .with_max_call_depth(64);
+        assert!(vm.run().is_ok());
+        assert_eq!(global(&vm, "depth"), "63.0");
+    }
+
+    #[test]
+    fn step_limit() {
+        let code = format!("{}countdown(100);", COUNTDOWN);
+        let mut unlimited = vm(&code);
+        assert!(unlimited.run().is_ok());
+        let steps = unlimited.steps;
+
+        let limits = VmLimits {
+            max_steps: Some(steps),
+            ..VmLimits::default()
+        };
+        assert!(vm(&code).with_limits(limits).run().is_ok());
+
+        let limits = VmLimits {
+            max_steps: Some(steps - 1),
+            ..VmLimits::default()
+        };
+        let error = vm(&code).with_limits(limits).run().unwrap_err();
+        assert_eq!(
+            error.message(),
+            format!(
+                "the limit of {} executed instructions was exceeded",
+                steps - 1
+            )
+        );
+    }
+
+    #[test]
+    fn limits_cannot_be_caught() {
+        let mut prelude = vm("");
+        assert!(prelude.run().is_ok());
+        let max_steps = prelude.steps + 200;
+
+        let code = format!(
+            "{}let caught = false;\ntry {{\n    countdown(100);\n}} catch (e) {{\n    caught = true;\n}}",
+            COUNTDOWN
+        );
+        let limits = VmLimits {
+            max_steps: Some(max_steps),
+            ..VmLimits::default()
+        };
+        let mut vm = vm(&code).with_limits(limits);
+        let error = vm.run().unwrap_err();
+        assert_eq!(
+            error.message(),
+            format!(
+                "the limit of {} executed instructions was exceeded",
+                max_steps
+            )
+        );
+        assert_eq!(global(&vm, "caught"), "0.0");
+    }
+
+    #[test]
+    fn timeout() {
+        let code = r#"
+fun spin(n: Num) -> Num {
+    if n == 0 {
+        return 0;
+    }
+    spin(n - 1) + spin(n - 1)
+}
+spin(30);
+"#;
+        let limits = VmLimits {
+            timeout: Some(Duration::from_millis(10)),
+            ..VmLimits::default()
+        };
+        let error = vm(code).with_limits(limits).run().unwrap_err();
+        assert_eq!(
+            error.message(),
+            "the time limit of 10 milliseconds was exceeded"
+        );
+    }
+
+    #[test]
+    fn heap_limit() {
+        let mut prelude = vm("");
+        assert!(prelude.run().is_ok());
+        let max_heap = prelude.memory.len() + 20;
+
+        let code = format!("{}countdown(100);", COUNTDOWN);
+        let limits = VmLimits {
+            max_heap: Some(max_heap),
+            ..VmLimits::default()
+        };
+        let error = vm(&code).with_limits(limits).run().unwrap_err();
+        assert_eq!(
+            error.message(),
+            format!("the limit of {} objects in memory was exceeded", max_heap)
+        );
+
+        let limits = VmLimits {
+            max_heap: Some(max_heap + 10_000),
+            ..VmLimits::default()
+        };
+        assert!(vm(&code).with_limits(limits).run().is_ok());
+    }
+
+    #[test]
+    fn propagation_runs_the_finally_blocks_it_leaves() {
+        let (vm, result) = run(r#"
+let log = "";
+fun parse(text: Str) -> Result<Num, Str> {
+    if text == "" {
+        return Result::err("empty");
+    }
+    Result::ok(1)
+}
+fun twice(text: Str) -> Result<Num, Str> {
+    try {
+        try {
+            let value = parse(text)?;
+            log = log + "parsed ";
+            return Result::ok(value + value);
+        } finally {
+            log = log + "inner ";
+        }
+    } catch (e) {
+        log = log + "caught ";
+    } finally {
+        log = log + "outer ";
+    }
+    Result::ok(0)
+}
+let ok = twice("1");
+let err = twice("");
+let doubled = ok.value;
+let error = err.error;
+assert(false);
+"#);
+        assert_eq!(result.unwrap_err().message(), "assertion failed");
+        assert_eq!(global(&vm, "doubled"), "2.0");
+        assert_eq!(global(&vm, "error"), "empty");
+        assert_eq!(global(&vm, "log"), "parsed inner outer inner outer ");
+    }
+}